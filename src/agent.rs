@@ -0,0 +1,206 @@
+//! Files rendered by [Vault Agent](https://www.vaultproject.io/docs/agent)
+//!
+//! Vault Agent authenticates on behalf of an application, writes its token to sink files, and
+//! renders secrets into files with templates. This module reads those files, and watches them
+//! for changes so that applications pick up renewed tokens and rotated secrets.
+//!
+//! Agent replaces files by renaming a new file over them, so the directory containing a file is
+//! watched, rather than the file itself. Files in Kubernetes volumes are replaced by swapping the
+//! `..data` symbolic link of the volume, which is watched too.
+use crate::{Error, Secret};
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::stream::{Stream, StreamExt};
+use log::debug;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+
+/// Link swapped by Kubernetes to update the files of a volume atomically
+const KUBERNETES_DATA: &str = "..data";
+
+/// Read the token written to a token sink file
+///
+/// Only plain tokens are supported. Sinks configured for response wrapping or encryption must
+/// be unwrapped or decrypted first.
+pub fn read_token_sink<P: AsRef<Path>>(path: P) -> Result<Secret, Error> {
+    let token = fs::read_to_string(path)?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(Error::MissingToken);
+    }
+    Ok(Secret(token.to_string()))
+}
+
+/// Read a file rendered by a template as JSON
+pub fn read_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, Error> {
+    Ok(serde_json::from_slice(&fs::read(path)?)?)
+}
+
+/// Stream of the contents of a file, each time it changes
+///
+/// The current contents of the file are returned first, if it exists. Changes that leave the
+/// contents identical are skipped, as are empty contents. The file is watched until the stream
+/// is dropped.
+///
+/// The file is read by the thread of the watcher as it changes, so that polling the stream
+/// never blocks the executor.
+pub struct FileUpdates {
+    path: PathBuf,
+    receiver: mpsc::UnboundedReceiver<Result<String, Error>>,
+    last: Option<String>,
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for FileUpdates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileUpdates")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+/// Read the contents of a watched file, unless it is being replaced or has not been rendered yet.
+/// Files rewritten in place are empty once truncated, before their new contents are written.
+fn read_update(path: &Path) -> Option<Result<String, Error>> {
+    match fs::read_to_string(path) {
+        Ok(contents) if contents.is_empty() => None,
+        Ok(contents) => Some(Ok(contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => Some(Err(e.into())),
+    }
+}
+
+/// Watch a file for changes. The current contents of the file are read before returning.
+pub fn watch<P: AsRef<Path>>(path: P) -> Result<FileUpdates, Error> {
+    let path = path.as_ref().to_path_buf();
+    let name: OsString = path
+        .file_name()
+        .ok_or_else(|| Error::InvalidRequest(format!("{} is not a file", path.display())))?
+        .to_os_string();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let (sender, receiver) = mpsc::unbounded();
+    // Read the current contents first
+    if let Some(update) = read_update(&path) {
+        let _ = sender.unbounded_send(update);
+    }
+
+    let watched = path.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let update = match event {
+            Ok(event)
+                if event.paths.iter().any(|p| {
+                    p.file_name() == Some(&name) || p.file_name() == Some(KUBERNETES_DATA.as_ref())
+                }) =>
+            {
+                match read_update(&watched) {
+                    Some(update) => update,
+                    None => return,
+                }
+            }
+            Ok(_) => return,
+            Err(e) => Err(e.into()),
+        };
+        let _ = sender.unbounded_send(update);
+    })?;
+    watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+    debug!("Watching {} for changes", path.display());
+
+    Ok(FileUpdates {
+        path,
+        receiver,
+        last: None,
+        _watcher: watcher,
+    })
+}
+
+/// Watch a token sink file for new tokens
+pub fn watch_token_sink<P: AsRef<Path>>(
+    path: P,
+) -> Result<impl Stream<Item = Result<Secret, Error>>, Error> {
+    Ok(watch(path)?.map(|contents| {
+        let token = contents?.trim().to_string();
+        if token.is_empty() {
+            return Err(Error::MissingToken);
+        }
+        Ok(Secret(token))
+    }))
+}
+
+/// Watch a file rendered by a template as JSON
+pub fn watch_json<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+) -> Result<impl Stream<Item = Result<T, Error>>, Error> {
+    Ok(watch(path)?.map(|contents| Ok(serde_json::from_str(&contents?)?)))
+}
+
+impl Stream for FileUpdates {
+    type Item = Result<String, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match futures::ready!(self.receiver.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Some(Ok(contents)) if self.last.as_ref() != Some(&contents) => {
+                    self.last = Some(contents.clone());
+                    return Poll::Ready(Some(Ok(contents)));
+                }
+                Some(Ok(_)) => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(crate::tests::uuid_prefix("agent"));
+        fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn can_read_token_sink() {
+        let dir = temp_dir();
+        let sink = dir.join("token");
+        fs::write(&sink, "s.abcdef\n").unwrap();
+        assert_eq!(read_token_sink(&sink).unwrap().as_str(), "s.abcdef");
+
+        fs::write(&sink, "").unwrap();
+        assert!(read_token_sink(&sink).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn streams_file_changes() {
+        let dir = temp_dir();
+        let path = dir.join("secret.json");
+        fs::write(&path, r#"{"password": "a"}"#).unwrap();
+
+        let mut updates = watch_json::<serde_json::Value, _>(&path).unwrap().boxed();
+        let first = updates.next().await.unwrap().unwrap();
+        assert_eq!(first["password"], "a");
+
+        // Replace the file like Agent does
+        let rendered = dir.join(".secret.json.tmp");
+        fs::write(&rendered, r#"{"password": "b"}"#).unwrap();
+        fs::rename(&rendered, &path).unwrap();
+
+        let next = updates.next().await;
+        assert_eq!(next.unwrap().unwrap()["password"], "b");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,424 @@
+//! GCP Auth Method
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/gcp/index.html).
+use crate::auth::TokenParameters;
+use crate::{Authentication, Error, Keys, Response, Secret};
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for configuring the GCP Auth Method
+#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+pub struct Config {
+    /// A JSON string containing the contents of a GCP credentials file. If this value is empty,
+    /// Vault will try to use Application Default Credentials from the machine on which the
+    /// Vault server is running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<Secret>,
+    /// Must be either `unique_id` or `role_id`. The value to use for the alias name on IAM
+    /// logins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iam_alias: Option<String>,
+    /// The metadata to include on the token returned by the IAM login endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iam_metadata: Option<Vec<String>>,
+    /// Must be either `instance_id` or `role_id`. The value to use for the alias name on GCE
+    /// logins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gce_alias: Option<String>,
+    /// The metadata to include on the token returned by the GCE login endpoint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gce_metadata: Option<Vec<String>>,
+    /// Specifies overrides to service endpoints used when making API requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_endpoint: Option<HashMap<String, String>>,
+}
+
+/// Configuration of the GCP Auth Method, as returned from Vault
+#[derive(Deserialize, Debug, Eq, PartialEq, Default)]
+pub struct ConfigResponse {
+    /// Email of the service account in the configured credentials
+    #[serde(default)]
+    pub client_email: Option<String>,
+    /// Client ID of the service account in the configured credentials
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// ID of the private key in the configured credentials
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    /// Project of the configured credentials
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// The value used for the alias name on IAM logins.
+    #[serde(default)]
+    pub iam_alias: Option<String>,
+    /// The metadata included on the token returned by the IAM login endpoint.
+    #[serde(default)]
+    pub iam_metadata: Option<Vec<String>>,
+    /// The value used for the alias name on GCE logins.
+    #[serde(default)]
+    pub gce_alias: Option<String>,
+    /// The metadata included on the token returned by the GCE login endpoint.
+    #[serde(default)]
+    pub gce_metadata: Option<Vec<String>>,
+    /// Overrides to service endpoints used when making API requests.
+    #[serde(default)]
+    pub custom_endpoint: Option<HashMap<String, String>>,
+}
+
+/// Type of GCP Role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleType {
+    /// Log in with a JWT signed by a service account
+    #[default]
+    Iam,
+    /// Log in with an instance identity metadata token
+    Gce,
+}
+
+/// GCP Auth Method Role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Role {
+    /// The type of this role.
+    #[serde(alias = "role_type")]
+    pub r#type: RoleType,
+    /// An array of service account emails or IDs that login is restricted to. Required for
+    /// `iam` roles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_service_accounts: Option<Vec<String>>,
+    /// If true, any auth token generated under this token will have associated group aliases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_group_aliases: Option<bool>,
+    /// (`iam` only) The number of seconds past the time of authentication that the login
+    /// param JWT must expire within.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_jwt_exp: Option<u64>,
+    /// (`iam` only) A flag to determine if this role should allow GCE instances to authenticate
+    /// by inferring service accounts from the GCE identity metadata token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_gce_inference: Option<bool>,
+    /// (`gce` only) An array of GCP project IDs. Only entities belonging to this project can
+    /// authenticate under the role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_projects: Option<Vec<String>>,
+    /// (`gce` only) The list of zones that a GCE instance must belong to in order to be
+    /// authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_zones: Option<Vec<String>>,
+    /// (`gce` only) The list of regions that a GCE instance must belong to in order to be
+    /// authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_regions: Option<Vec<String>>,
+    /// (`gce` only) The instance groups that an authorized instance must belong to in order to
+    /// be authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bound_instance_groups: Option<Vec<String>>,
+    /// (`gce` only) A list of GCP labels formatted as `key:value` strings that must be set on
+    /// authorized GCE instances. Vault returns the labels as a map, which is converted to
+    /// `key:value` strings sorted by key.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_labels"
+    )]
+    pub bound_labels: Option<Vec<String>>,
+    /// Token parameters for tokens generated under this role
+    #[serde(flatten)]
+    pub token: TokenParameters,
+}
+
+/// Deserialize labels either as `key:value` strings, or as the map of labels returned by Vault
+fn deserialize_labels<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Labels {
+        List(Vec<String>),
+        Map(std::collections::BTreeMap<String, String>),
+    }
+
+    Ok(
+        Option::deserialize(deserializer)?.map(|labels| match labels {
+            Labels::List(labels) => labels,
+            Labels::Map(labels) => labels
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", key, value))
+                .collect(),
+        }),
+    )
+}
+
+/// Login request for the GCP Auth Method
+#[derive(Serialize, Debug, Eq, PartialEq)]
+struct Login<'a> {
+    role: &'a str,
+    jwt: &'a str,
+}
+
+/// Claims of the JWT used to log in with an `iam` role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct IamClaims {
+    /// Audience of the JWT, in the form `vault/<role>`
+    pub aud: String,
+    /// Email or unique ID of the service account signing the JWT
+    pub sub: String,
+    /// Expiry of the JWT, in seconds since the Unix Epoch
+    pub exp: u64,
+}
+
+impl IamClaims {
+    /// Build the claims for logging in to `role` as `service_account`, with the JWT expiring
+    /// after `validity`. The validity must not exceed the role's `max_jwt_exp`.
+    pub fn new(role: &str, service_account: &str, validity: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            aud: format!("vault/{}", role),
+            sub: service_account.to_string(),
+            exp: (now + validity).as_secs(),
+        }
+    }
+}
+
+/// Contents of a GCP service account key file
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct ServiceAccountKey {
+    /// Project that the service account belongs to
+    pub project_id: String,
+    /// ID of the private key
+    pub private_key_id: String,
+    /// PEM encoded private key
+    pub private_key: Secret,
+    /// Email of the service account
+    pub client_email: String,
+    /// Unique ID of the service account
+    pub client_id: String,
+}
+
+impl ServiceAccountKey {
+    /// Parse a service account key from the JSON contents of the key file
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Sign the claims locally with this key to produce the JWT for an `iam` login
+    #[cfg(feature = "gcp-service-account")]
+    pub fn sign_jwt(&self, claims: &IamClaims) -> Result<Secret, Error> {
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(self.private_key_id.clone());
+        let key = EncodingKey::from_rsa_pem(self.private_key.as_bytes())?;
+        Ok(Secret(jsonwebtoken::encode(&header, claims, &key)?))
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct SignJwtRequest {
+    payload: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SignJwtResponse {
+    signed_jwt: Secret,
+}
+
+/// Have the IAM Credentials `signJwt` API sign the claims on behalf of the service account in
+/// `claims.sub`, producing the JWT for an `iam` login.
+///
+/// `access_token` is an OAuth2 access token for an identity with the
+/// `iam.serviceAccounts.signJwt` permission on the service account, such as one obtained from
+/// the GCE or GKE metadata server.
+pub async fn sign_jwt_with_iam(
+    client: &reqwest::Client,
+    access_token: &str,
+    claims: &IamClaims,
+) -> Result<Secret, Error> {
+    let url = format!(
+        "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{}:signJwt",
+        claims.sub
+    );
+    let payload = SignJwtRequest {
+        payload: serde_json::to_string(claims)?,
+    };
+    let response: SignJwtResponse = client
+        .post(&url)
+        .bearer_auth(access_token)
+        .json(&payload)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response.signed_jwt)
+}
+
+/// Fetch the instance identity token from the GCE metadata server for a `gce` login
+///
+/// If `service_account` is `None`, the default service account of the instance is used.
+pub async fn gce_identity_jwt(
+    client: &reqwest::Client,
+    role: &str,
+    service_account: Option<&str>,
+) -> Result<Secret, Error> {
+    let url = format!(
+        "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/{}/identity",
+        service_account.unwrap_or("default")
+    );
+    let audience = format!("http://vault/{}", role);
+    let jwt = client
+        .get(&url)
+        .header("Metadata-Flavor", "Google")
+        .query(&[("audience", audience.as_str()), ("format", "full")])
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(Secret(jwt))
+}
+
+/// GCP Auth Method
+///
+/// See the [documentation](https://www.vaultproject.io/api/auth/gcp/index.html).
+#[async_trait]
+pub trait Gcp {
+    /// Configure the credentials that Vault uses to communicate with GCP
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error>;
+    /// Read the configuration of the auth method
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error>;
+    /// Create role
+    async fn create_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error>;
+    /// Update Role
+    async fn update_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error> {
+        self.create_role(path, name, role).await
+    }
+    /// Read Role
+    async fn read_role(&self, path: &str, name: &str) -> Result<Role, Error>;
+    /// List Roles
+    async fn list_roles(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete Role
+    async fn delete_role(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Log in with a signed JWT or GCE identity token
+    async fn login(&self, path: &str, role: &str, jwt: &str) -> Result<Authentication, Error>;
+}
+
+#[async_trait]
+impl<T> Gcp for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error> {
+        let path = format!("auth/{}/config", path);
+        self.post(&path, config, false).await
+    }
+
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error> {
+        let path = format!("auth/{}/config", path);
+        self.get(&path).await?.data()
+    }
+
+    async fn create_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error> {
+        let path = format!("auth/{}/role/{}", path, name);
+        self.post(&path, role, false).await
+    }
+
+    async fn read_role(&self, path: &str, name: &str) -> Result<Role, Error> {
+        let path = format!("auth/{}/role/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_roles(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("auth/{}/roles", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_role(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("auth/{}/role/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn login(&self, path: &str, role: &str, jwt: &str) -> Result<Authentication, Error> {
+        let path = format!("auth/{}/login", path);
+        self.post(&path, &Login { role, jwt }, true).await?.auth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_roles() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("gcp");
+        let mount = AuthMount::new(&client, &path, "gcp").await;
+
+        let role = Role {
+            r#type: RoleType::Iam,
+            bound_service_accounts: Some(vec!["vault@example.iam.gserviceaccount.com".to_string()]),
+            token: TokenParameters {
+                token_policies: Some(vec!["default".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let response = Gcp::create_role(&client, &mount.path, "test", &role)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Gcp::read_role(&client, &mount.path, "test").await.unwrap();
+        assert_eq!(actual.r#type, RoleType::Iam);
+        assert_eq!(actual.bound_service_accounts, role.bound_service_accounts);
+
+        let roles = Gcp::list_roles(&client, &mount.path).await.unwrap();
+        assert_eq!(vec!["test"], roles);
+
+        let response = Gcp::delete_role(&client, &mount.path, "test")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+    }
+
+    #[test]
+    fn can_parse_role_returned_by_vault() {
+        let role: Role = serde_json::from_value(serde_json::json!({
+            "role_type": "gce",
+            "bound_projects": ["project"],
+            "bound_labels": {"team": "vault", "env": "prod"},
+            "token_policies": ["default"]
+        }))
+        .unwrap();
+        assert_eq!(role.r#type, RoleType::Gce);
+        assert_eq!(
+            role.bound_labels,
+            Some(vec!["env:prod".to_string(), "team:vault".to_string()])
+        );
+
+        let role: Role = serde_json::from_value(serde_json::json!({
+            "type": "gce",
+            "bound_labels": ["team:vault"]
+        }))
+        .unwrap();
+        assert_eq!(role.bound_labels, Some(vec!["team:vault".to_string()]));
+    }
+
+    #[test]
+    fn iam_claims_have_expected_audience() {
+        let claims = IamClaims::new("test", "vault@example.com", Duration::from_secs(60));
+        assert_eq!(claims.aud, "vault/test");
+        assert_eq!(claims.sub, "vault@example.com");
+    }
+}
@@ -0,0 +1,209 @@
+//! Kubernetes Auth Method
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/kubernetes/index.html).
+use crate::{Authentication, Error};
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Path of the service account token mounted in pods by default
+pub const SERVICE_ACCOUNT_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+#[derive(Serialize, Debug)]
+struct Login<'a> {
+    role: &'a str,
+    jwt: &'a str,
+}
+
+/// Read a service account token from a file, like [`SERVICE_ACCOUNT_TOKEN_PATH`]
+pub fn read_service_account_token<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let token = std::fs::read_to_string(path)?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(Error::MissingToken);
+    }
+    Ok(token.to_string())
+}
+
+/// Kubernetes Auth Method
+///
+/// See the [documentation](https://www.vaultproject.io/api/auth/kubernetes/index.html).
+#[async_trait]
+pub trait Kubernetes {
+    /// Log in against the role with a service account token
+    async fn login(&self, path: &str, role: &str, jwt: &str) -> Result<Authentication, Error>;
+    /// Log in against the role with the service account token in a file, like
+    /// [`SERVICE_ACCOUNT_TOKEN_PATH`]
+    async fn login_with_token_file<P: AsRef<Path> + Send>(
+        &self,
+        path: &str,
+        role: &str,
+        token_file: P,
+    ) -> Result<Authentication, Error> {
+        let jwt = read_service_account_token(token_file)?;
+        self.login(path, role, &jwt).await
+    }
+}
+
+#[async_trait]
+impl<T> Kubernetes for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn login(&self, path: &str, role: &str, jwt: &str) -> Result<Authentication, Error> {
+        let path = format!("auth/{}/login", path);
+        self.post(&path, &Login { role, jwt }, true).await?.auth()
+    }
+}
+
+/// Watch the service account token in a file, and log in with it each time Kubernetes rotates
+/// it.
+///
+/// Projected service account tokens expire independently of the Vault tokens obtained with
+/// them. The first item is the result of logging in with the current token. The token of
+/// `client` is left unchanged: set the Vault tokens on the clients that need them, for example
+/// with [`Client::set_token`](crate::Client::set_token), as they are received.
+#[cfg(feature = "agent")]
+pub fn watch_logins<'a, T, P>(
+    client: &'a T,
+    path: &'a str,
+    role: &'a str,
+    token_file: P,
+) -> Result<impl futures::Stream<Item = Result<Authentication, Error>> + 'a, Error>
+where
+    T: crate::Vault + Send + Sync,
+    P: AsRef<Path>,
+{
+    use futures::StreamExt;
+
+    Ok(
+        crate::agent::watch(token_file)?.then(move |jwt| async move {
+            let jwt = jwt?;
+            let jwt = jwt.trim();
+            if jwt.is_empty() {
+                return Err(Error::MissingToken);
+            }
+            Kubernetes::login(client, path, role, jwt).await
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_account_tokens_are_trimmed() {
+        let path = std::env::temp_dir().join(crate::tests::uuid_prefix("token"));
+        std::fs::write(&path, "eyJhbGciOiJSUzI1NiJ9.e30.c2ln\n").unwrap();
+        assert_eq!(
+            read_service_account_token(&path).unwrap(),
+            "eyJhbGciOiJSUzI1NiJ9.e30.c2ln"
+        );
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(read_service_account_token(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Records the service account tokens logged in with
+    #[cfg(feature = "agent")]
+    #[derive(Default)]
+    struct LoginRecorder {
+        jwts: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[cfg(feature = "agent")]
+    #[async_trait]
+    impl crate::Vault for LoginRecorder {
+        async fn read(
+            &self,
+            _path: &str,
+            _method: crate::Method,
+        ) -> Result<crate::Response, Error> {
+            Ok(crate::Response::Empty)
+        }
+
+        async fn read_with_query<Q: Serialize + Send + Sync + ?Sized>(
+            &self,
+            _path: &str,
+            _method: crate::Method,
+            _query: &Q,
+        ) -> Result<crate::Response, Error> {
+            Ok(crate::Response::Empty)
+        }
+
+        async fn write<Q: Serialize + Send + Sync>(
+            &self,
+            _path: &str,
+            payload: &Q,
+            _method: crate::Method,
+            _response_expected: bool,
+        ) -> Result<crate::Response, Error> {
+            let jwt = serde_json::to_value(payload)?["jwt"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            let mut jwts = self.jwts.lock().expect("Lock not to be poisoned");
+            jwts.push(jwt);
+            Ok(serde_json::from_value(serde_json::json!({
+                "request_id": "",
+                "lease_id": "",
+                "renewable": false,
+                "lease_duration": 0,
+                "data": null,
+                "auth": {
+                    "client_token": format!("token-{}", jwts.len()),
+                    "accessor": "",
+                    "policies": ["default"],
+                    "lease_duration": 60,
+                    "renewable": true,
+                    "entity_id": "",
+                    "token_type": "service"
+                }
+            }))?)
+        }
+    }
+
+    #[cfg(feature = "agent")]
+    #[tokio::test]
+    async fn logs_in_again_when_the_token_is_rotated() {
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        let directory = std::env::temp_dir().join(crate::tests::uuid_prefix("serviceaccount"));
+        std::fs::create_dir(&directory).unwrap();
+        let path = directory.join("token");
+        std::fs::write(&path, "jwt-1\n").unwrap();
+
+        let client = LoginRecorder::default();
+        let logins = watch_logins(&client, "kubernetes", "role", &path).unwrap();
+        futures::pin_mut!(logins);
+        let timeout = Duration::from_secs(10);
+
+        let login = tokio::time::timeout(timeout, logins.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(login.client_token.as_ref(), "token-1");
+
+        std::fs::write(&path, "jwt-2\n").unwrap();
+        let login = tokio::time::timeout(timeout, logins.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(login.client_token.as_ref(), "token-2");
+        assert_eq!(*client.jwts.lock().unwrap(), vec!["jwt-1", "jwt-2"]);
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(matches!(
+            tokio::time::timeout(timeout, logins.next()).await.unwrap(),
+            Some(Err(Error::MissingToken))
+        ));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
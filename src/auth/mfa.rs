@@ -0,0 +1,534 @@
+//! Login MFA
+//!
+//! Login MFA methods are configured in the identity secrets engine, and enforced on logins to
+//! auth methods, entities or groups by login enforcements. When a login requires MFA, Vault
+//! returns an [`MfaRequirement`] in [`Authentication::mfa_requirement`] instead of a token.
+//! The login is completed with [`LoginMfa::validate_mfa`], with a passcode for one of the
+//! methods of each constraint, or an empty passcode for methods which send a push
+//! notification.
+//!
+//! See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/identity/mfa).
+use crate::{Authentication, Error, Keys, Response, Secret};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// MFA required to complete a login
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MfaRequirement {
+    /// ID of the login request, to validate
+    pub mfa_request_id: String,
+    /// Constraints to satisfy, keyed by the name of their login enforcement
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub mfa_constraints: HashMap<String, MfaConstraint>,
+}
+
+/// Constraint of a login enforcement, satisfied by any of its methods
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MfaConstraint {
+    /// Methods which satisfy the constraint
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub any: Vec<MfaMethodSummary>,
+}
+
+/// Method which satisfies an MFA constraint
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct MfaMethodSummary {
+    /// Type of the method
+    pub r#type: MfaMethodType,
+    /// ID of the method
+    pub id: String,
+    /// Whether a passcode must be provided, rather than approving a push notification
+    #[serde(default)]
+    pub uses_passcode: bool,
+    /// Name of the method, if any
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Type of a login MFA method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MfaMethodType {
+    /// Time-based one time passwords
+    Totp,
+    /// Duo
+    Duo,
+    /// Okta
+    Okta,
+    /// PingID
+    PingId,
+}
+
+impl MfaMethodType {
+    fn as_str(self) -> &'static str {
+        match self {
+            MfaMethodType::Totp => "totp",
+            MfaMethodType::Duo => "duo",
+            MfaMethodType::Okta => "okta",
+            MfaMethodType::PingId => "pingid",
+        }
+    }
+}
+
+/// Parameters of a TOTP method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct TotpMethod {
+    /// Unique name of the method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_name: Option<String>,
+    /// Name of the issuer shown in authenticator apps
+    pub issuer: String,
+    /// Validity of each passcode, in seconds. Defaults to 30.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<u64>,
+    /// Size of the generated keys, in bytes. Defaults to 20.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_size: Option<u64>,
+    /// Size of the QR code images, in pixels. Defaults to 200.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qr_size: Option<u64>,
+    /// Hash algorithm, like `SHA1`, `SHA256` or `SHA512`. Defaults to `SHA1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    /// Number of digits of passcodes, 6 or 8. Defaults to 6.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digits: Option<u64>,
+    /// Number of periods before and after the current one in which passcodes are accepted.
+    /// Defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skew: Option<u64>,
+    /// Number of failed validations after which the entity is locked out. Defaults to 5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_validation_attempts: Option<u64>,
+}
+
+/// Parameters of a Duo method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct DuoMethod {
+    /// Unique name of the method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_name: Option<String>,
+    /// Template of the Duo username, like `{{identity.entity.name}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_format: Option<String>,
+    /// Secret key of the Duo application. Not returned by Vault.
+    #[serde(default)]
+    pub secret_key: Secret,
+    /// Integration key of the Duo application. Not returned by Vault.
+    #[serde(default)]
+    pub integration_key: Secret,
+    /// API hostname of the Duo application
+    #[serde(default)]
+    pub api_hostname: String,
+    /// Information shown in push notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_info: Option<String>,
+    /// Whether a passcode is required instead of a push notification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_passcode: Option<bool>,
+}
+
+/// Parameters of an Okta method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct OktaMethod {
+    /// Unique name of the method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_name: Option<String>,
+    /// Template of the Okta username, like `{{identity.entity.name}}@example.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_format: Option<String>,
+    /// Name of the Okta organization
+    #[serde(default)]
+    pub org_name: String,
+    /// Okta API token. Not returned by Vault.
+    #[serde(default)]
+    pub api_token: Secret,
+    /// Base domain of the Okta API, like `okta.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Match users by their primary email rather than their login
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_email: Option<bool>,
+}
+
+/// Parameters of a PingID method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct PingIdMethod {
+    /// Unique name of the method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_name: Option<String>,
+    /// Template of the PingID username, like `{{identity.entity.name}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_format: Option<String>,
+    /// Settings file of the PingID client, base64 encoded. Not returned by Vault.
+    #[serde(default)]
+    pub settings_file_base64: Secret,
+}
+
+/// Parameters of a login MFA method
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum MfaMethodConfig {
+    /// Time-based one time passwords
+    Totp(TotpMethod),
+    /// Duo
+    Duo(DuoMethod),
+    /// Okta
+    Okta(OktaMethod),
+    /// PingID
+    PingId(PingIdMethod),
+}
+
+impl MfaMethodConfig {
+    /// Type of the method
+    pub fn method_type(&self) -> MfaMethodType {
+        match self {
+            MfaMethodConfig::Totp(_) => MfaMethodType::Totp,
+            MfaMethodConfig::Duo(_) => MfaMethodType::Duo,
+            MfaMethodConfig::Okta(_) => MfaMethodType::Okta,
+            MfaMethodConfig::PingId(_) => MfaMethodType::PingId,
+        }
+    }
+}
+
+/// Login MFA method, as returned from Vault
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct MfaMethod {
+    /// ID of the method
+    pub id: String,
+    /// Type of the method
+    pub r#type: MfaMethodType,
+    /// Name of the method, if any
+    #[serde(default)]
+    pub name: String,
+    /// ID of the namespace of the method
+    #[serde(default)]
+    pub namespace_id: String,
+    /// Settings of the method, which depend on its type
+    #[serde(flatten)]
+    pub settings: HashMap<String, Value>,
+}
+
+impl MfaMethod {
+    /// Parse the settings of the method according to its type. Secrets are not returned by
+    /// Vault, and are left empty.
+    pub fn config(&self) -> Result<MfaMethodConfig, Error> {
+        let settings = Value::Object(self.settings.clone().into_iter().collect());
+        Ok(match self.r#type {
+            MfaMethodType::Totp => MfaMethodConfig::Totp(serde_json::from_value(settings)?),
+            MfaMethodType::Duo => MfaMethodConfig::Duo(serde_json::from_value(settings)?),
+            MfaMethodType::Okta => MfaMethodConfig::Okta(serde_json::from_value(settings)?),
+            MfaMethodType::PingId => MfaMethodConfig::PingId(serde_json::from_value(settings)?),
+        })
+    }
+}
+
+/// TOTP key generated for an entity
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct TotpKey {
+    /// QR code of the key, as a base64 encoded PNG image
+    #[serde(default)]
+    pub barcode: String,
+    /// `otpauth://` URL of the key
+    #[serde(default)]
+    pub url: Secret,
+}
+
+/// Login enforcement, requiring MFA methods for logins
+///
+/// Logins matching any of the auth methods, entities or groups require one of the methods.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct LoginEnforcement {
+    /// IDs of the MFA methods, any of which satisfies the enforcement
+    pub mfa_method_ids: Vec<String>,
+    /// Accessors of the auth methods to enforce MFA on
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub auth_method_accessors: Vec<String>,
+    /// Types of the auth methods to enforce MFA on, like `userpass`
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub auth_method_types: Vec<String>,
+    /// IDs of the identity groups to enforce MFA on
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub identity_group_ids: Vec<String>,
+    /// IDs of the identity entities to enforce MFA on
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub identity_entity_ids: Vec<String>,
+}
+
+/// Login MFA
+///
+/// See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/identity/mfa).
+#[async_trait]
+pub trait LoginMfa {
+    /// Create a login MFA method, returning its ID
+    async fn create_mfa_method(&self, method: &MfaMethodConfig) -> Result<String, Error>;
+    /// Update the login MFA method. Its type cannot be changed.
+    async fn update_mfa_method(
+        &self,
+        id: &str,
+        method: &MfaMethodConfig,
+    ) -> Result<Response, Error>;
+    /// Read a login MFA method
+    async fn read_mfa_method(&self, id: &str) -> Result<MfaMethod, Error>;
+    /// List the IDs of the login MFA methods
+    async fn list_mfa_methods(&self) -> Result<Vec<String>, Error>;
+    /// Delete a login MFA method. Methods used by login enforcements cannot be deleted.
+    async fn delete_mfa_method(
+        &self,
+        method_type: MfaMethodType,
+        id: &str,
+    ) -> Result<Response, Error>;
+    /// Generate the TOTP key of the entity for the TOTP method. Fails if the entity already
+    /// has a key.
+    async fn generate_totp_key(&self, method_id: &str, entity_id: &str) -> Result<TotpKey, Error>;
+
+    /// Create or update a login enforcement
+    async fn write_login_enforcement(
+        &self,
+        name: &str,
+        enforcement: &LoginEnforcement,
+    ) -> Result<Response, Error>;
+    /// Read a login enforcement
+    async fn read_login_enforcement(&self, name: &str) -> Result<LoginEnforcement, Error>;
+    /// List the names of the login enforcements
+    async fn list_login_enforcements(&self) -> Result<Vec<String>, Error>;
+    /// Delete a login enforcement
+    async fn delete_login_enforcement(&self, name: &str) -> Result<Response, Error>;
+
+    /// Complete a login requiring MFA, with the passcodes of the methods, keyed by their ID.
+    /// Methods which send a push notification take an empty passcode.
+    async fn validate_mfa(
+        &self,
+        mfa_request_id: &str,
+        passcodes: &HashMap<String, Vec<String>>,
+    ) -> Result<Authentication, Error>;
+}
+
+#[async_trait]
+impl<T> LoginMfa for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn create_mfa_method(&self, method: &MfaMethodConfig) -> Result<String, Error> {
+        #[derive(Deserialize)]
+        struct MethodId {
+            method_id: String,
+        }
+
+        let path = format!("identity/mfa/method/{}", method.method_type().as_str());
+        let id: MethodId = self.post(&path, method, true).await?.data()?;
+        Ok(id.method_id)
+    }
+
+    async fn update_mfa_method(
+        &self,
+        id: &str,
+        method: &MfaMethodConfig,
+    ) -> Result<Response, Error> {
+        let path = format!(
+            "identity/mfa/method/{}/{}",
+            method.method_type().as_str(),
+            id
+        );
+        self.post(&path, method, false).await
+    }
+
+    async fn read_mfa_method(&self, id: &str) -> Result<MfaMethod, Error> {
+        let path = format!("identity/mfa/method/{}", id);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_mfa_methods(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/mfa/method").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_mfa_method(
+        &self,
+        method_type: MfaMethodType,
+        id: &str,
+    ) -> Result<Response, Error> {
+        let path = format!("identity/mfa/method/{}/{}", method_type.as_str(), id);
+        self.delete(&path, false).await
+    }
+
+    async fn generate_totp_key(&self, method_id: &str, entity_id: &str) -> Result<TotpKey, Error> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            method_id: &'a str,
+            entity_id: &'a str,
+        }
+
+        let request = Request {
+            method_id,
+            entity_id,
+        };
+        self.post("identity/mfa/method/totp/admin-generate", &request, true)
+            .await?
+            .data()
+    }
+
+    async fn write_login_enforcement(
+        &self,
+        name: &str,
+        enforcement: &LoginEnforcement,
+    ) -> Result<Response, Error> {
+        let path = format!("identity/mfa/login-enforcement/{}", name);
+        self.post(&path, enforcement, false).await
+    }
+
+    async fn read_login_enforcement(&self, name: &str) -> Result<LoginEnforcement, Error> {
+        let path = format!("identity/mfa/login-enforcement/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_login_enforcements(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/mfa/login-enforcement").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_login_enforcement(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("identity/mfa/login-enforcement/{}", name);
+        self.delete(&path, false).await
+    }
+
+    async fn validate_mfa(
+        &self,
+        mfa_request_id: &str,
+        passcodes: &HashMap<String, Vec<String>>,
+    ) -> Result<Authentication, Error> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            mfa_request_id: &'a str,
+            mfa_payload: &'a HashMap<String, Vec<String>>,
+        }
+
+        let request = Request {
+            mfa_request_id,
+            mfa_payload: passcodes,
+        };
+        self.post("sys/mfa/validate", &request, true).await?.auth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[test]
+    fn logins_requiring_mfa_have_no_token() {
+        let response: Response = serde_json::from_value(serde_json::json!({
+            "request_id": "1d1d4f1d-5a1e-b9a4-1f5c-6d3c2c1ea8b1",
+            "lease_id": "",
+            "renewable": false,
+            "lease_duration": 0,
+            "data": null,
+            "wrap_info": null,
+            "warnings": null,
+            "auth": {
+                "client_token": "",
+                "accessor": "",
+                "policies": null,
+                "token_policies": null,
+                "metadata": null,
+                "lease_duration": 0,
+                "renewable": false,
+                "entity_id": "",
+                "token_type": "default",
+                "orphan": false,
+                "mfa_requirement": {
+                    "mfa_request_id": "d0c9eec7-6921-8cc0-be62-202b289ef163",
+                    "mfa_constraints": {
+                        "admins": {
+                            "any": [{
+                                "type": "totp",
+                                "id": "8dcd7e4b-3c2f-4d4e-9e5b-3a6f1a1c2b3d",
+                                "uses_passcode": true,
+                                "name": ""
+                            }]
+                        }
+                    }
+                },
+                "num_uses": 0
+            }
+        }))
+        .unwrap();
+        let auth = response.auth().unwrap();
+        assert!(auth.client_token.is_empty());
+        let requirement = auth.mfa_requirement.unwrap();
+        let method = &requirement.mfa_constraints["admins"].any[0];
+        assert_eq!(method.r#type, MfaMethodType::Totp);
+        assert!(method.uses_passcode);
+    }
+
+    #[test]
+    fn method_settings_are_parsed_by_type() {
+        let method: MfaMethod = serde_json::from_value(serde_json::json!({
+            "id": "8dcd7e4b-3c2f-4d4e-9e5b-3a6f1a1c2b3d",
+            "type": "totp",
+            "name": "",
+            "namespace_id": "root",
+            "issuer": "vault",
+            "period": 30,
+            "digits": 6,
+            "algorithm": "SHA256"
+        }))
+        .unwrap();
+        match method.config().unwrap() {
+            MfaMethodConfig::Totp(totp) => {
+                assert_eq!(totp.issuer, "vault");
+                assert_eq!(totp.algorithm.as_deref(), Some("SHA256"));
+            }
+            config => panic!("Unexpected method {:?}", config),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_enforce_totp_on_logins() {
+        let client = crate::tests::vault_client();
+        let path = crate::tests::uuid_prefix("userpass");
+        let _mount = AuthMount::new(&client, &path, "userpass").await;
+
+        let method = MfaMethodConfig::Totp(TotpMethod {
+            issuer: "vault-rs".to_string(),
+            ..Default::default()
+        });
+        let id = LoginMfa::create_mfa_method(&client, &method).await.unwrap();
+        assert!(LoginMfa::list_mfa_methods(&client)
+            .await
+            .unwrap()
+            .contains(&id));
+        let read = LoginMfa::read_mfa_method(&client, &id).await.unwrap();
+        assert_eq!(read.r#type, MfaMethodType::Totp);
+        match read.config().unwrap() {
+            MfaMethodConfig::Totp(totp) => assert_eq!(totp.issuer, "vault-rs"),
+            config => panic!("Unexpected method {:?}", config),
+        }
+
+        let name = crate::tests::uuid_prefix("enforcement");
+        let enforcement = LoginEnforcement {
+            mfa_method_ids: vec![id.clone()],
+            auth_method_types: vec!["userpass".to_string()],
+            ..Default::default()
+        };
+        let _ = LoginMfa::write_login_enforcement(&client, &name, &enforcement)
+            .await
+            .unwrap();
+        let read = LoginMfa::read_login_enforcement(&client, &name)
+            .await
+            .unwrap();
+        assert_eq!(read.mfa_method_ids, [id.as_str()]);
+
+        let _ = LoginMfa::delete_login_enforcement(&client, &name)
+            .await
+            .unwrap();
+        let _ = LoginMfa::delete_mfa_method(&client, MfaMethodType::Totp, &id)
+            .await
+            .unwrap();
+    }
+}
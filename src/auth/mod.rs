@@ -0,0 +1,105 @@
+//! Implementation of the Various Vault Auth Methods
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/).
+
+pub mod gcp;
+pub mod kubernetes;
+pub mod mfa;
+pub mod okta;
+pub mod radius;
+pub mod token;
+
+#[doc(inline)]
+pub use gcp::Gcp;
+#[doc(inline)]
+pub use kubernetes::Kubernetes;
+#[doc(inline)]
+pub use mfa::LoginMfa;
+#[doc(inline)]
+pub use okta::Okta;
+#[doc(inline)]
+pub use radius::Radius;
+#[doc(inline)]
+pub use token::Token;
+
+use crate::TokenType;
+
+use serde::{Deserialize, Serialize};
+
+/// Token parameters common to the roles and configuration of most auth methods
+///
+/// See the [documentation](https://www.vaultproject.io/docs/auth/token.html) on tokens.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct TokenParameters {
+    /// The incremental lifetime for generated tokens, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_ttl: Option<u64>,
+    /// The maximum lifetime for generated tokens, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_max_ttl: Option<u64>,
+    /// List of policies to encode onto generated tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_policies: Option<Vec<String>>,
+    /// List of CIDR blocks. If set, specifies blocks of IP addresses which can authenticate
+    /// successfully, and ties the resulting token to these blocks as well.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_bound_cidrs: Option<Vec<String>>,
+    /// If set, will encode an explicit max TTL onto the token, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_explicit_max_ttl: Option<u64>,
+    /// If set, the default policy will not be set on generated tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_no_default_policy: Option<bool>,
+    /// The maximum number of times a generated token may be used. 0 means unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_num_uses: Option<u64>,
+    /// The period, in seconds, to set on the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_period: Option<u64>,
+    /// The type of token that should be generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<TokenType>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::Vault;
+
+    /// Enables an auth method for the duration of a test, and disables it on drop
+    pub(crate) struct AuthMount<T>
+    where
+        T: Vault + Send + Sync,
+    {
+        pub(crate) path: String,
+        pub(crate) client: T,
+    }
+
+    impl<T> AuthMount<T>
+    where
+        T: Vault + Send + Sync + Clone,
+    {
+        pub(crate) async fn new(client: &T, path: &str, r#type: &str) -> Self {
+            let payload = serde_json::json!({ "type": r#type });
+            let response = client
+                .post(&format!("sys/auth/{}", path), &payload, false)
+                .await
+                .unwrap();
+            assert!(response.ok().unwrap().is_none());
+            AuthMount {
+                path: path.to_string(),
+                client: client.clone(),
+            }
+        }
+    }
+
+    impl<T> Drop for AuthMount<T>
+    where
+        T: Vault + Send + Sync,
+    {
+        fn drop(&mut self) {
+            let path = format!("sys/auth/{}", self.path);
+            let response = futures::executor::block_on(self.client.delete(&path, false)).unwrap();
+            assert!(response.ok().unwrap().is_none());
+        }
+    }
+}
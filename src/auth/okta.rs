@@ -0,0 +1,249 @@
+//! Okta Auth Method
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/okta/index.html).
+use crate::auth::TokenParameters;
+use crate::{Authentication, Error, Keys, Response, Secret};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for configuring the Okta Auth Method
+#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+pub struct Config {
+    /// Name of the organization to be used in the Okta API.
+    pub org_name: String,
+    /// Okta API token. This is required to query Okta for user group membership. If this is not
+    /// supplied only locally configured groups will be enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<Secret>,
+    /// If set, will be used as the base domain for API requests. Examples are `okta.com`,
+    /// `oktapreview.com`, and `okta-emea.com`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Whether to bypass an Okta MFA request. Useful if using one of Vault's built-in MFA
+    /// mechanisms, but this will also cause certain other statuses to be ignored, such as
+    /// `PASSWORD_EXPIRED`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bypass_okta_mfa: Option<bool>,
+    /// Token parameters for tokens generated by logging in
+    #[serde(flatten)]
+    pub token: TokenParameters,
+}
+
+/// Configuration of the Okta Auth Method, as returned from Vault
+#[derive(Deserialize, Debug, Eq, PartialEq, Default)]
+pub struct ConfigResponse {
+    /// Name of the organization used in the Okta API.
+    #[serde(default)]
+    pub org_name: String,
+    /// Base domain for API requests
+    #[serde(default)]
+    pub base_url: String,
+    /// Whether Okta MFA requests are bypassed
+    #[serde(default)]
+    pub bypass_okta_mfa: bool,
+    /// Token parameters for tokens generated by logging in
+    #[serde(flatten)]
+    pub token: TokenParameters,
+}
+
+/// Policies and groups mapped to an Okta user
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct User {
+    /// List of Okta groups to associate with the user. These are in addition to the groups
+    /// the user belongs to in Okta.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// List of policies to associate with the user.
+    #[serde(default)]
+    pub policies: Vec<String>,
+}
+
+/// Policies mapped to an Okta group
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Group {
+    /// List of policies to associate with the group.
+    #[serde(default)]
+    pub policies: Vec<String>,
+}
+
+/// Parameters for logging in with the Okta Auth Method
+#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+pub struct Login {
+    /// Password for the user
+    pub password: Secret,
+    /// Okta Verify TOTP passcode, if MFA is required
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+    /// MFA provider, like `OKTA` or `GOOGLE`, if MFA is required
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// Okta Auth Method
+///
+/// See the [documentation](https://www.vaultproject.io/api/auth/okta/index.html).
+#[async_trait]
+pub trait Okta {
+    /// Configure the connection to Okta
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error>;
+    /// Read the configuration of the auth method
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error>;
+    /// List users with mappings
+    async fn list_users(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Create or update the mappings for a user
+    async fn write_user(&self, path: &str, username: &str, user: &User) -> Result<Response, Error>;
+    /// Read the mappings for a user
+    async fn read_user(&self, path: &str, username: &str) -> Result<User, Error>;
+    /// Delete the mappings for a user
+    async fn delete_user(&self, path: &str, username: &str) -> Result<Response, Error>;
+    /// List groups with mappings
+    async fn list_groups(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Create or update the policies mapped to a group
+    async fn write_group(&self, path: &str, name: &str, group: &Group) -> Result<Response, Error>;
+    /// Read the policies mapped to a group
+    async fn read_group(&self, path: &str, name: &str) -> Result<Group, Error>;
+    /// Delete the policies mapped to a group
+    async fn delete_group(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Log in with an Okta username and password
+    async fn login(
+        &self,
+        path: &str,
+        username: &str,
+        login: &Login,
+    ) -> Result<Authentication, Error>;
+}
+
+#[async_trait]
+impl<T> Okta for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error> {
+        let path = format!("auth/{}/config", path);
+        self.post(&path, config, false).await
+    }
+
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error> {
+        let path = format!("auth/{}/config", path);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_users(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("auth/{}/users", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn write_user(&self, path: &str, username: &str, user: &User) -> Result<Response, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.post(&path, user, false).await
+    }
+
+    async fn read_user(&self, path: &str, username: &str) -> Result<User, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_user(&self, path: &str, username: &str) -> Result<Response, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.delete(&path, false).await
+    }
+
+    async fn list_groups(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("auth/{}/groups", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn write_group(&self, path: &str, name: &str, group: &Group) -> Result<Response, Error> {
+        let path = format!("auth/{}/groups/{}", path, name);
+        self.post(&path, group, false).await
+    }
+
+    async fn read_group(&self, path: &str, name: &str) -> Result<Group, Error> {
+        let path = format!("auth/{}/groups/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_group(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("auth/{}/groups/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn login(
+        &self,
+        path: &str,
+        username: &str,
+        login: &Login,
+    ) -> Result<Authentication, Error> {
+        let path = format!("auth/{}/login/{}", path, username);
+        self.post(&path, login, true).await?.auth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_configure_and_map_users_and_groups() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("okta");
+        let mount = AuthMount::new(&client, &path, "okta").await;
+
+        let config = Config {
+            org_name: "example".to_string(),
+            base_url: Some("oktapreview.com".to_string()),
+            ..Default::default()
+        };
+        let response = Okta::configure(&client, &mount.path, &config)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Okta::read_config(&client, &mount.path).await.unwrap();
+        assert_eq!(actual.org_name, "example");
+        assert_eq!(actual.base_url, "oktapreview.com");
+
+        let group = Group {
+            policies: vec!["operators".to_string()],
+        };
+        let response = Okta::write_group(&client, &mount.path, "operators", &group)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let actual = Okta::read_group(&client, &mount.path, "operators")
+            .await
+            .unwrap();
+        assert_eq!(actual, group);
+        let groups = Okta::list_groups(&client, &mount.path).await.unwrap();
+        assert_eq!(vec!["operators"], groups);
+
+        let user = User {
+            groups: vec!["operators".to_string()],
+            policies: vec!["default".to_string()],
+        };
+        let response = Okta::write_user(&client, &mount.path, "alice", &user)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let actual = Okta::read_user(&client, &mount.path, "alice")
+            .await
+            .unwrap();
+        assert_eq!(actual, user);
+        let users = Okta::list_users(&client, &mount.path).await.unwrap();
+        assert_eq!(vec!["alice"], users);
+
+        let response = Okta::delete_user(&client, &mount.path, "alice")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let response = Okta::delete_group(&client, &mount.path, "operators")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+    }
+}
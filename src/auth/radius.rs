@@ -0,0 +1,202 @@
+//! RADIUS Auth Method
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/radius/index.html).
+use crate::auth::TokenParameters;
+use crate::{Authentication, Error, Keys, Response, Secret};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for configuring the RADIUS Auth Method
+#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+pub struct Config {
+    /// The RADIUS server to connect to. Examples: `radius.myorg.com`, `127.0.0.1`
+    pub host: String,
+    /// The UDP port where the RADIUS server is listening on. Defaults is 1812.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// The RADIUS shared secret.
+    pub secret: Secret,
+    /// A comma-separated list of policies to be granted to unregistered users.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unregistered_user_policies: Option<String>,
+    /// Number of second to wait for a backend connection before timing out. Default is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dial_timeout: Option<u64>,
+    /// Number of second to wait for a backend response before timing out. Default is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<u64>,
+    /// The NAS-Port attribute of the RADIUS request. Defaults is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nas_port: Option<u64>,
+    /// The NAS-Identifier attribute of the RADIUS request. Defaults is an empty string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nas_identifier: Option<String>,
+    /// Token parameters for tokens generated by logging in
+    #[serde(flatten)]
+    pub token: TokenParameters,
+}
+
+/// Configuration of the RADIUS Auth Method, as returned from Vault
+#[derive(Deserialize, Debug, Eq, PartialEq, Default)]
+pub struct ConfigResponse {
+    /// The RADIUS server to connect to
+    #[serde(default)]
+    pub host: String,
+    /// The UDP port where the RADIUS server is listening on
+    #[serde(default)]
+    pub port: u16,
+    /// Policies granted to unregistered users
+    #[serde(default)]
+    pub unregistered_user_policies: Vec<String>,
+    /// Number of second to wait for a backend connection before timing out
+    #[serde(default)]
+    pub dial_timeout: u64,
+    /// Number of second to wait for a backend response before timing out
+    #[serde(default)]
+    pub read_timeout: u64,
+    /// The NAS-Port attribute of the RADIUS request
+    #[serde(default)]
+    pub nas_port: u64,
+    /// The NAS-Identifier attribute of the RADIUS request
+    #[serde(default)]
+    pub nas_identifier: String,
+    /// Token parameters for tokens generated by logging in
+    #[serde(flatten)]
+    pub token: TokenParameters,
+}
+
+/// Policies mapped to a RADIUS user
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct User {
+    /// List of policies associated to the user.
+    #[serde(default)]
+    pub policies: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct Login<'a> {
+    password: &'a str,
+}
+
+/// RADIUS Auth Method
+///
+/// See the [documentation](https://www.vaultproject.io/api/auth/radius/index.html).
+#[async_trait]
+pub trait Radius {
+    /// Configure the connection to the RADIUS server
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error>;
+    /// Read the configuration of the auth method
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error>;
+    /// List registered users
+    async fn list_users(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Register a user, or update the policies of a registered user
+    async fn write_user(&self, path: &str, username: &str, user: &User) -> Result<Response, Error>;
+    /// Read the policies of a registered user
+    async fn read_user(&self, path: &str, username: &str) -> Result<User, Error>;
+    /// Delete a registered user
+    async fn delete_user(&self, path: &str, username: &str) -> Result<Response, Error>;
+    /// Log in with a RADIUS username and password
+    async fn login(
+        &self,
+        path: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Authentication, Error>;
+}
+
+#[async_trait]
+impl<T> Radius for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn configure(&self, path: &str, config: &Config) -> Result<Response, Error> {
+        let path = format!("auth/{}/config", path);
+        self.post(&path, config, false).await
+    }
+
+    async fn read_config(&self, path: &str) -> Result<ConfigResponse, Error> {
+        let path = format!("auth/{}/config", path);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_users(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("auth/{}/users", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn write_user(&self, path: &str, username: &str, user: &User) -> Result<Response, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.post(&path, user, false).await
+    }
+
+    async fn read_user(&self, path: &str, username: &str) -> Result<User, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_user(&self, path: &str, username: &str) -> Result<Response, Error> {
+        let path = format!("auth/{}/users/{}", path, username);
+        self.delete(&path, false).await
+    }
+
+    async fn login(
+        &self,
+        path: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Authentication, Error> {
+        let path = format!("auth/{}/login/{}", path, username);
+        self.post(&path, &Login { password }, true).await?.auth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_configure_and_register_users() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("radius");
+        let mount = AuthMount::new(&client, &path, "radius").await;
+
+        let config = Config {
+            host: "radius.example.com".to_string(),
+            secret: Secret("shared".to_string()),
+            unregistered_user_policies: Some("default".to_string()),
+            ..Default::default()
+        };
+        let response = Radius::configure(&client, &mount.path, &config)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Radius::read_config(&client, &mount.path).await.unwrap();
+        assert_eq!(actual.host, "radius.example.com");
+        assert_eq!(actual.port, 1812);
+        assert_eq!(actual.unregistered_user_policies, vec!["default"]);
+
+        let user = User {
+            policies: vec!["operators".to_string()],
+        };
+        let response = Radius::write_user(&client, &mount.path, "alice", &user)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let actual = Radius::read_user(&client, &mount.path, "alice")
+            .await
+            .unwrap();
+        assert_eq!(actual, user);
+        let users = Radius::list_users(&client, &mount.path).await.unwrap();
+        assert_eq!(vec!["alice"], users);
+
+        let response = Radius::delete_user(&client, &mount.path, "alice")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+    }
+}
@@ -0,0 +1,647 @@
+//! Token Auth Method
+//!
+//! See the [documentation](https://www.vaultproject.io/api/auth/token/index.html).
+use crate::{Authentication, Empty, Error, Keys, Response, Secret, TokenType};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for creating a new token
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct CreateTokenRequest {
+    /// The ID of the client token. Can only be specified by a root token. The ID provided may
+    /// not contain a `.` character. Otherwise, the token ID is a randomly generated value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// A list of policies for the token. This must be a subset of the policies belonging to
+    /// the token making the request, unless root. If not specified, defaults to all the
+    /// policies of the calling token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policies: Option<Vec<String>>,
+    /// A map of string to string valued metadata. This is passed through to the audit devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, String>>,
+    /// If true and set by a root caller, the token will not have the parent token of the
+    /// caller. This creates a token with no parent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_parent: Option<bool>,
+    /// If true the `default` policy will not be contained in this token's policy set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_default_policy: Option<bool>,
+    /// Set to false to disable the ability of the token to be renewed past its initial TTL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renewable: Option<bool>,
+    /// The TTL period of the token, provided as "1h", where hour is the largest suffix. If not
+    /// provided, the token is valid for the default lease TTL, or indefinitely if the root
+    /// policy is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<String>,
+    /// The token type.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<TokenType>,
+    /// If set, the token will have an explicit max TTL set upon it. This maximum token TTL
+    /// cannot be changed later, and unlike with normal tokens, updates to the system/mount max
+    /// TTL value will have no effect at renewal time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explicit_max_ttl: Option<String>,
+    /// Name to associate with this token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The maximum uses for the given token. This can be used to create a one-time-token or
+    /// limited use token. The value of 0 has no limit to the number of uses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_uses: Option<u64>,
+    /// If specified, the token will be periodic; it will have no maximum TTL (unless an
+    /// `explicit_max_ttl` is also set) but every renewal will use the given period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    /// Name of the entity alias to associate with during token creation. Only works in
+    /// combination with a role name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_alias: Option<String>,
+}
+
+impl CreateTokenRequest {
+    /// Check that the options requested are compatible with the requested token type.
+    ///
+    /// Batch tokens cannot be renewed, cannot be periodic and cannot have an explicit max TTL.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.r#type != Some(TokenType::Batch) {
+            return Ok(());
+        }
+
+        if self.renewable == Some(true) {
+            return Err(Error::InvalidRequest(
+                "batch tokens cannot be renewable".to_string(),
+            ));
+        }
+        if self.period.is_some() {
+            return Err(Error::InvalidRequest(
+                "batch tokens cannot be periodic".to_string(),
+            ));
+        }
+        if self.explicit_max_ttl.is_some() {
+            return Err(Error::InvalidRequest(
+                "batch tokens cannot have an explicit max TTL".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Information about a token, as returned from the lookup endpoints
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct TokenInfo {
+    /// The token itself. This is empty when looking up a token by its accessor.
+    #[serde(default)]
+    pub id: Secret,
+    /// The accessor for the token
+    #[serde(default)]
+    pub accessor: String,
+    /// Time the token was created, in seconds since the Unix Epoch
+    pub creation_time: u64,
+    /// TTL of the token when it was created, in seconds
+    pub creation_ttl: u64,
+    /// Display name of the token
+    #[serde(default)]
+    pub display_name: String,
+    /// UUID of the entity associated with the token, if any
+    #[serde(default)]
+    pub entity_id: String,
+    /// Time the token expires, in RFC 3339 format. Tokens without a TTL do not expire.
+    #[serde(default)]
+    pub expire_time: Option<String>,
+    /// Explicit max TTL of the token, in seconds. 0 if none was set.
+    #[serde(default)]
+    pub explicit_max_ttl: u64,
+    /// Policies attached to the token through its identity
+    #[serde(default)]
+    pub identity_policies: Option<Vec<String>>,
+    /// Time the token was issued, in RFC 3339 format
+    #[serde(default)]
+    pub issue_time: Option<String>,
+    /// Metadata attached to the token
+    #[serde(default)]
+    pub meta: Option<HashMap<String, String>>,
+    /// Number of uses remaining for the token. 0 means unlimited.
+    #[serde(default)]
+    pub num_uses: u64,
+    /// Whether the token has no parent
+    #[serde(default)]
+    pub orphan: bool,
+    /// Path the token was created at
+    #[serde(default)]
+    pub path: String,
+    /// Policies attached to the token
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub policies: Vec<String>,
+    /// Whether the token can be renewed
+    #[serde(default)]
+    pub renewable: bool,
+    /// Remaining TTL of the token, in seconds. 0 for tokens that do not expire.
+    pub ttl: u64,
+    /// Period of the token, in seconds, if it is a periodic token
+    #[serde(default)]
+    pub period: Option<u64>,
+    /// Role the token was created against, if any
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Type of the token
+    pub r#type: TokenType,
+}
+
+/// Token Role, used to create tokens with a fixed set of parameters
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct TokenRole {
+    /// Name of the role. Only returned when reading a role.
+    #[serde(default, skip_serializing)]
+    pub name: String,
+    /// If set, tokens can be created with any subset of the policies in this list, rather
+    /// than the normal semantics of tokens being a subset of the calling token's policies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_policies: Option<Vec<String>>,
+    /// If set, successful token creation via this role will require that no policies in the
+    /// given list are requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disallowed_policies: Option<Vec<String>>,
+    /// If true, tokens created against this policy will be orphan tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orphan: Option<bool>,
+    /// Set to false to disable the ability of the token to be renewed past its initial TTL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renewable: Option<bool>,
+    /// If set, tokens created against this role will have the given suffix as part of their
+    /// path in addition to the role name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_suffix: Option<String>,
+    /// If set, allowed entity aliases for tokens created against this role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_entity_aliases: Option<Vec<String>>,
+    /// List of CIDR blocks. If set, specifies blocks of IP addresses which can use the
+    /// generated tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_bound_cidrs: Option<Vec<String>>,
+    /// If set, will encode an explicit max TTL onto the token, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_explicit_max_ttl: Option<u64>,
+    /// If set, the default policy will not be set on generated tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_no_default_policy: Option<bool>,
+    /// The maximum number of times a generated token may be used. 0 means unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_num_uses: Option<u64>,
+    /// The period, in seconds, to set on the generated tokens, making them periodic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_period: Option<u64>,
+    /// The type of token that should be generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<TokenType>,
+}
+
+#[derive(Serialize, Debug)]
+struct TokenPayload<'a> {
+    token: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct AccessorPayload<'a> {
+    accessor: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct Renew<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accessor: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    increment: Option<&'a str>,
+}
+
+/// Token Auth Method
+///
+/// Unlike other auth methods, the token auth method is always mounted at `auth/token`.
+///
+/// See the [documentation](https://www.vaultproject.io/api/auth/token/index.html).
+#[async_trait]
+pub trait Token {
+    /// Create a new token, as a child of the token making the request
+    async fn create_token(&self, request: &CreateTokenRequest) -> Result<Authentication, Error>;
+    /// Create a new batch token, as a child of the token making the request.
+    ///
+    /// Batch tokens are not renewable, and are not backed by storage. They are revoked when
+    /// their parent is revoked, or when they expire.
+    async fn create_batch_token(
+        &self,
+        request: &CreateTokenRequest,
+    ) -> Result<Authentication, Error> {
+        let request = CreateTokenRequest {
+            r#type: Some(TokenType::Batch),
+            ..request.clone()
+        };
+        self.create_token(&request).await
+    }
+    /// Create a new token without a parent
+    async fn create_orphan_token(
+        &self,
+        request: &CreateTokenRequest,
+    ) -> Result<Authentication, Error>;
+    /// Create a new token against a token role
+    async fn create_token_with_role(
+        &self,
+        role: &str,
+        request: &CreateTokenRequest,
+    ) -> Result<Authentication, Error>;
+    /// Look up information about a token
+    async fn lookup(&self, token: &str) -> Result<TokenInfo, Error>;
+    /// Look up information about a token by its accessor. The token ID is not returned.
+    async fn lookup_accessor(&self, accessor: &str) -> Result<TokenInfo, Error>;
+    /// Look up information about the token making the request
+    async fn lookup_self(&self) -> Result<TokenInfo, Error>;
+    /// Renew a token, optionally requesting an `increment` like "1h" for the new TTL.
+    ///
+    /// Vault may grant a shorter TTL than requested.
+    async fn renew(&self, token: &str, increment: Option<&str>) -> Result<Authentication, Error>;
+    /// Renew a token by its accessor, optionally requesting an `increment` for the new TTL.
+    /// The token ID is not returned.
+    async fn renew_accessor(
+        &self,
+        accessor: &str,
+        increment: Option<&str>,
+    ) -> Result<Authentication, Error>;
+    /// Renew the token making the request, optionally requesting an `increment` for the new TTL
+    async fn renew_self(&self, increment: Option<&str>) -> Result<Authentication, Error>;
+    /// Revoke a token and all its children
+    async fn revoke(&self, token: &str) -> Result<Response, Error>;
+    /// Revoke a token and all its children by the token's accessor
+    async fn revoke_accessor(&self, accessor: &str) -> Result<Response, Error>;
+    /// Revoke a token but not its children. The children become orphans.
+    async fn revoke_orphan(&self, token: &str) -> Result<Response, Error>;
+    /// Revoke the token making the request, and all its children
+    ///
+    /// If successful, the token can no longer be used
+    async fn revoke_self(&self) -> Result<Response, Error>;
+    /// Create Token Role
+    async fn create_role(&self, name: &str, role: &TokenRole) -> Result<Response, Error>;
+    /// Update Token Role
+    async fn update_role(&self, name: &str, role: &TokenRole) -> Result<Response, Error> {
+        self.create_role(name, role).await
+    }
+    /// Read Token Role
+    async fn read_role(&self, name: &str) -> Result<TokenRole, Error>;
+    /// List Token Roles
+    async fn list_roles(&self) -> Result<Vec<String>, Error>;
+    /// Delete Token Role
+    async fn delete_role(&self, name: &str) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Token for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn create_token(&self, request: &CreateTokenRequest) -> Result<Authentication, Error> {
+        request.validate()?;
+        self.post("auth/token/create", request, true).await?.auth()
+    }
+
+    async fn create_orphan_token(
+        &self,
+        request: &CreateTokenRequest,
+    ) -> Result<Authentication, Error> {
+        request.validate()?;
+        self.post("auth/token/create-orphan", request, true)
+            .await?
+            .auth()
+    }
+
+    async fn create_token_with_role(
+        &self,
+        role: &str,
+        request: &CreateTokenRequest,
+    ) -> Result<Authentication, Error> {
+        request.validate()?;
+        let path = format!("auth/token/create/{}", role);
+        self.post(&path, request, true).await?.auth()
+    }
+
+    async fn lookup(&self, token: &str) -> Result<TokenInfo, Error> {
+        self.post("auth/token/lookup", &TokenPayload { token }, true)
+            .await?
+            .data()
+    }
+
+    async fn lookup_accessor(&self, accessor: &str) -> Result<TokenInfo, Error> {
+        self.post(
+            "auth/token/lookup-accessor",
+            &AccessorPayload { accessor },
+            true,
+        )
+        .await?
+        .data()
+    }
+
+    async fn lookup_self(&self) -> Result<TokenInfo, Error> {
+        self.get("auth/token/lookup-self").await?.data()
+    }
+
+    async fn renew(&self, token: &str, increment: Option<&str>) -> Result<Authentication, Error> {
+        let payload = Renew {
+            token: Some(token),
+            accessor: None,
+            increment,
+        };
+        self.post("auth/token/renew", &payload, true).await?.auth()
+    }
+
+    async fn renew_accessor(
+        &self,
+        accessor: &str,
+        increment: Option<&str>,
+    ) -> Result<Authentication, Error> {
+        let payload = Renew {
+            token: None,
+            accessor: Some(accessor),
+            increment,
+        };
+        self.post("auth/token/renew-accessor", &payload, true)
+            .await?
+            .auth()
+    }
+
+    async fn renew_self(&self, increment: Option<&str>) -> Result<Authentication, Error> {
+        let payload = Renew {
+            token: None,
+            accessor: None,
+            increment,
+        };
+        self.post("auth/token/renew-self", &payload, true)
+            .await?
+            .auth()
+    }
+
+    async fn revoke(&self, token: &str) -> Result<Response, Error> {
+        self.post("auth/token/revoke", &TokenPayload { token }, false)
+            .await
+    }
+
+    async fn revoke_accessor(&self, accessor: &str) -> Result<Response, Error> {
+        self.post(
+            "auth/token/revoke-accessor",
+            &AccessorPayload { accessor },
+            false,
+        )
+        .await
+    }
+
+    async fn revoke_orphan(&self, token: &str) -> Result<Response, Error> {
+        self.post("auth/token/revoke-orphan", &TokenPayload { token }, false)
+            .await
+    }
+
+    async fn revoke_self(&self) -> Result<Response, Error> {
+        info!("Revoking self Vault Token");
+        // HTTP 204 is returned
+        self.post("auth/token/revoke-self", &Empty, false).await
+    }
+
+    async fn create_role(&self, name: &str, role: &TokenRole) -> Result<Response, Error> {
+        let path = format!("auth/token/roles/{}", name);
+        self.post(&path, role, false).await
+    }
+
+    async fn read_role(&self, name: &str) -> Result<TokenRole, Error> {
+        let path = format!("auth/token/roles/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_roles(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("auth/token/roles").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_role(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("auth/token/roles/{}", name);
+        self.delete(&path, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_create_tokens() {
+        let client = crate::tests::vault_client();
+
+        let request = CreateTokenRequest {
+            policies: Some(vec!["default".to_string()]),
+            ttl: Some("1h".to_string()),
+            ..Default::default()
+        };
+        let token = Token::create_token(&client, &request).await.unwrap();
+        assert_eq!(token.policies, vec!["default"]);
+        assert_eq!(token.lease_duration, 3600);
+        assert_eq!(token.token_type, TokenType::Service);
+
+        let token = Token::create_orphan_token(&client, &request).await.unwrap();
+        assert_eq!(token.policies, vec!["default"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_create_batch_tokens() {
+        let client = crate::tests::vault_client();
+
+        let request = CreateTokenRequest {
+            policies: Some(vec!["default".to_string()]),
+            ttl: Some("1h".to_string()),
+            ..Default::default()
+        };
+        let token = Token::create_batch_token(&client, &request).await.unwrap();
+        assert_eq!(token.token_type, TokenType::Batch);
+        assert!(token.is_batch());
+        assert!(!token.is_renewable());
+    }
+
+    #[test]
+    fn token_types_are_serialized_like_vault() {
+        let types = serde_json::to_value(&[
+            TokenType::Service,
+            TokenType::Batch,
+            TokenType::Default,
+            TokenType::DefaultService,
+            TokenType::DefaultBatch,
+        ])
+        .unwrap();
+        assert_eq!(
+            types,
+            serde_json::json!([
+                "service",
+                "batch",
+                "default",
+                "default-service",
+                "default-batch"
+            ])
+        );
+    }
+
+    #[test]
+    fn batch_tokens_cannot_be_renewable_or_periodic() {
+        let request = CreateTokenRequest {
+            r#type: Some(TokenType::Batch),
+            renewable: Some(true),
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+
+        let request = CreateTokenRequest {
+            r#type: Some(TokenType::Batch),
+            period: Some("1h".to_string()),
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+
+        let request = CreateTokenRequest {
+            r#type: Some(TokenType::Service),
+            renewable: Some(true),
+            period: Some("1h".to_string()),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_lookup_tokens() {
+        let client = crate::tests::vault_client();
+
+        let info = Token::lookup_self(&client).await.unwrap();
+        assert_eq!(info.policies, vec!["root"]);
+
+        let request = CreateTokenRequest {
+            policies: Some(vec!["default".to_string()]),
+            ttl: Some("1h".to_string()),
+            ..Default::default()
+        };
+        let token = Token::create_token(&client, &request).await.unwrap();
+
+        let info = Token::lookup(&client, &token.client_token).await.unwrap();
+        assert_eq!(info.accessor, token.accessor);
+        assert_eq!(info.creation_ttl, 3600);
+        assert!(info.renewable);
+
+        let info = Token::lookup_accessor(&client, &token.accessor)
+            .await
+            .unwrap();
+        assert!(info.id.is_empty());
+        assert_eq!(info.policies, vec!["default"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_renew_tokens() {
+        let client = crate::tests::vault_client();
+
+        let request = CreateTokenRequest {
+            policies: Some(vec!["default".to_string()]),
+            ttl: Some("1h".to_string()),
+            ..Default::default()
+        };
+        let token = Token::create_token(&client, &request).await.unwrap();
+
+        let renewed = Token::renew(&client, &token.client_token, Some("2h"))
+            .await
+            .unwrap();
+        assert_eq!(renewed.accessor, token.accessor);
+        assert_eq!(renewed.lease_duration, 7200);
+
+        let renewed = Token::renew_accessor(&client, &token.accessor, None)
+            .await
+            .unwrap();
+        assert_eq!(renewed.accessor, token.accessor);
+
+        let child = crate::Client::from_environment::<_, _, &str>(
+            Some(client.address()),
+            Some(&*token.client_token),
+            None,
+        )
+        .unwrap();
+        let renewed = Token::renew_self(&child, Some("30m")).await.unwrap();
+        assert_eq!(renewed.lease_duration, 1800);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_revoke_tokens() {
+        let client = crate::tests::vault_client();
+
+        let request = CreateTokenRequest {
+            policies: Some(vec!["default".to_string()]),
+            ..Default::default()
+        };
+
+        let token = Token::create_token(&client, &request).await.unwrap();
+        let response = Token::revoke(&client, &token.client_token).await.unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Token::lookup(&client, &token.client_token).await.is_err());
+
+        let token = Token::create_token(&client, &request).await.unwrap();
+        let response = Token::revoke_accessor(&client, &token.accessor)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Token::lookup(&client, &token.client_token).await.is_err());
+
+        let token = Token::create_token(&client, &request).await.unwrap();
+        let response = Token::revoke_orphan(&client, &token.client_token)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Token::lookup(&client, &token.client_token).await.is_err());
+
+        let token = Token::create_token(&client, &request).await.unwrap();
+        let child = crate::Client::from_environment::<_, _, &str>(
+            Some(client.address()),
+            Some(&*token.client_token),
+            None,
+        )
+        .unwrap();
+        let response = Token::revoke_self(&child).await.unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Token::lookup(&client, &token.client_token).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_roles() {
+        let client = crate::tests::vault_client();
+
+        let name = crate::tests::uuid_prefix("role");
+        let role = TokenRole {
+            allowed_policies: Some(vec!["default".to_string()]),
+            orphan: Some(true),
+            renewable: Some(false),
+            token_period: Some(3600),
+            ..Default::default()
+        };
+        let response = Token::create_role(&client, &name, &role).await.unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Token::read_role(&client, &name).await.unwrap();
+        assert_eq!(actual.name, name);
+        assert_eq!(actual.allowed_policies, role.allowed_policies);
+        assert_eq!(actual.orphan, Some(true));
+        assert_eq!(actual.token_period, Some(3600));
+
+        let roles = Token::list_roles(&client).await.unwrap();
+        assert!(roles.contains(&name));
+
+        let token = Token::create_token_with_role(&client, &name, &Default::default())
+            .await
+            .unwrap();
+        assert!(!token.renewable);
+        assert_eq!(token.lease_duration, 3600);
+
+        let response = Token::delete_role(&client, &name).await.unwrap();
+        assert!(response.ok().unwrap().is_none());
+    }
+}
@@ -0,0 +1,199 @@
+//! Bootstrap a new Vault for development and tests
+//!
+//! [`dev_bootstrap`] initializes and unseals a Vault server started without the `-dev` flag,
+//! waits until it is active, mounts a Key/Value Version 2 and a Transit secrets engine, and
+//! writes ACL policies, so that
+//! test suites can provision their own Vault instead of depending on a dev server set up
+//! beforehand. The unseal key shares and root token are returned, and should only ever be used
+//! with throwaway servers.
+use crate::sys::health::HealthStatus;
+use crate::sys::init::{Init, InitRequest};
+use crate::sys::reconcile::{self, Change, MountSpec};
+use crate::sys::Seal;
+use crate::{Client, Error, Secret};
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+/// Options for [`dev_bootstrap`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BootstrapOptions {
+    /// Number of shares to split the unseal key into. Defaults to 1.
+    pub secret_shares: u64,
+    /// Number of shares required to unseal. Defaults to 1.
+    pub secret_threshold: u64,
+    /// Path to mount the Key/Value Version 2 secrets engine at, if any. Defaults to `secret`.
+    pub kv_path: Option<String>,
+    /// Path to mount the Transit secrets engine at, if any. Defaults to `transit`.
+    pub transit_path: Option<String>,
+    /// ACL policies to write, keyed by their name. Defaults to none.
+    pub policies: BTreeMap<String, String>,
+    /// How long to wait for Vault to become active once unsealed. Defaults to 30 seconds.
+    pub active_timeout: Duration,
+}
+
+impl Default for BootstrapOptions {
+    fn default() -> Self {
+        Self {
+            secret_shares: 1,
+            secret_threshold: 1,
+            kv_path: Some("secret".to_string()),
+            transit_path: Some("transit".to_string()),
+            policies: BTreeMap::new(),
+            active_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Vault bootstrapped by [`dev_bootstrap`]
+#[derive(Debug, Clone)]
+pub struct Bootstrap {
+    /// Client authenticated with the root token
+    pub client: Client,
+    /// Initial root token
+    pub root_token: Secret,
+    /// Shares of the unseal key, hex encoded
+    pub unseal_keys: Vec<Secret>,
+}
+
+/// Initialize and unseal the Vault at `address`, then mount secrets engines and write policies
+/// according to the options.
+///
+/// Fails with [`Error::InvalidRequest`] if Vault is already initialized, or does not become
+/// active within [`BootstrapOptions::active_timeout`] once unsealed.
+pub async fn dev_bootstrap<S1, S2>(
+    address: S1,
+    ca_cert: Option<S2>,
+    options: &BootstrapOptions,
+) -> Result<Bootstrap, Error>
+where
+    S1: AsRef<str>,
+    S2: AsRef<str>,
+{
+    let mut client = Client::without_token(Some(address), ca_cert)?;
+    if Init::is_initialized(&client).await? {
+        return Err(Error::InvalidRequest(format!(
+            "Vault at {} is already initialized",
+            client.address()
+        )));
+    }
+
+    let request = InitRequest::shamir(options.secret_shares, options.secret_threshold);
+    let init = Init::init(&client, &request).await?;
+    let _ = Seal::unseal_with_keys(&client, &init.keys, false).await?;
+    client.set_token(&init.root_token);
+    wait_until_active(&client, options.active_timeout).await?;
+
+    let mounts = vec![
+        (options.kv_path.as_ref(), kv_v2()),
+        (options.transit_path.as_ref(), MountSpec::of_type("transit")),
+    ];
+    let changes: Vec<Change> = mounts
+        .into_iter()
+        .filter_map(|(path, spec)| {
+            path.map(|path| Change::EnableMount {
+                path: path.clone(),
+                spec,
+            })
+        })
+        .chain(
+            options
+                .policies
+                .iter()
+                .map(|(name, policy)| Change::WritePolicy {
+                    name: name.clone(),
+                    policy: policy.clone(),
+                }),
+        )
+        .collect();
+    reconcile::apply(&client, &changes).await?;
+
+    Ok(Bootstrap {
+        client,
+        root_token: init.root_token,
+        unseal_keys: init.keys,
+    })
+}
+
+/// Poll the health of Vault until it is active. A node elects itself leader some time after it
+/// is unsealed, and rejects requests until then.
+async fn wait_until_active(client: &Client, timeout: Duration) -> Result<(), Error> {
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match client.health().await {
+            Ok(health) if health.status == HealthStatus::Active => return Ok(()),
+            Ok(health) => debug!("Waiting for Vault to become active: {:?}", health.status),
+            Err(e) => debug!("Waiting for Vault to become active: {}", e),
+        }
+        if Instant::now() + INTERVAL > deadline {
+            return Err(Error::InvalidRequest(format!(
+                "Vault at {} did not become active within {:?}",
+                client.address(),
+                timeout
+            )));
+        }
+        tokio::time::sleep(INTERVAL).await;
+    }
+}
+
+fn kv_v2() -> MountSpec {
+    MountSpec {
+        options: Some(
+            vec![("version".to_string(), "2".to_string())]
+                .into_iter()
+                .collect(),
+        ),
+        ..MountSpec::of_type("kv")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs against the `vault_uninitialized` service of `tests/docker-compose.yml`, which can
+    /// only be bootstrapped once: recreate the container to run the test again.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_bootstrap_uninitialized_vault() {
+        let options = BootstrapOptions {
+            policies: vec![(
+                "reader".to_string(),
+                r#"path "secret/data/*" { capabilities = ["read"] }"#.to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let bootstrap = dev_bootstrap("http://127.0.0.1:8210", None::<&str>, &options)
+            .await
+            .unwrap();
+        assert_eq!(bootstrap.unseal_keys.len(), 1);
+
+        let client = &bootstrap.client;
+        assert_eq!(client.health().await.unwrap().status, HealthStatus::Active);
+        let mounts = crate::sys::ui_mounts::UiMounts::visible_mounts(client)
+            .await
+            .unwrap();
+        assert_eq!(mounts.secret["secret"].options["version"], "2");
+        assert_eq!(mounts.secret["transit"].r#type, "transit");
+        let policy = crate::sys::policies::Policies::read_policy(client, "reader")
+            .await
+            .unwrap();
+        assert!(policy.policy.contains("secret/data/*"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn initialized_vaults_are_not_bootstrapped() {
+        let result = dev_bootstrap(
+            "http://127.0.0.1:8200",
+            None::<&str>,
+            &BootstrapOptions::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+}
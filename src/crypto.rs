@@ -0,0 +1,174 @@
+//! Client-side cryptographic primitives
+//!
+//! The client only needs a few primitives itself: base64 to encode the payloads of the Transit
+//! Secrets Engine, and digests to prehash the inputs of signatures. Builds which must only use
+//! approved implementations, like FIPS validated modules, can install their own backends once,
+//! at startup, before the client is used.
+use crate::Error;
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+/// Base64 encoding, with the standard alphabet and padding
+pub trait Base64: Send + Sync + 'static {
+    /// Encode the bytes
+    fn encode(&self, bytes: &[u8]) -> String;
+    /// Decode the string
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, Error>;
+}
+
+/// Hash algorithms supported by Vault for prehashed inputs
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum HashAlgorithm {
+    /// SHA-2 with 256 bits
+    #[serde(rename = "sha2-256")]
+    Sha2_256,
+    /// SHA-2 with 384 bits
+    #[serde(rename = "sha2-384")]
+    Sha2_384,
+    /// SHA-2 with 512 bits
+    #[serde(rename = "sha2-512")]
+    Sha2_512,
+}
+
+impl HashAlgorithm {
+    /// Name of the algorithm in Vault paths
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha2_256 => "sha2-256",
+            HashAlgorithm::Sha2_384 => "sha2-384",
+            HashAlgorithm::Sha2_512 => "sha2-512",
+        }
+    }
+}
+
+/// Message digests
+pub trait Digest: Send + Sync + 'static {
+    /// Compute the digest of the data with the algorithm
+    fn digest(&self, algorithm: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Base64 implemented with the `base64` crate. Used unless another implementation is installed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultBase64;
+
+impl Base64 for DefaultBase64 {
+    fn encode(&self, bytes: &[u8]) -> String {
+        base64::encode(bytes)
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, Error> {
+        Ok(base64::decode(encoded)?)
+    }
+}
+
+/// Digests implemented with `ring`. Used unless another implementation is installed, when the
+/// `ring` dependency is enabled by a feature.
+#[cfg(feature = "ring")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RingDigest;
+
+#[cfg(feature = "ring")]
+impl Digest for RingDigest {
+    fn digest(&self, algorithm: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let algorithm = match algorithm {
+            HashAlgorithm::Sha2_256 => &ring::digest::SHA256,
+            HashAlgorithm::Sha2_384 => &ring::digest::SHA384,
+            HashAlgorithm::Sha2_512 => &ring::digest::SHA512,
+        };
+        Ok(ring::digest::digest(algorithm, data).as_ref().to_vec())
+    }
+}
+
+static BASE64: OnceLock<Box<dyn Base64>> = OnceLock::new();
+static DIGEST: OnceLock<Box<dyn Digest>> = OnceLock::new();
+
+/// Install the base64 implementation used by the client. Fails if an implementation was
+/// already installed, or was already used.
+pub fn set_base64<B: Base64>(base64: B) -> Result<(), Error> {
+    BASE64
+        .set(Box::new(base64))
+        .map_err(|_| Error::InvalidRequest("A base64 implementation is already in use".to_string()))
+}
+
+/// Install the digest implementation used by the client. Fails if an implementation was
+/// already installed, or was already used.
+pub fn set_digest<D: Digest>(digest: D) -> Result<(), Error> {
+    DIGEST
+        .set(Box::new(digest))
+        .map_err(|_| Error::InvalidRequest("A digest implementation is already in use".to_string()))
+}
+
+/// The base64 implementation in use
+pub fn base64() -> &'static dyn Base64 {
+    BASE64.get_or_init(|| Box::new(DefaultBase64)).as_ref()
+}
+
+/// Decode base64 which may omit its padding, with the implementation in use
+pub(crate) fn decode_unpadded(encoded: &str) -> Result<Vec<u8>, Error> {
+    let encoded = encoded.trim_end_matches('=');
+    let padding = (4 - encoded.len() % 4) % 4;
+    base64().decode(&format!("{}{}", encoded, "=".repeat(padding)))
+}
+
+/// Decode base64 with the URL-safe alphabet and without padding, like the segments of JWTs,
+/// with the implementation in use
+pub(crate) fn decode_url_safe(encoded: &str) -> Result<Vec<u8>, Error> {
+    let encoded: String = encoded
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    decode_unpadded(&encoded)
+}
+
+/// The digest implementation in use. Without the `ring` dependency, an implementation must be
+/// installed with [`set_digest`].
+pub fn digest() -> Result<&'static dyn Digest, Error> {
+    #[cfg(feature = "ring")]
+    let digest = Some(DIGEST.get_or_init(|| Box::new(RingDigest)));
+    #[cfg(not(feature = "ring"))]
+    let digest = DIGEST.get();
+
+    digest
+        .map(AsRef::as_ref)
+        .ok_or_else(|| Error::InvalidRequest("No digest implementation is installed".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpadded_and_url_safe_base64_are_decoded() {
+        assert_eq!(decode_unpadded("dmF1bHQ").unwrap(), b"vault");
+        assert_eq!(decode_unpadded("dmF1bHQ=").unwrap(), b"vault");
+        assert_eq!(decode_url_safe("-_8").unwrap(), [0xfb, 0xff]);
+        assert!(decode_unpadded("dmF1bHQ!").is_err());
+    }
+
+    #[test]
+    fn default_base64_round_trips() {
+        let encoded = base64().encode(b"vault");
+        assert_eq!(encoded, "dmF1bHQ=");
+        assert_eq!(base64().decode(&encoded).unwrap(), b"vault");
+        assert!(set_base64(DefaultBase64).is_err());
+    }
+
+    #[cfg(feature = "ring")]
+    #[test]
+    fn ring_computes_digests() {
+        let digest = digest()
+            .unwrap()
+            .digest(HashAlgorithm::Sha2_256, b"")
+            .unwrap();
+        assert_eq!(
+            base64().encode(&digest),
+            "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+        );
+    }
+}
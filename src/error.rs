@@ -33,18 +33,93 @@ pub enum Error {
     /// Error decoding bytes to UTF-8
     #[fail(display = "Error converting bytes to UTF-8: {}", _0)]
     Utf8Error(#[cause] std::string::FromUtf8Error),
+    /// Error decoding base64
+    #[fail(display = "Error decoding base64: {}", _0)]
+    Base64Error(#[cause] base64::DecodeError),
     /// Vault Error
     #[fail(display = "Vault Error: {}", _0)]
     VaultError(String),
     /// Missing data from Vault
     #[fail(display = "Expected data from Vault, but was missing: {:#?}", _0)]
     MissingData(Box<crate::Response>),
+    /// Response was wrapped in a single use token, as requested with
+    /// [`RequestOptions::wrap_ttl`](crate::RequestOptions::wrap_ttl), instead of returned
+    #[fail(display = "Response from Vault was wrapped in a single use token")]
+    ResponseWrapped(Box<crate::WrappedResponse>),
     /// Expected an empty response, but got something
     #[fail(display = "Expected an empty response from Vault but got {}", _0)]
     UnexpectedResponse(String),
     /// Response from Vault was not in the right form
     #[fail(display = "Response from Vault was not in the expected form")]
     MalformedResponse,
+    /// Request parameters are invalid, and the request was not sent
+    #[fail(display = "Invalid request: {}", _0)]
+    InvalidRequest(String),
+    /// Secret does not match the schema registered for its path
+    #[fail(
+        display = "Secret at {} does not match its schema. Missing fields: {:?}, unexpected fields: {:?}",
+        path, missing, extra
+    )]
+    SchemaViolation {
+        /// Path of the secret
+        path: String,
+        /// Required fields that are missing
+        missing: Vec<String>,
+        /// Fields present that are not in the schema
+        extra: Vec<String>,
+    },
+    /// Vault refused to enable an audit device, because the test message could not be written
+    /// to it
+    #[fail(display = "Audit device failed its test write: {}", _0)]
+    AuditTestFailed(String),
+    /// Some leases could not be revoked. Holds the ID of each lease with its error.
+    #[fail(display = "Failed to revoke leases: {:?}", _0)]
+    LeaseRevocationFailed(Vec<(String, Error)>),
+    /// The body of a response exceeded the configured size limit, and was not read further
+    #[fail(display = "Response body exceeds the limit of {} bytes", limit)]
+    ResponseTooLarge {
+        /// Limit, in bytes
+        limit: usize,
+    },
+    /// The body of a request exceeded the configured size limit, and the request was not sent
+    #[fail(
+        display = "Request body of {} bytes exceeds the limit of {} bytes",
+        size, limit
+    )]
+    RequestTooLarge {
+        /// Size of the body, in bytes
+        size: usize,
+        /// Limit, in bytes
+        limit: usize,
+    },
+    /// The Vault server is too old for the feature
+    #[fail(
+        display = "{} requires Vault {} or later, but the server runs Vault {}",
+        feature, required, actual
+    )]
+    UnsupportedByServer {
+        /// Feature requested
+        feature: String,
+        /// Minimum version of Vault required
+        required: String,
+        /// Version of the Vault server
+        actual: String,
+    },
+    /// Certificate or private key could not be used for TLS
+    #[fail(display = "Invalid TLS certificate or key: {}", _0)]
+    InvalidTlsMaterial(String),
+    /// Errors watching files
+    #[cfg(feature = "agent")]
+    #[fail(display = "Error watching file: {}", _0)]
+    NotifyError(#[cause] notify::Error),
+    /// Errors generating private keys and Certificate Signing Requests
+    #[cfg(feature = "pki-local-keys")]
+    #[fail(display = "Error generating Certificate Signing Request: {}", _0)]
+    CsrError(#[cause] rcgen::RcgenError),
+    /// Errors signing JWTs
+    #[cfg(feature = "gcp-service-account")]
+    #[fail(display = "Error signing JWT: {}", _0)]
+    JwtError(#[cause] jsonwebtoken::errors::Error),
 }
 
 impl From<reqwest::Error> for Error {
@@ -88,3 +163,30 @@ impl From<std::string::FromUtf8Error> for Error {
         Error::Utf8Error(error)
     }
 }
+
+impl From<base64::DecodeError> for Error {
+    fn from(error: base64::DecodeError) -> Self {
+        Error::Base64Error(error)
+    }
+}
+
+#[cfg(feature = "gcp-service-account")]
+impl From<jsonwebtoken::errors::Error> for Error {
+    fn from(error: jsonwebtoken::errors::Error) -> Self {
+        Error::JwtError(error)
+    }
+}
+
+#[cfg(feature = "agent")]
+impl From<notify::Error> for Error {
+    fn from(error: notify::Error) -> Self {
+        Error::NotifyError(error)
+    }
+}
+
+#[cfg(feature = "pki-local-keys")]
+impl From<rcgen::RcgenError> for Error {
+    fn from(error: rcgen::RcgenError) -> Self {
+        Error::CsrError(error)
+    }
+}
@@ -0,0 +1,351 @@
+//! Vault Events
+//!
+//! Vault publishes events, like writes to Key/Value secrets, as
+//! [CloudEvents](https://cloudevents.io/) to clients subscribed to `sys/events/subscribe`.
+//! [`Event`] deserializes the CloudEvent envelope, and [`Event::payload`] parses the metadata of
+//! the event types known here into typed payloads, falling back to
+//! [`EventPayload::Unknown`] for other event types.
+//!
+//! See the [documentation](https://developer.hashicorp.com/vault/docs/concepts/events).
+use serde::{Deserialize, Serialize};
+
+/// Event, in the CloudEvents format
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Event {
+    /// ID of the event
+    pub id: String,
+    /// Source of the event, like `vault://hostname`
+    pub source: String,
+    /// Version of the CloudEvents specification
+    pub specversion: String,
+    /// Type of the CloudEvent. The type of the Vault event is in the `data`.
+    #[serde(rename = "type")]
+    pub cloud_event_type: String,
+    /// Time of the event, in RFC 3339 format
+    #[serde(default)]
+    pub time: Option<String>,
+    /// Vault event
+    pub data: EventData,
+}
+
+/// Vault event carried by a CloudEvent
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct EventData {
+    /// Event
+    pub event: EventBody,
+    /// Type of the event, like `kv-v2/data-write`
+    pub event_type: String,
+    /// Plugin which published the event
+    #[serde(default)]
+    pub plugin_info: Option<PluginInfo>,
+}
+
+/// ID and metadata of an event
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct EventBody {
+    /// ID of the event
+    pub id: String,
+    /// Metadata of the event, specific to its type
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// Plugin which published an event
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct PluginInfo {
+    /// Class of the mount, like `secret` or `auth`
+    pub mount_class: String,
+    /// Accessor of the mount
+    pub mount_accessor: String,
+    /// Path of the mount, with a trailing `/`
+    pub mount_path: String,
+    /// Name of the plugin, like `kv`
+    pub plugin: String,
+}
+
+/// Operation on a Key/Value Version 2 secret
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum KvV2Operation {
+    /// `kv-v2/data-write`
+    DataWrite,
+    /// `kv-v2/data-patch`
+    DataPatch,
+    /// `kv-v2/data-delete`
+    DataDelete,
+    /// `kv-v2/data-undelete`
+    DataUndelete,
+    /// `kv-v2/destroy`
+    Destroy,
+    /// `kv-v2/metadata-write`
+    MetadataWrite,
+    /// `kv-v2/metadata-patch`
+    MetadataPatch,
+    /// `kv-v2/metadata-delete`
+    MetadataDelete,
+}
+
+impl KvV2Operation {
+    fn from_event_type(event_type: &str) -> Option<Self> {
+        let operation = match event_type {
+            "kv-v2/data-write" => KvV2Operation::DataWrite,
+            "kv-v2/data-patch" => KvV2Operation::DataPatch,
+            "kv-v2/data-delete" => KvV2Operation::DataDelete,
+            "kv-v2/data-undelete" => KvV2Operation::DataUndelete,
+            "kv-v2/destroy" => KvV2Operation::Destroy,
+            "kv-v2/metadata-write" => KvV2Operation::MetadataWrite,
+            "kv-v2/metadata-patch" => KvV2Operation::MetadataPatch,
+            "kv-v2/metadata-delete" => KvV2Operation::MetadataDelete,
+            _ => return None,
+        };
+        Some(operation)
+    }
+}
+
+/// Event on a Key/Value Version 2 secret
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct KvV2Event {
+    /// Operation on the secret
+    pub operation: KvV2Operation,
+    /// Path of the request, like `secret/data/foo`
+    pub path: String,
+    /// Path of the data of the secret, if the operation involves it
+    pub data_path: Option<String>,
+    /// Current version of the secret, if known
+    pub current_version: Option<u64>,
+    /// Oldest version of the secret, if known
+    pub oldest_version: Option<u64>,
+    /// Whether the secret was modified
+    pub modified: bool,
+}
+
+/// Operation on a Key/Value Version 1 secret
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum KvV1Operation {
+    /// `kv-v1/write`
+    Write,
+    /// `kv-v1/delete`
+    Delete,
+}
+
+/// Event on a Key/Value Version 1 secret
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct KvV1Event {
+    /// Operation on the secret
+    pub operation: KvV1Operation,
+    /// Path of the secret, like `kv/foo`
+    pub path: String,
+    /// Whether the secret was modified
+    pub modified: bool,
+}
+
+/// Event on a lease, with an event type starting with `lease/`
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LeaseEvent {
+    /// Operation on the lease, like `revoke` for `lease/revoke`
+    pub operation: String,
+    /// ID of the lease
+    pub lease_id: String,
+    /// Path of the request which created the lease, if known
+    pub path: Option<String>,
+}
+
+/// Typed payload of an event
+#[derive(Debug, PartialEq, Clone)]
+pub enum EventPayload {
+    /// Event on a Key/Value Version 2 secret
+    KvV2(KvV2Event),
+    /// Event on a Key/Value Version 1 secret
+    KvV1(KvV1Event),
+    /// Event on a lease
+    Lease(LeaseEvent),
+    /// Event of a type not known here, or with metadata missing expected fields
+    Unknown {
+        /// Type of the event
+        event_type: String,
+        /// Metadata of the event
+        metadata: serde_json::Value,
+    },
+}
+
+impl Event {
+    /// Type of the Vault event, like `kv-v2/data-write`
+    pub fn event_type(&self) -> &str {
+        &self.data.event_type
+    }
+
+    /// Parse the metadata of the event into a typed payload
+    pub fn payload(&self) -> EventPayload {
+        let event_type = self.event_type();
+        let metadata = Metadata::new(&self.data.event.metadata);
+        let payload = if let Some(operation) = KvV2Operation::from_event_type(event_type) {
+            metadata.string("path").map(|path| {
+                EventPayload::KvV2(KvV2Event {
+                    operation,
+                    path,
+                    data_path: metadata.string("data_path"),
+                    current_version: metadata.number("current_version"),
+                    oldest_version: metadata.number("oldest_version"),
+                    modified: metadata.boolean("modified"),
+                })
+            })
+        } else if event_type.starts_with("kv-v1/") {
+            let operation = match event_type {
+                "kv-v1/write" => Some(KvV1Operation::Write),
+                "kv-v1/delete" => Some(KvV1Operation::Delete),
+                _ => None,
+            };
+            operation.and_then(|operation| {
+                metadata.string("path").map(|path| {
+                    EventPayload::KvV1(KvV1Event {
+                        operation,
+                        path,
+                        modified: metadata.boolean("modified"),
+                    })
+                })
+            })
+        } else if let Some(operation) = event_type.strip_prefix("lease/") {
+            metadata.string("lease_id").map(|lease_id| {
+                EventPayload::Lease(LeaseEvent {
+                    operation: operation.to_string(),
+                    lease_id,
+                    path: metadata.string("path"),
+                })
+            })
+        } else {
+            None
+        };
+
+        payload.unwrap_or_else(|| EventPayload::Unknown {
+            event_type: event_type.to_string(),
+            metadata: self.data.event.metadata.clone(),
+        })
+    }
+}
+
+/// Metadata of an event. Vault sends most values as strings, even numbers and booleans.
+struct Metadata<'a>(Option<&'a serde_json::Map<String, serde_json::Value>>);
+
+impl<'a> Metadata<'a> {
+    fn new(metadata: &'a serde_json::Value) -> Self {
+        Metadata(metadata.as_object())
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        match self.0?.get(key)? {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+
+    fn number(&self, key: &str) -> Option<u64> {
+        self.string(key)?.parse().ok()
+    }
+
+    fn boolean(&self, key: &str) -> bool {
+        self.string(key).as_deref() == Some("true")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud_event(event_type: &str, metadata: serde_json::Value) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "id": "a3be9fb1-b514-519f-5b25-b6f144a8c1ce",
+            "source": "vault://mycomputer",
+            "specversion": "1.0",
+            "type": "*",
+            "data": {
+                "event": {
+                    "id": "a3be9fb1-b514-519f-5b25-b6f144a8c1ce",
+                    "metadata": metadata
+                },
+                "event_type": event_type,
+                "plugin_info": {
+                    "mount_class": "secret",
+                    "mount_accessor": "kv_5dc4d18e",
+                    "mount_path": "secret/",
+                    "plugin": "kv"
+                }
+            },
+            "datacontentype": "application/cloudevents",
+            "time": "2023-09-12T15:19:49.394915-07:00"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn kv_v2_events_are_typed() {
+        let event = cloud_event(
+            "kv-v2/data-write",
+            serde_json::json!({
+                "current_version": "1",
+                "data_path": "secret/data/foo",
+                "modified": "true",
+                "oldest_version": "0",
+                "operation": "data-write",
+                "path": "secret/data/foo"
+            }),
+        );
+        assert_eq!(
+            event.payload(),
+            EventPayload::KvV2(KvV2Event {
+                operation: KvV2Operation::DataWrite,
+                path: "secret/data/foo".to_string(),
+                data_path: Some("secret/data/foo".to_string()),
+                current_version: Some(1),
+                oldest_version: Some(0),
+                modified: true,
+            })
+        );
+        assert_eq!(event.data.plugin_info.unwrap().mount_path, "secret/");
+    }
+
+    #[test]
+    fn kv_v1_and_lease_events_are_typed() {
+        let event = cloud_event(
+            "kv-v1/delete",
+            serde_json::json!({"modified": "true", "operation": "delete", "path": "kv/foo"}),
+        );
+        assert_eq!(
+            event.payload(),
+            EventPayload::KvV1(KvV1Event {
+                operation: KvV1Operation::Delete,
+                path: "kv/foo".to_string(),
+                modified: true,
+            })
+        );
+
+        let event = cloud_event(
+            "lease/revoke",
+            serde_json::json!({"lease_id": "aws/creds/app/abcd"}),
+        );
+        assert_eq!(
+            event.payload(),
+            EventPayload::Lease(LeaseEvent {
+                operation: "revoke".to_string(),
+                lease_id: "aws/creds/app/abcd".to_string(),
+                path: None,
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_events_fall_back() {
+        let metadata = serde_json::json!({"path": "database/rotate-role/app"});
+        let event = cloud_event("database/rotate", metadata.clone());
+        assert_eq!(
+            event.payload(),
+            EventPayload::Unknown {
+                event_type: "database/rotate".to_string(),
+                metadata: metadata.clone(),
+            }
+        );
+
+        // Known types missing expected fields fall back too
+        let event = cloud_event("kv-v2/data-write", serde_json::json!({}));
+        assert!(matches!(event.payload(), EventPayload::Unknown { .. }));
+    }
+}
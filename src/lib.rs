@@ -48,19 +48,37 @@
 #![doc(test(attr(allow(unused_variables), deny(warnings))))]
 
 mod error;
+mod routing;
 mod utils;
 
+#[cfg(feature = "agent")]
+pub mod agent;
+pub mod auth;
+pub mod bootstrap;
+pub mod crypto;
+pub mod events;
+pub mod oidc;
+pub mod path;
+pub mod pool;
+pub mod schema;
 pub mod secrets;
 pub mod sys;
+#[cfg(feature = "pki-rustls")]
+pub mod tls;
+pub mod version;
 
 pub use error::Error;
+pub use path::{MountPath, SecretPath};
 pub use reqwest::Method;
+pub use routing::Routing;
 
 use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::fs::File;
 use std::io::Read;
 use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use log::{debug, info, warn};
@@ -70,7 +88,7 @@ use serde::{Deserialize, Serialize};
 
 /// A wrapper around a String with custom implementation of Display and Debug to not leak
 /// secrets during logging.
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 pub struct Secret(pub String);
 
 impl Deref for Secret {
@@ -105,6 +123,52 @@ impl From<String> for Secret {
     }
 }
 
+/// List all the keys at a path, fetching pages of `page_size` keys as the stream is polled.
+///
+/// Endpoints that do not support pagination return all the keys in the first page. Keys which
+/// do not come after the previous page are skipped, and the stream ends on a page without new
+/// keys, so endpoints ignoring `after` neither repeat keys nor loop forever. The stream ends
+/// after the first error.
+pub fn list_all<'a, T>(
+    client: &'a T,
+    path: &'a str,
+    page_size: u64,
+) -> impl futures::Stream<Item = Result<String, Error>> + 'a
+where
+    T: Vault + Send + Sync,
+{
+    use futures::stream::{self, StreamExt};
+
+    let page_size = page_size.max(1);
+    stream::unfold(
+        Some(None),
+        move |after: Option<Option<String>>| async move {
+            let after = after?;
+            match client
+                .list_page(path, after.as_deref(), Some(page_size))
+                .await
+            {
+                Ok(keys) => {
+                    let full = keys.len() as u64 == page_size;
+                    // Endpoints ignoring `after` return keys of previous pages again
+                    let keys: Vec<String> = match &after {
+                        Some(after) => keys.into_iter().filter(|key| key > after).collect(),
+                        None => keys,
+                    };
+                    // Stop once a page is not full, or has no new key
+                    let next = match keys.last() {
+                        Some(last) if full => Some(Some(last.clone())),
+                        _ => None,
+                    };
+                    Some((keys.into_iter().map(Ok).collect::<Vec<_>>(), next))
+                }
+                Err(e) => Some((vec![Err(e)], None)),
+            }
+        },
+    )
+    .flat_map(stream::iter)
+}
+
 /// Vault API Client
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -112,11 +176,110 @@ pub struct Client {
     address: String,
     client: HttpClient,
     revoke_self_on_drop: bool,
+    routing: Routing,
+    limits: SizeLimits,
+    server_version: Arc<Mutex<Option<version::ServerVersion>>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub(crate) struct Empty;
 
+/// Data returned from `LIST` operations
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Keys {
+    pub(crate) keys: Vec<String>,
+}
+
+/// Options applying to an individual request
+///
+/// Independently of any timeout, dropping the future of a request aborts the in-flight HTTP
+/// request. Callers can race a request against their own cancellation signal, for example with
+/// `tokio::select!`.
+///
+/// Typed operations of secrets engines and auth methods are given options with
+/// [`Client::with_options`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RequestOptions {
+    /// Do not send the Vault token with the request. Useful for unauthenticated endpoints.
+    /// Clients without a token, created with [`Client::without_token`], never send one.
+    pub unauthenticated: bool,
+    /// Timeout for the request, overriding any timeout configured on the HTTP client.
+    pub timeout: Option<Duration>,
+    /// Have Vault wrap the response in a single use token, valid for the duration, instead of
+    /// returning it. The token is read with [`Response::wrapped`]. Vault only accepts whole
+    /// seconds, so the duration is rounded up to the next second, and must not be zero.
+    ///
+    /// Typed operations given the option with [`Client::with_options`] fail with
+    /// [`Error::ResponseWrapped`], holding the wrapping token, as the response has no data.
+    pub wrap_ttl: Option<Duration>,
+}
+
+impl RequestOptions {
+    /// Options of `self`, with the options it does not set taken from `defaults`
+    fn or(&self, defaults: &RequestOptions) -> RequestOptions {
+        RequestOptions {
+            unauthenticated: self.unauthenticated || defaults.unauthenticated,
+            timeout: self.timeout.or(defaults.timeout),
+            wrap_ttl: self.wrap_ttl.or(defaults.wrap_ttl),
+        }
+    }
+}
+
+/// Value of the `X-Vault-Wrap-TTL` header, in whole seconds rounded up
+fn wrap_ttl_header(wrap_ttl: Duration) -> Result<String, Error> {
+    if wrap_ttl == Duration::from_secs(0) {
+        return Err(Error::InvalidRequest(
+            "wrap_ttl must not be zero".to_string(),
+        ));
+    }
+    let seconds = wrap_ttl.as_secs() + u64::from(wrap_ttl.subsec_nanos() > 0);
+    Ok(format!("{}s", seconds))
+}
+
+/// Client applying [`RequestOptions`] to every request, created with [`Client::with_options`]
+///
+/// Secrets engines and auth methods are implemented for any [`Vault`], so their typed
+/// operations can be made with per-request options, like a timeout, through this client.
+/// Options given to a single request take precedence.
+#[derive(Debug, Clone)]
+pub struct WithOptions<'a> {
+    client: &'a Client,
+    options: RequestOptions,
+}
+
+impl<'a> WithOptions<'a> {
+    /// Options applied to every request
+    pub fn options(&self) -> &RequestOptions {
+        &self.options
+    }
+}
+
+/// Limits on the size of the bodies of requests and responses
+///
+/// Responses larger than the limit are rejected with [`Error::ResponseTooLarge`] while they are
+/// read, rather than buffered whole in memory. Requests larger than the limit are rejected with
+/// [`Error::RequestTooLarge`] before they are sent. There are no limits by default.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct SizeLimits {
+    /// Maximum size of the body of responses, in bytes
+    pub max_response_bytes: Option<usize>,
+    /// Maximum size of the body of requests, in bytes
+    pub max_request_bytes: Option<usize>,
+}
+
+impl SizeLimits {
+    fn check_request(&self, request: &reqwest::Request) -> Result<(), Error> {
+        let size = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map_or(0, <[u8]>::len);
+        match self.max_request_bytes {
+            Some(limit) if size > limit => Err(Error::RequestTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Generic Vault Response
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
@@ -155,8 +318,138 @@ pub struct ResponseData {
     /// Data for secrets requests
     #[serde(default)]
     pub data: Option<serde_json::Value>,
-    // Missing and ignored fields:
-    // - wrap_info
+
+    /// Wrapping token, when the response was wrapped
+    #[serde(default)]
+    pub wrap_info: Option<WrappedResponse>,
+
+    /// Cluster that served the request, from the response headers and body
+    #[serde(flatten)]
+    pub cluster: ClusterInfo,
+
+    /// Whether the response was served from the cache of a Vault Agent, from the response
+    /// headers
+    #[serde(skip)]
+    pub cache: CacheInfo,
+}
+
+/// Response wrapped by Vault in a single use token
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct WrappedResponse {
+    /// Token to unwrap the response with
+    pub token: Secret,
+    /// Accessor of the wrapping token
+    pub accessor: String,
+    /// TTL of the wrapping token, in seconds
+    pub ttl: u64,
+    /// Time the wrapping token was created, in RFC 3339 format
+    pub creation_time: String,
+    /// Path of the request which produced the response
+    pub creation_path: String,
+    /// Accessor of the token in the response, for wrapped authentication responses
+    #[serde(default)]
+    pub wrapped_accessor: String,
+}
+
+/// Identity of the cluster, and of the node, which served a request
+///
+/// The ID and name of the cluster are only in the body of some endpoints, like
+/// [`sys/health`](crate::sys::health). The node is identified by the `X-Vault-Hostname` and
+/// `X-Vault-Raft-Node-ID` headers, which Vault only sends when its listener is configured with
+/// `enable_response_header_hostname` and `enable_response_header_raft_node_id`.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct ClusterInfo {
+    /// ID of the cluster
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster_id: Option<String>,
+    /// Name of the cluster
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster_name: Option<String>,
+    /// Host name of the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// ID of the node in the Raft cluster
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raft_node_id: Option<String>,
+}
+
+impl ClusterInfo {
+    const HOSTNAME_HEADER: &'static str = "X-Vault-Hostname";
+    const RAFT_NODE_ID_HEADER: &'static str = "X-Vault-Raft-Node-ID";
+
+    /// Returns whether nothing is known about the cluster
+    pub fn is_empty(&self) -> bool {
+        self.cluster_id.is_none()
+            && self.cluster_name.is_none()
+            && self.hostname.is_none()
+            && self.raft_node_id.is_none()
+    }
+
+    /// Fill in the information missing from the response body with the response headers
+    fn merge_headers(&mut self, headers: &reqwest::header::HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        self.hostname = self
+            .hostname
+            .take()
+            .or_else(|| header(Self::HOSTNAME_HEADER));
+        self.raft_node_id = self
+            .raft_node_id
+            .take()
+            .or_else(|| header(Self::RAFT_NODE_ID_HEADER));
+    }
+}
+
+/// Caching of a response by a proxy, like the Vault Agent cache
+///
+/// The Vault Agent sets the `X-Cache` header to `HIT` on responses served from its cache, along
+/// with their `Age`. Vault itself never caches responses.
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct CacheInfo {
+    /// Whether the response was served from a cache
+    pub hit: bool,
+    /// Time since the response was cached, if known
+    pub age: Option<Duration>,
+    /// `Cache-Control` header of the response, if any
+    pub cache_control: Option<String>,
+}
+
+impl CacheInfo {
+    const CACHE_HEADER: &'static str = "X-Cache";
+    const AGE_HEADER: &'static str = "Age";
+    const CACHE_CONTROL_HEADER: &'static str = "Cache-Control";
+
+    /// Returns whether the response was served from a cache and is older than `max_age`.
+    /// Cached responses of unknown age are considered stale.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.hit && self.age.is_none_or(|age| age > max_age)
+    }
+
+    /// Maximum age of the response allowed by the `max-age` directive of `Cache-Control`, if any
+    pub fn max_age(&self) -> Option<Duration> {
+        self.cache_control
+            .as_deref()?
+            .split(',')
+            .find_map(|directive| {
+                let seconds = directive.trim().strip_prefix("max-age=")?;
+                seconds.parse().ok().map(Duration::from_secs)
+            })
+    }
+
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        CacheInfo {
+            hit: header(Self::CACHE_HEADER).is_some_and(|value| value.eq_ignore_ascii_case("hit")),
+            age: header(Self::AGE_HEADER)
+                .and_then(|age| age.trim().parse().ok())
+                .map(Duration::from_secs),
+            cache_control: header(Self::CACHE_CONTROL_HEADER).map(str::to_string),
+        }
+    }
 }
 
 /// Wrapped Vault Secret with Lease Data
@@ -177,6 +470,16 @@ impl<T> LeasedData<T> {
     pub fn unwrap(self) -> T {
         self.data
     }
+
+    /// Duration of the lease
+    pub fn lease_duration(&self) -> Duration {
+        Duration::from_secs(self.lease_duration)
+    }
+
+    /// Instant at which the lease expires, given the instant at which it was received
+    pub fn deadline(&self, received: std::time::Instant) -> std::time::Instant {
+        received + self.lease_duration()
+    }
 }
 
 /// Authentication data from Vault
@@ -187,10 +490,13 @@ pub struct Authentication {
     /// The accessor for the Token
     pub accessor: String,
     /// List of policies for token, including from Identity
+    #[serde(default, deserialize_with = "utils::deserialize_null_default")]
     pub policies: Vec<String>,
     /// List of tokens directly assigned to token
+    #[serde(default, deserialize_with = "utils::deserialize_null_default")]
     pub token_policies: Vec<String>,
     /// Arbitrary metadata
+    #[serde(default, deserialize_with = "utils::deserialize_null_default")]
     pub metadata: HashMap<String, String>,
     /// Lease Duration for the token
     pub lease_duration: u64,
@@ -200,17 +506,53 @@ pub struct Authentication {
     pub entity_id: String,
     /// Type of token
     pub token_type: TokenType,
+    /// Whether the token has no parent
+    #[serde(default)]
+    pub orphan: bool,
+    /// MFA required to complete the login. Logins requiring MFA have no token until they are
+    /// validated with [`auth::mfa::LoginMfa::validate_mfa`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mfa_requirement: Option<auth::mfa::MfaRequirement>,
+}
+
+impl Authentication {
+    /// Whether the token is a batch token.
+    ///
+    /// Batch tokens are not renewable, cannot create child tokens and cannot be revoked by
+    /// themselves. They expire at the end of their TTL, or when their parent is revoked.
+    pub fn is_batch(&self) -> bool {
+        self.token_type == TokenType::Batch
+    }
+
+    /// Whether the login requires MFA to be completed, and has no token yet
+    pub fn requires_mfa(&self) -> bool {
+        self.mfa_requirement.is_some()
+    }
+
+    /// Whether the TTL of the token can be extended by renewing it
+    pub fn is_renewable(&self) -> bool {
+        self.renewable && !self.is_batch()
+    }
 }
 
 /// Type of token from Vault
 /// See [Vault Documentation](https://www.vaultproject.io/docs/concepts/tokens.html#token-types-in-detail)
+///
+/// The `Default*` variants can only be used when configuring the tokens generated by a role or an
+/// auth method. Tokens themselves are always either service or batch tokens.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "kebab-case")]
 pub enum TokenType {
     /// Long lived service tokens
     Service,
     /// Short lived batch tokens
     Batch,
+    /// Use the type configured on the mount, which defaults to service tokens
+    Default,
+    /// Service tokens, unless the client requests a batch token
+    DefaultService,
+    /// Batch tokens, unless the client requests a service token
+    DefaultBatch,
 }
 
 /// Trait implementing the basic API operations for Vault
@@ -227,6 +569,27 @@ pub trait Vault {
         query: &T,
     ) -> Result<Response, Error>;
 
+    /// Read a generic Path from Vault with per-request options, deserializing the body into `R`.
+    ///
+    /// Use `Response` for `R` to decode the usual Vault response. Some endpoints, like OIDC
+    /// discovery, return their data as-is without the usual response wrapping.
+    ///
+    /// The default implementation ignores `options`, and converts the response of
+    /// [`Vault::read`]. Endpoints returning their data as-is need an implementation sending the
+    /// request itself.
+    async fn read_with_options<R>(
+        &self,
+        path: &str,
+        method: Method,
+        _options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned + Debug + Send,
+    {
+        let response = self.read(path, method).await?;
+        Ok(serde_json::from_value(serde_json::to_value(response)?)?)
+    }
+
     /// Write to a generic Path in Vault.
     async fn write<T: Serialize + Send + Sync>(
         &self,
@@ -236,11 +599,81 @@ pub trait Vault {
         response_expected: bool,
     ) -> Result<Response, Error>;
 
+    /// Write to a generic Path in Vault with per-request options.
+    ///
+    /// The default implementation ignores `options`, and calls [`Vault::write`].
+    async fn write_with_options<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+        _options: &RequestOptions,
+    ) -> Result<Response, Error> {
+        self.write(path, payload, method, response_expected).await
+    }
+
+    /// Write to a generic Path in Vault with per-request options, deserializing the body into
+    /// `R`.
+    ///
+    /// Some endpoints, like the ones generating root tokens, return their data as-is without the
+    /// usual response wrapping.
+    ///
+    /// The default implementation ignores `options`, and converts the response of
+    /// [`Vault::write`]. Endpoints returning their data as-is need an implementation sending the
+    /// request itself.
+    async fn write_raw<T, R>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        _options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        T: Serialize + Send + Sync,
+        R: DeserializeOwned + Debug + Send,
+    {
+        let response = self.write(path, payload, method, true).await?;
+        Ok(serde_json::from_value(serde_json::to_value(response)?)?)
+    }
+
     /// Convenience method to Get a generic path from Vault
     async fn get(&self, path: &str) -> Result<Response, Error> {
         self.read(path, Method::GET).await
     }
 
+    /// Read a path, with the response wrapped in a single use token valid for `wrap_ttl`
+    async fn read_wrapped(
+        &self,
+        path: &str,
+        method: Method,
+        wrap_ttl: Duration,
+    ) -> Result<WrappedResponse, Error> {
+        let options = RequestOptions {
+            wrap_ttl: Some(wrap_ttl),
+            ..Default::default()
+        };
+        let response: Response = self.read_with_options(path, method, &options).await?;
+        response.wrapped()
+    }
+
+    /// Write to a path, with the response wrapped in a single use token valid for `wrap_ttl`
+    async fn write_wrapped<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        wrap_ttl: Duration,
+    ) -> Result<WrappedResponse, Error> {
+        let options = RequestOptions {
+            wrap_ttl: Some(wrap_ttl),
+            ..Default::default()
+        };
+        self.write_with_options(path, payload, method, true, &options)
+            .await?
+            .wrapped()
+    }
+
     /// Convenience method to Get a generic path from VaultResult
     async fn get_with_query<T: Serialize + Send + Sync + ?Sized>(
         &self,
@@ -256,6 +689,35 @@ pub trait Vault {
             .await
     }
 
+    /// List a page of at most `limit` keys at a path, starting after the key `after`.
+    ///
+    /// Only some endpoints support pagination, from Vault 1.17. Other endpoints ignore the
+    /// parameters, and return all the keys. A path without any key returns an empty page.
+    async fn list_page(
+        &self,
+        path: &str,
+        after: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, Error> {
+        #[derive(Serialize)]
+        struct Query<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            after: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            limit: Option<u64>,
+        }
+
+        let method = Method::from_bytes(b"LIST").expect("Not to fail");
+        match self
+            .read_with_query(path, method, &Query { after, limit })
+            .await?
+        {
+            // Vault responds with a 404 without errors when there is no key
+            Response::Error { errors } if errors.is_empty() => Ok(vec![]),
+            response => Ok(response.data::<Keys>()?.keys),
+        }
+    }
+
     /// Convenience method to Post to a generic path to Vault
     async fn post<T: Serialize + Send + Sync>(
         &self,
@@ -306,6 +768,9 @@ impl Client {
             token: Secret(vault_token.as_ref().to_string()),
             revoke_self_on_drop,
             client,
+            routing: Default::default(),
+            limits: Default::default(),
+            server_version: Default::default(),
         })
     }
 
@@ -348,10 +813,28 @@ impl Client {
         S2: AsRef<str>,
         S3: AsRef<str>,
     {
-        let address = Self::environment_variable_or_provided("VAULT_ADDR", address)
-            .ok_or(Error::MissingAddress)?;
         let token = Self::environment_variable_or_provided("VAULT_TOKEN", token)
             .ok_or(Error::MissingToken)?;
+        let mut client = Self::without_token(address, ca_cert)?;
+        client.token = Secret(token);
+        Ok(client)
+    }
+
+    /// Create a client without a token, for the unauthenticated endpoints like the seal status
+    /// or the OpenID Connect discovery documents. The address and the CA certificate are read
+    /// from the environment variables like [`Client::from_environment`], unless they are
+    /// provided.
+    ///
+    /// The client never sends a token, so that monitoring agents do not need to hold one.
+    /// Authenticated endpoints fail with a permission error until a token is set with
+    /// [`Client::set_token`].
+    pub fn without_token<S1, S2>(address: Option<S1>, ca_cert: Option<S2>) -> Result<Self, Error>
+    where
+        S1: AsRef<str>,
+        S2: AsRef<str>,
+    {
+        let address = Self::environment_variable_or_provided("VAULT_ADDR", address)
+            .ok_or(Error::MissingAddress)?;
         let root_ca = Self::environment_variable_or_provided("VAULT_CACERT", ca_cert);
 
         let client = if let Some(cert) = root_ca {
@@ -366,7 +849,7 @@ impl Client {
         // VAULT_CLIENT_CERT
         // VAULT_CLIENT_KEY
         // VAULT_TLS_SERVER_NAME
-        Self::internal_new(&address, &token, false, client)
+        Self::internal_new(&address, "", false, client)
     }
 
     fn environment_variable_or_provided<S>(
@@ -386,69 +869,208 @@ impl Client {
         &self.address
     }
 
-    async fn execute_request<T>(client: &HttpClient, request: reqwest::Request) -> Result<T, Error>
+    /// Update the Vault address used for subsequent requests, for example after discovering
+    /// the active node of a cluster.
+    ///
+    /// Requests are only sent to this address if the client is not routing requests across the
+    /// nodes of a cluster. See [`Routing`].
+    ///
+    /// The address is validated before it is updated, and the existing address is kept if it
+    /// is invalid.
+    pub fn set_address<S: AsRef<str>>(&mut self, address: S) -> Result<(), Error> {
+        let _ = url::Url::parse(address.as_ref())?;
+        self.address = address.as_ref().to_string();
+        self.server_version = Default::default();
+        Ok(())
+    }
+
+    /// Replace the token used for subsequent requests, for example after Vault Agent renders a
+    /// new token to its sink.
+    pub fn set_token<S: AsRef<str>>(&mut self, token: S) {
+        self.token = Secret(token.as_ref().to_string());
+    }
+
+    /// Returns the underlying HTTP client
+    pub fn http_client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// Replace the underlying HTTP client used for subsequent requests, for example to pick up
+    /// a rotated CA certificate.
+    pub fn set_http_client(&mut self, client: HttpClient) {
+        self.client = client;
+    }
+
+    /// Returns the limits on the size of requests and responses
+    pub fn size_limits(&self) -> SizeLimits {
+        self.limits
+    }
+
+    /// Limit the size of subsequent requests and responses
+    pub fn set_size_limits(&mut self, limits: SizeLimits) {
+        self.limits = limits;
+    }
+
+    async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+        self.limits.check_request(&request)?;
+        debug!("Executing request: {:#?}", request);
+        let response = self.client.execute(request).await?;
+        debug!("Response received: {:#?}", response);
+        Ok(response)
+    }
+
+    /// Read the body of the response, up to the size limit
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, Error> {
+        let limit = match self.limits.max_response_bytes {
+            Some(limit) => limit,
+            None => return Ok(response.bytes().await?.to_vec()),
+        };
+        if response
+            .content_length()
+            .is_some_and(|length| length > limit as u64)
+        {
+            return Err(Error::ResponseTooLarge { limit });
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(Error::ResponseTooLarge { limit });
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn read_text(&self, response: reqwest::Response) -> Result<String, Error> {
+        let body = self.read_body(response).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    async fn execute_request<T>(&self, request: reqwest::Request) -> Result<T, Error>
     where
         T: DeserializeOwned + Debug,
     {
-        debug!("Executing request: {:#?}", request);
-        let response = client.execute(request).await?;
-        debug!("Response received: {:#?}", response);
-        let body = response.text().await?;
+        let (result, _) = self.execute_request_with_headers(request).await?;
+        Ok(result)
+    }
+
+    /// Execute the request, returning the deserialized body along with the response headers
+    async fn execute_request_with_headers<T>(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<(T, reqwest::header::HeaderMap), Error>
+    where
+        T: DeserializeOwned + Debug,
+    {
+        let response = self.send(request).await?;
+        let headers = response.headers().clone();
+        let body = self.read_text(response).await?;
         debug!("Response body: {}", body);
-        let result = serde_json::from_str(&body)?;
+        // Some endpoints respond with 204 and no body, depending on the version of Vault
+        let body = if body.is_empty() { "null" } else { &body };
+        let result = serde_json::from_str(body)?;
         debug!("Deserialized body: {:#?}", result);
+        Ok((result, headers))
+    }
+
+    async fn execute_response(&self, request: reqwest::Request) -> Result<Response, Error> {
+        let (mut result, headers) = self.execute_request_with_headers(request).await?;
+        if let Response::Response(data) = &mut result {
+            data.cluster.merge_headers(&headers);
+            data.cache = CacheInfo::from_headers(&headers);
+        }
         Ok(result)
     }
 
-    async fn execute_request_no_body(
-        client: &HttpClient,
-        request: reqwest::Request,
-    ) -> Result<(), Error> {
-        debug!("Executing request: {:#?}", request);
-        let response = client.execute(request).await?;
-        debug!("Response received: {:#?}", response);
-        let body = response.text().await?;
+    async fn execute_request_no_body(&self, request: reqwest::Request) -> Result<(), Error> {
+        let response = self.send(request).await?;
+        let body = self.read_text(response).await?;
         if !body.is_empty() {
             return Err(Error::UnexpectedResponse(body));
         }
         Ok(())
     }
 
+    async fn execute_request_bytes(&self, request: reqwest::Request) -> Result<Vec<u8>, Error> {
+        let response = self.send(request).await?;
+        if !response.status().is_success() {
+            let body = self.read_text(response).await?;
+            return match serde_json::from_str::<Response>(&body) {
+                Ok(Response::Error { errors }) => Err(Error::VaultError(errors.join("; "))),
+                _ => Err(Error::UnexpectedResponse(body)),
+            };
+        }
+        self.read_body(response).await
+    }
+
     fn build_request<S: AsRef<str>>(
         &self,
         path: S,
         method: Method,
     ) -> Result<reqwest::RequestBuilder, Error> {
-        let vault_address = url::Url::parse(self.address())?;
+        self.build_request_with_options(path, method, &Default::default())
+    }
+
+    fn build_request_with_options<S: AsRef<str>>(
+        &self,
+        path: S,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<reqwest::RequestBuilder, Error> {
+        let vault_address = url::Url::parse(self.address_for(&method))?;
         let vault_address = vault_address.join(&format!("/v1/{}", path.as_ref()))?;
 
-        Ok(self
-            .client
-            .request(method, vault_address)
-            .header("X-Vault-Token", self.token.as_str()))
+        let mut request = self.client.request(method, vault_address);
+        if !options.unauthenticated && !self.token.is_empty() {
+            request = request.header("X-Vault-Token", self.token.as_str());
+        }
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+        if let Some(wrap_ttl) = options.wrap_ttl {
+            request = request.header("X-Vault-Wrap-TTL", wrap_ttl_header(wrap_ttl)?);
+        }
+        Ok(request)
     }
 
-    /// Revoke the Vault token itself
+    /// Make requests with the options, for example to give typed operations a timeout:
     ///
-    /// If successful, the Vault Token can no longer be used
-    pub async fn revoke_self(&self) -> Result<(), Error> {
-        info!("Revoking self Vault Token");
+    /// ```rust,ignore
+    /// let options = RequestOptions {
+    ///     timeout: Some(Duration::from_secs(5)),
+    ///     ..Default::default()
+    /// };
+    /// let status = Seal::unseal(&client.with_options(options), key, false).await?;
+    /// ```
+    pub fn with_options(&self, options: RequestOptions) -> WithOptions<'_> {
+        WithOptions {
+            client: self,
+            options,
+        }
+    }
 
-        let request = self.build_revoke_self_request()?;
-        // HTTP 204 is returned
-        Self::execute_request_no_body(&self.client, request).await?;
+    /// Shut the client down, revoking its token if `revoke_token` is set, or if the client was
+    /// created to revoke its token on drop.
+    ///
+    /// Unlike revoking on drop, which blocks the thread until Vault responds, the revocation is
+    /// awaited, and its error returned. The client does not run any background task, so there
+    /// is nothing else to stop.
+    pub async fn shutdown(mut self, revoke_token: bool) -> Result<(), Error> {
+        let revoke_token = revoke_token || self.revoke_self_on_drop;
+        self.revoke_self_on_drop = false;
+        if revoke_token {
+            info!("Vault Client is shutting down. Revoking its own Token");
+            let _ = auth::Token::revoke_self(&self).await?;
+        }
         Ok(())
     }
 
-    fn build_revoke_self_request(&self) -> Result<reqwest::Request, Error> {
-        let vault_address = url::Url::parse(self.address())?;
-        let vault_address = vault_address.join("/v1/auth/token/revoke-self")?;
-
-        Ok(self
-            .client
-            .post(vault_address)
-            .header("X-Vault-Token", self.token.as_str())
-            .build()?)
+    /// Revoke the Vault token itself
+    ///
+    /// If successful, the Vault Token can no longer be used
+    #[deprecated(note = "Use `auth::Token::revoke_self` instead")]
+    pub async fn revoke_self(&self) -> Result<(), Error> {
+        auth::Token::revoke_self(self).await.map(|_| ())
     }
 }
 
@@ -471,6 +1093,18 @@ where
         T::read_with_query(self, path, method, query).await
     }
 
+    async fn read_with_options<R>(
+        &self,
+        path: &str,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned + Debug + Send,
+    {
+        T::read_with_options(self, path, method, options).await
+    }
+
     async fn write<P: Serialize + Send + Sync>(
         &self,
         path: &str,
@@ -480,6 +1114,31 @@ where
     ) -> Result<Response, Error> {
         T::write(self, path, payload, method, response_expected).await
     }
+
+    async fn write_with_options<P: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &P,
+        method: Method,
+        response_expected: bool,
+        options: &RequestOptions,
+    ) -> Result<Response, Error> {
+        T::write_with_options(self, path, payload, method, response_expected, options).await
+    }
+
+    async fn write_raw<P, R>(
+        &self,
+        path: &str,
+        payload: &P,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Debug + Send,
+    {
+        T::write_raw(self, path, payload, method, options).await
+    }
 }
 
 #[async_trait]
@@ -487,7 +1146,7 @@ impl Vault for Client {
     async fn read(&self, path: &str, method: Method) -> Result<Response, Error> {
         let request = self.build_request(path, method)?.build()?;
 
-        Self::execute_request(&self.client, request).await
+        self.execute_response(request).await
     }
 
     async fn read_with_query<T: Serialize + Send + Sync + ?Sized>(
@@ -497,7 +1156,22 @@ impl Vault for Client {
         query: &T,
     ) -> Result<Response, Error> {
         let request = self.build_request(path, method)?.query(&query).build()?;
-        Self::execute_request(&self.client, request).await
+        self.execute_response(request).await
+    }
+
+    async fn read_with_options<R>(
+        &self,
+        path: &str,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned + Debug + Send,
+    {
+        let request = self
+            .build_request_with_options(path, method, options)?
+            .build()?;
+        self.execute_request(request).await
     }
 
     async fn write<T: Serialize + Send + Sync>(
@@ -507,23 +1181,146 @@ impl Vault for Client {
         method: Method,
         response_expected: bool,
     ) -> Result<Response, Error> {
-        let request = self.build_request(path, method)?.json(payload).build()?;
+        self.write_with_options(
+            path,
+            payload,
+            method,
+            response_expected,
+            &Default::default(),
+        )
+        .await
+    }
+
+    async fn write_with_options<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+        options: &RequestOptions,
+    ) -> Result<Response, Error> {
+        let mut request = self.build_request_with_options(path, method.clone(), options)?;
+        if method == Method::PATCH {
+            // Vault only accepts JSON merge patches
+            request = request.header(
+                reqwest::header::CONTENT_TYPE,
+                "application/merge-patch+json",
+            );
+        }
+        let request = request.json(payload).build()?;
         if response_expected {
-            Self::execute_request(&self.client, request).await
+            self.execute_response(request).await
         } else {
-            Self::execute_request_no_body(&self.client, request)
+            self.execute_request_no_body(request)
                 .await
                 .map(|_| Response::Empty)
         }
     }
+
+    async fn write_raw<T, R>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        T: Serialize + Send + Sync,
+        R: DeserializeOwned + Debug + Send,
+    {
+        let request = self
+            .build_request_with_options(path, method, options)?
+            .json(payload)
+            .build()?;
+        self.execute_request(request).await
+    }
+}
+
+#[async_trait]
+impl<'a> Vault for WithOptions<'a> {
+    async fn read(&self, path: &str, method: Method) -> Result<Response, Error> {
+        let request = self
+            .client
+            .build_request_with_options(path, method, &self.options)?
+            .build()?;
+        self.client.execute_response(request).await
+    }
+
+    async fn read_with_query<T: Serialize + Send + Sync + ?Sized>(
+        &self,
+        path: &str,
+        method: Method,
+        query: &T,
+    ) -> Result<Response, Error> {
+        let request = self
+            .client
+            .build_request_with_options(path, method, &self.options)?
+            .query(&query)
+            .build()?;
+        self.client.execute_response(request).await
+    }
+
+    async fn read_with_options<R>(
+        &self,
+        path: &str,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned + Debug + Send,
+    {
+        let options = options.or(&self.options);
+        self.client.read_with_options(path, method, &options).await
+    }
+
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+    ) -> Result<Response, Error> {
+        self.client
+            .write_with_options(path, payload, method, response_expected, &self.options)
+            .await
+    }
+
+    async fn write_with_options<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+        options: &RequestOptions,
+    ) -> Result<Response, Error> {
+        let options = options.or(&self.options);
+        self.client
+            .write_with_options(path, payload, method, response_expected, &options)
+            .await
+    }
+
+    async fn write_raw<T, R>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        T: Serialize + Send + Sync,
+        R: DeserializeOwned + Debug + Send,
+    {
+        let options = options.or(&self.options);
+        self.client.write_raw(path, payload, method, &options).await
+    }
 }
 
 impl Drop for Client {
     fn drop(&mut self) {
         if self.revoke_self_on_drop {
             info!("Vault Client is being dropped. Revoking its own Token");
-            match futures::executor::block_on(self.revoke_self()) {
-                Ok(()) => {}
+            match futures::executor::block_on(auth::Token::revoke_self(self)) {
+                Ok(_) => {}
                 Err(e) => warn!("Error revoking self: {}", e),
             }
         }
@@ -547,7 +1344,7 @@ impl Response {
             Response::Error { errors } => Err(Error::VaultError(errors.join("; "))),
             Response::Empty => Err(Error::MissingData(Box::new(self.clone()))),
             Response::Response(data) => match &data.data {
-                None => Err(Error::MissingData(Box::new(self.clone()))),
+                None => Err(self.missing_data()),
                 Some(data) => Ok(data),
             },
         }
@@ -559,19 +1356,55 @@ impl Response {
             Response::Error { errors } => Err(Error::VaultError(errors.join("; "))),
             Response::Empty => Err(Error::MissingData(Box::new(self.clone()))),
             Response::Response(response_data) => match &response_data.data {
-                None => Err(Error::MissingData(Box::new(self.clone()))),
+                None => Err(self.missing_data()),
                 Some(data) => Ok(serde_json::from_value(data.clone())?),
             },
         }
     }
 
+    /// Decode the authentication data from a login response
+    pub fn auth(&self) -> Result<Authentication, Error> {
+        match self {
+            Response::Error { errors } => Err(Error::VaultError(errors.join("; "))),
+            Response::Empty => Err(Error::MissingData(Box::new(self.clone()))),
+            Response::Response(response_data) => match &response_data.auth {
+                None => Err(self.missing_data()),
+                Some(auth) => Ok(auth.clone()),
+            },
+        }
+    }
+
+    /// Error for a response without the data expected from it
+    fn missing_data(&self) -> Error {
+        match self {
+            Response::Response(ResponseData {
+                wrap_info: Some(wrap_info),
+                ..
+            }) => Error::ResponseWrapped(Box::new(wrap_info.clone())),
+            _ => Error::MissingData(Box::new(self.clone())),
+        }
+    }
+
+    /// Decode the wrapping token of a response requested with
+    /// [`RequestOptions::wrap_ttl`]
+    pub fn wrapped(&self) -> Result<WrappedResponse, Error> {
+        match self {
+            Response::Error { errors } => Err(Error::VaultError(errors.join("; "))),
+            Response::Empty => Err(Error::MissingData(Box::new(self.clone()))),
+            Response::Response(response_data) => match &response_data.wrap_info {
+                None => Err(Error::MissingData(Box::new(self.clone()))),
+                Some(wrap_info) => Ok(wrap_info.clone()),
+            },
+        }
+    }
+
     /// Decode the response into the appropriate data type along with lease data
     pub fn leased_data<T: DeserializeOwned>(&self) -> Result<LeasedData<T>, Error> {
         match self {
             Response::Error { errors } => Err(Error::VaultError(errors.join("; "))),
             Response::Empty => Err(Error::MissingData(Box::new(self.clone()))),
             Response::Response(response_data) => match &response_data.data {
-                None => Err(Error::MissingData(Box::new(self.clone()))),
+                None => Err(self.missing_data()),
                 Some(data) => {
                     let deserialized = serde_json::from_value(data.clone())?;
                     Ok(LeasedData {
@@ -622,6 +1455,382 @@ pub(crate) mod tests {
         let _ = client.get("/auth/token/lookup-self").await.unwrap();
     }
 
+    #[tokio::test]
+    async fn can_read_with_timeout() {
+        let client = vault_client();
+        let options = RequestOptions {
+            timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let response: Response = client
+            .read_with_options("auth/token/lookup-self", Method::GET, &options)
+            .await
+            .unwrap();
+        let _ = response.data_value().unwrap();
+    }
+
+    #[tokio::test]
+    async fn typed_operations_time_out() {
+        // Accepts connections into its backlog, but never responds
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let client = Client::from_environment(Some(address), Some("12345"), None::<&str>).unwrap();
+
+        let options = RequestOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let result = sys::mounts::Mounts::list(&client.with_options(options)).await;
+        assert!(matches!(result, Err(Error::ReqwestError(e)) if e.is_timeout()));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Options of a single request take precedence
+        let options = RequestOptions {
+            timeout: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let single = RequestOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let result: Result<Response, _> = client
+            .with_options(options)
+            .read_with_options("sys/mounts", Method::GET, &single)
+            .await;
+        assert!(matches!(result, Err(Error::ReqwestError(e)) if e.is_timeout()));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Implements only the required methods of `Vault`, returning `Empty` to every request
+    struct EmptyVault;
+
+    #[async_trait]
+    impl Vault for EmptyVault {
+        async fn read(&self, _path: &str, _method: Method) -> Result<Response, Error> {
+            Ok(Response::Empty)
+        }
+
+        async fn read_with_query<T: Serialize + Send + Sync + ?Sized>(
+            &self,
+            path: &str,
+            method: Method,
+            _query: &T,
+        ) -> Result<Response, Error> {
+            self.read(path, method).await
+        }
+
+        async fn write<T: Serialize + Send + Sync>(
+            &self,
+            _path: &str,
+            _payload: &T,
+            _method: Method,
+            _response_expected: bool,
+        ) -> Result<Response, Error> {
+            Ok(Response::Empty)
+        }
+    }
+
+    /// Lists keys of a single page of at most `limit` keys, ignoring `after`
+    struct UnpaginatedVault;
+
+    #[async_trait]
+    impl Vault for UnpaginatedVault {
+        async fn read(&self, path: &str, method: Method) -> Result<Response, Error> {
+            self.read_with_query(path, method, &Empty).await
+        }
+
+        async fn read_with_query<T: Serialize + Send + Sync + ?Sized>(
+            &self,
+            _path: &str,
+            _method: Method,
+            query: &T,
+        ) -> Result<Response, Error> {
+            let limit = serde_json::to_value(query)?["limit"].as_u64().unwrap_or(3);
+            let keys: Vec<_> = ["a", "b", "c"].iter().take(limit as usize).collect();
+            Ok(serde_json::from_value(serde_json::json!({
+                "request_id": "",
+                "lease_id": "",
+                "renewable": false,
+                "lease_duration": 0,
+                "data": {"keys": keys},
+            }))?)
+        }
+
+        async fn write<T: Serialize + Send + Sync>(
+            &self,
+            _path: &str,
+            _payload: &T,
+            _method: Method,
+            _response_expected: bool,
+        ) -> Result<Response, Error> {
+            Ok(Response::Empty)
+        }
+    }
+
+    #[tokio::test]
+    async fn listing_all_keys_ends_when_pagination_is_ignored() {
+        use futures::StreamExt;
+
+        for page_size in 1..=4 {
+            let keys: Result<Vec<String>, Error> = list_all(&UnpaginatedVault, "secret", page_size)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect();
+            let expected = ["a", "b", "c"].iter().take(page_size as usize);
+            assert_eq!(keys.unwrap(), expected.copied().collect::<Vec<_>>());
+        }
+    }
+
+    #[tokio::test]
+    async fn options_default_to_plain_requests() {
+        let options = RequestOptions::default();
+        let response: Response = EmptyVault
+            .read_with_options("secret/data", Method::GET, &options)
+            .await
+            .unwrap();
+        assert_eq!(response, Response::Empty);
+        let response = EmptyVault
+            .write_with_options("secret/data", &Empty, Method::POST, false, &options)
+            .await
+            .unwrap();
+        assert_eq!(response, Response::Empty);
+        let response: Response = EmptyVault
+            .write_raw("sys/generate-root/attempt", &Empty, Method::PUT, &options)
+            .await
+            .unwrap();
+        assert_eq!(response, Response::Empty);
+    }
+
+    #[test]
+    fn cluster_info_is_read_from_body_and_headers() {
+        let body = serde_json::json!({
+            "request_id": "id",
+            "lease_id": "",
+            "renewable": false,
+            "lease_duration": 0,
+            "data": {"key": "value"},
+            "cluster_name": "vault-cluster-a",
+        });
+        let mut response: ResponseData = serde_json::from_value(body).unwrap();
+        assert_eq!(
+            response.cluster.cluster_name.as_deref(),
+            Some("vault-cluster-a")
+        );
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        let _ = headers.insert("X-Vault-Raft-Node-ID", "node-1".parse().unwrap());
+        let _ = headers.insert("X-Vault-Hostname", "vault-0".parse().unwrap());
+        let _ = headers.insert("X-Vault-Cluster-Name", "ignored".parse().unwrap());
+        response.cluster.merge_headers(&headers);
+        assert_eq!(response.cluster.raft_node_id.as_deref(), Some("node-1"));
+        assert_eq!(response.cluster.hostname.as_deref(), Some("vault-0"));
+        assert_eq!(
+            response.cluster.cluster_name.as_deref(),
+            Some("vault-cluster-a")
+        );
+        assert!(response.cluster.cluster_id.is_none());
+        assert_eq!(response.data.unwrap()["key"], "value");
+    }
+
+    #[test]
+    fn clients_without_token_do_not_send_one() {
+        let client = Client::without_token(Some("http://127.0.0.1:8200"), None::<&str>).unwrap();
+        let request = client
+            .build_request("sys/seal-status", Method::GET)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(request.headers().get("X-Vault-Token").is_none());
+
+        let request = vault_client()
+            .build_request("sys/seal-status", Method::GET)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(request.headers().get("X-Vault-Token").is_some());
+    }
+
+    #[test]
+    fn large_requests_are_rejected() {
+        let request = reqwest::Client::new()
+            .post("http://127.0.0.1:8200/v1/secret/data/large")
+            .body("0123456789")
+            .build()
+            .unwrap();
+        assert!(SizeLimits::default().check_request(&request).is_ok());
+
+        let limits = SizeLimits {
+            max_request_bytes: Some(8),
+            ..Default::default()
+        };
+        match limits.check_request(&request) {
+            Err(Error::RequestTooLarge { size, limit }) => assert_eq!((size, limit), (10, 8)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cache_info_is_read_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(CacheInfo::from_headers(&headers), CacheInfo::default());
+
+        let _ = headers.insert("X-Cache", "HIT".parse().unwrap());
+        let _ = headers.insert("Age", "30".parse().unwrap());
+        let _ = headers.insert("Cache-Control", "private, max-age=60".parse().unwrap());
+        let cache = CacheInfo::from_headers(&headers);
+        assert!(cache.hit);
+        assert_eq!(cache.age, Some(Duration::from_secs(30)));
+        assert_eq!(cache.max_age(), Some(Duration::from_secs(60)));
+        assert!(cache.is_stale(Duration::from_secs(10)));
+        assert!(!cache.is_stale(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn wrap_ttl_is_rounded_up_to_seconds() {
+        assert_eq!(wrap_ttl_header(Duration::from_secs(60)).unwrap(), "60s");
+        assert_eq!(wrap_ttl_header(Duration::from_millis(500)).unwrap(), "1s");
+        assert_eq!(
+            wrap_ttl_header(Duration::from_millis(60_001)).unwrap(),
+            "61s"
+        );
+        assert!(matches!(
+            wrap_ttl_header(Duration::from_secs(0)),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn wrapped_responses_have_no_data() {
+        let response: Response = serde_json::from_value(serde_json::json!({
+            "request_id": "",
+            "lease_id": "",
+            "renewable": false,
+            "lease_duration": 0,
+            "data": null,
+            "wrap_info": {
+                "token": "hvs.wrapping",
+                "accessor": "accessor",
+                "ttl": 60,
+                "creation_time": "2024-01-01T00:00:00Z",
+                "creation_path": "auth/token/lookup-self"
+            }
+        }))
+        .unwrap();
+        match response.data::<serde_json::Value>() {
+            Err(Error::ResponseWrapped(wrapped)) => assert_eq!(wrapped.ttl, 60),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(matches!(response.auth(), Err(Error::ResponseWrapped(_))));
+        assert!(matches!(
+            Response::Empty.data::<serde_json::Value>(),
+            Err(Error::MissingData(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn can_wrap_responses() {
+        let client = vault_client();
+        let wrapped = client
+            .read_wrapped(
+                "auth/token/lookup-self",
+                Method::GET,
+                Duration::from_secs(60),
+            )
+            .await
+            .unwrap();
+        assert!(!wrapped.token.is_empty());
+        assert_eq!(wrapped.ttl, 60);
+        assert_eq!(wrapped.creation_path, "auth/token/lookup-self");
+
+        let wrapped = client
+            .write_wrapped(
+                "sys/wrapping/wrap",
+                &serde_json::json!({"secret": "value"}),
+                Method::POST,
+                Duration::from_millis(1500),
+            )
+            .await
+            .unwrap();
+        assert_eq!(wrapped.ttl, 2);
+
+        let options = RequestOptions {
+            wrap_ttl: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        match auth::Token::lookup_self(&client.with_options(options)).await {
+            Err(Error::ResponseWrapped(wrapped)) => {
+                assert_eq!(wrapped.creation_path, "auth/token/lookup-self")
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_revokes_token() {
+        let client = vault_client();
+        let token = auth::Token::create_token(&client, &Default::default())
+            .await
+            .unwrap();
+        let mut child = client.clone();
+        child.set_token(token.client_token.as_str());
+        child.shutdown(true).await.unwrap();
+
+        assert!(auth::Token::lookup(&client, &token.client_token)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn can_list_all_keys_in_pages() {
+        use futures::StreamExt;
+
+        let client = vault_client();
+        let engine = sys::mounts::SecretEngine {
+            path: uuid_prefix("kv"),
+            r#type: "kv".to_string(),
+            ..Default::default()
+        };
+        let mount = sys::mounts::tests::Mount::new(&client, &engine).await;
+        for key in &["a", "b", "c", "d", "e"] {
+            let _ = client
+                .post(
+                    &format!("{}/{}", mount.path, key),
+                    &serde_json::json!({"key": "value"}),
+                    false,
+                )
+                .await
+                .unwrap();
+        }
+
+        let path = mount.path.clone();
+        let keys: Result<Vec<String>, Error> = list_all(&client, &path, 2)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect();
+        assert_eq!(keys.unwrap(), vec!["a", "b", "c", "d", "e"]);
+
+        let path = format!("{}/missing", mount.path);
+        assert!(client
+            .list_page(&path, None, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn set_address_rejects_invalid_addresses() {
+        let mut client = vault_client();
+        assert!(client.set_address("not an address").is_err());
+        assert_eq!(client.address(), "http://127.0.0.1:8200");
+
+        client.set_address("http://127.0.0.1:8300").unwrap();
+        assert_eq!(client.address(), "http://127.0.0.1:8300");
+    }
+
     #[tokio::test]
     async fn can_list_kv() {
         let client = vault_client();
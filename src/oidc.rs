@@ -0,0 +1,259 @@
+//! OpenID Connect Discovery
+//!
+//! Vault publishes OpenID Connect discovery documents and JSON Web Key Sets for its
+//! [identity tokens](https://www.vaultproject.io/api-docs/secret/identity/tokens) and OIDC
+//! providers. These endpoints are unauthenticated, and the helpers here do not send the Vault
+//! token when fetching them.
+//!
+//! The `path` provided to the helpers is the issuer path without the `.well-known` suffix, like
+//! `identity/oidc` or `identity/oidc/provider/default`.
+use crate::{Error, Method, RequestOptions};
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// OpenID Provider Metadata returned from the discovery endpoint
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct OpenIdConfiguration {
+    /// Issuer of the tokens
+    pub issuer: String,
+    /// URL of the JSON Web Key Set used to verify token signatures
+    pub jwks_uri: String,
+    /// URL of the authorization endpoint, if any
+    #[serde(default)]
+    pub authorization_endpoint: Option<String>,
+    /// URL of the token endpoint, if any
+    #[serde(default)]
+    pub token_endpoint: Option<String>,
+    /// URL of the userinfo endpoint, if any
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    /// Supported response types
+    #[serde(default)]
+    pub response_types_supported: Vec<String>,
+    /// Supported subject identifier types
+    #[serde(default)]
+    pub subject_types_supported: Vec<String>,
+    /// Supported algorithms for signing ID tokens
+    #[serde(default)]
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    /// Supported scopes, if any
+    #[serde(default)]
+    pub scopes_supported: Option<Vec<String>>,
+}
+
+/// JSON Web Key Set
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct JsonWebKeySet {
+    /// List of keys
+    pub keys: Vec<JsonWebKey>,
+}
+
+impl JsonWebKeySet {
+    /// Find the key with the provided Key ID
+    pub fn key(&self, kid: &str) -> Option<&JsonWebKey> {
+        self.keys.iter().find(|key| key.kid == kid)
+    }
+}
+
+/// A JSON Web Key
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct JsonWebKey {
+    /// Key Type, like `RSA` or `EC`
+    pub kty: String,
+    /// Key ID
+    pub kid: String,
+    /// Algorithm intended for use with the key
+    #[serde(default)]
+    pub alg: Option<String>,
+    /// Intended use of the key
+    #[serde(default, rename = "use")]
+    pub r#use: Option<String>,
+    /// RSA modulus, base64url encoded
+    #[serde(default)]
+    pub n: Option<String>,
+    /// RSA public exponent, base64url encoded
+    #[serde(default)]
+    pub e: Option<String>,
+    /// Elliptic curve name
+    #[serde(default)]
+    pub crv: Option<String>,
+    /// Elliptic curve point X coordinate, base64url encoded
+    #[serde(default)]
+    pub x: Option<String>,
+    /// Elliptic curve point Y coordinate, base64url encoded
+    #[serde(default)]
+    pub y: Option<String>,
+}
+
+/// Unauthenticated OpenID Connect discovery endpoints
+#[async_trait]
+pub trait OidcDiscovery {
+    /// Fetch the OpenID Provider Metadata from `<path>/.well-known/openid-configuration`
+    async fn openid_configuration(&self, path: &str) -> Result<OpenIdConfiguration, Error>;
+    /// Fetch the JSON Web Key Set from `<path>/.well-known/keys`
+    async fn jwks(&self, path: &str) -> Result<JsonWebKeySet, Error>;
+}
+
+#[async_trait]
+impl<T> OidcDiscovery for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn openid_configuration(&self, path: &str) -> Result<OpenIdConfiguration, Error> {
+        let path = format!("{}/.well-known/openid-configuration", path);
+        self.read_with_options(&path, Method::GET, &unauthenticated())
+            .await
+    }
+
+    async fn jwks(&self, path: &str) -> Result<JsonWebKeySet, Error> {
+        let path = format!("{}/.well-known/keys", path);
+        self.read_with_options(&path, Method::GET, &unauthenticated())
+            .await
+    }
+}
+
+/// Read the Key ID from the header of a JWT, to select the key verifying its signature
+pub fn token_kid(jwt: &str) -> Result<String, Error> {
+    #[derive(Deserialize)]
+    struct Header {
+        kid: String,
+    }
+
+    let header = jwt
+        .split('.')
+        .next()
+        .ok_or_else(|| Error::InvalidRequest("token is not a JWT".to_string()))?;
+    let header = crate::crypto::decode_url_safe(header)?;
+    let header: Header = serde_json::from_slice(&header)?;
+    Ok(header.kid)
+}
+
+/// JSON Web Key Set of an issuer, fetched from Vault and cached for local validation of tokens.
+///
+/// The keys are fetched again once they are older than the refresh interval. Vault rotates its
+/// keys, so a token signed with a key that is not cached yet makes the keys be fetched again,
+/// at most once per minimum refresh interval to avoid flooding Vault with requests for tokens
+/// signed with unknown keys.
+#[derive(Debug)]
+pub struct CachedJwks<C> {
+    client: C,
+    path: String,
+    refresh_interval: Duration,
+    min_refresh_interval: Duration,
+    cached: Mutex<Option<(Instant, Arc<JsonWebKeySet>)>>,
+}
+
+impl<C> CachedJwks<C>
+where
+    C: crate::Vault + Send + Sync,
+{
+    /// Cache the keys of the issuer at `path`, like `identity/oidc`, for `refresh_interval`
+    pub fn new<S: Into<String>>(client: C, path: S, refresh_interval: Duration) -> Self {
+        Self {
+            client,
+            path: path.into(),
+            refresh_interval,
+            min_refresh_interval: Duration::from_secs(10),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Set the minimum interval between fetches of the keys caused by unknown Key IDs.
+    /// Defaults to 10 seconds.
+    pub fn with_min_refresh_interval(mut self, interval: Duration) -> Self {
+        self.min_refresh_interval = interval;
+        self
+    }
+
+    /// Returns the keys, fetching them if they are not cached or are older than the refresh
+    /// interval
+    pub async fn keys(&self) -> Result<Arc<JsonWebKeySet>, Error> {
+        match self.cached(self.refresh_interval) {
+            Some(keys) => Ok(keys),
+            None => self.refresh().await,
+        }
+    }
+
+    /// Returns the key with the Key ID, fetching the keys again if it is not cached
+    pub async fn key(&self, kid: &str) -> Result<Option<JsonWebKey>, Error> {
+        let keys = self.keys().await?;
+        if let Some(key) = keys.key(kid) {
+            return Ok(Some(key.clone()));
+        }
+        if self.cached(self.min_refresh_interval).is_some() {
+            return Ok(None);
+        }
+        Ok(self.refresh().await?.key(kid).cloned())
+    }
+
+    /// Returns the key verifying the signature of the JWT, according to its Key ID
+    pub async fn key_for_token(&self, jwt: &str) -> Result<Option<JsonWebKey>, Error> {
+        self.key(&token_kid(jwt)?).await
+    }
+
+    /// Fetch the keys, and cache them
+    pub async fn refresh(&self) -> Result<Arc<JsonWebKeySet>, Error> {
+        let keys = Arc::new(OidcDiscovery::jwks(&self.client, &self.path).await?);
+        *self.cached.lock().expect("Lock not to be poisoned") =
+            Some((Instant::now(), Arc::clone(&keys)));
+        Ok(keys)
+    }
+
+    /// Returns the keys cached, if they were fetched less than `max_age` ago
+    fn cached(&self, max_age: Duration) -> Option<Arc<JsonWebKeySet>> {
+        let cached = self.cached.lock().expect("Lock not to be poisoned");
+        match &*cached {
+            Some((fetched, keys)) if fetched.elapsed() < max_age => Some(Arc::clone(keys)),
+            _ => None,
+        }
+    }
+}
+
+fn unauthenticated() -> RequestOptions {
+    RequestOptions {
+        unauthenticated: true,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kid_is_read_from_token_header() {
+        let header = base64::encode_config(
+            r#"{"alg":"RS256","kid":"2b5f7c1e"}"#,
+            base64::URL_SAFE_NO_PAD,
+        );
+        let jwt = format!("{}.e30.c2ln", header);
+        assert_eq!(token_kid(&jwt).unwrap(), "2b5f7c1e");
+        assert!(token_kid("not a token").is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_cache_identity_token_keys() {
+        let client = crate::tests::vault_client();
+        let jwks = CachedJwks::new(&client, "identity/oidc", Duration::from_secs(300));
+
+        let keys = jwks.keys().await.unwrap();
+        assert!(Arc::ptr_eq(&keys, &jwks.keys().await.unwrap()));
+        assert!(jwks.key("unknown").await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_discover_identity_tokens_issuer() {
+        let client = crate::tests::vault_client();
+
+        let configuration = OidcDiscovery::openid_configuration(&client, "identity/oidc")
+            .await
+            .unwrap();
+        assert!(configuration.jwks_uri.ends_with("/.well-known/keys"));
+
+        let _ = OidcDiscovery::jwks(&client, "identity/oidc").await.unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! Validated paths of mounts and secrets
+//!
+//! A stray slash or `..` segment in a path silently results in a request to the wrong path.
+//! [`MountPath`] and [`SecretPath`] validate and normalize paths once, and dereference to `&str`
+//! so that they can be passed wherever a path is expected.
+//!
+//! The [`Mounts`](crate::sys::mounts::Mounts) and [`AuthMethods`](crate::sys::auth::AuthMethods)
+//! traits validate the paths of mounts they are given. The traits of secrets engines and auth
+//! methods still take `&str`, and format them into request paths as-is: string paths are not
+//! deprecated, as the mount and the path of a secret are given to them as a single string.
+//! Build those with [`MountPath::join`] to have them validated.
+use crate::Error;
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Normalize the path, and validate its segments
+fn normalize(path: &str) -> Result<String, Error> {
+    let path = path.trim_matches('/');
+    for segment in path.split('/') {
+        if path.is_empty() {
+            break;
+        }
+        let invalid = match segment {
+            "" => Some("empty segment"),
+            "." | ".." => Some("relative segment"),
+            s if s.chars().any(char::is_control) => Some("control character"),
+            s if s.contains(['?', '#']) => Some("query or fragment character"),
+            _ => None,
+        };
+        if let Some(reason) = invalid {
+            return Err(Error::InvalidRequest(format!(
+                "invalid path {:?}: {}",
+                path, reason
+            )));
+        }
+    }
+    Ok(path.to_string())
+}
+
+macro_rules! path_type {
+    ($name:ident) => {
+        impl $name {
+            /// Returns the path as a string slice
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(path: &str) -> Result<Self, Error> {
+                Self::new(path)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = Error;
+
+            fn try_from(path: &str) -> Result<Self, Error> {
+                Self::new(path)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(path: String) -> Result<Self, Error> {
+                Self::new(&path)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(path: $name) -> String {
+                path.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let path = String::deserialize(deserializer)?;
+                Self::new(&path).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+/// Path of a secrets engine or auth method mount, without leading or trailing slashes
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MountPath(String);
+
+impl MountPath {
+    /// Validate and normalize the path of a mount, like `secret/` or `/auth/approle`
+    pub fn new(path: &str) -> Result<Self, Error> {
+        let path = normalize(path)?;
+        if path.is_empty() {
+            return Err(Error::InvalidRequest(
+                "the path of a mount cannot be empty".to_string(),
+            ));
+        }
+        Ok(MountPath(path))
+    }
+
+    /// Path of the secret relative to the mount
+    pub fn join(&self, secret: &SecretPath) -> String {
+        if secret.is_empty() {
+            self.0.clone()
+        } else {
+            format!("{}/{}", self.0, secret.0)
+        }
+    }
+}
+
+path_type!(MountPath);
+
+/// Path of a secret relative to its mount, without leading or trailing slashes. The root of the
+/// mount is the empty path.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+pub struct SecretPath(String);
+
+impl SecretPath {
+    /// Validate and normalize the path of a secret, like `database/primary`
+    pub fn new(path: &str) -> Result<Self, Error> {
+        Ok(SecretPath(normalize(path)?))
+    }
+
+    /// The root of the mount
+    pub fn root() -> Self {
+        Default::default()
+    }
+}
+
+path_type!(SecretPath);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(MountPath::new("/secret/").unwrap().as_str(), "secret");
+        assert_eq!(
+            MountPath::new("auth/approle").unwrap().as_str(),
+            "auth/approle"
+        );
+        assert_eq!(SecretPath::new("/").unwrap(), SecretPath::root());
+
+        let mount = MountPath::new("secret/").unwrap();
+        let secret = SecretPath::new("database/primary/").unwrap();
+        assert_eq!(mount.join(&secret), "secret/database/primary");
+        assert_eq!(mount.join(&SecretPath::root()), "secret");
+    }
+
+    #[test]
+    fn invalid_paths_are_rejected() {
+        assert!(MountPath::new("/").is_err());
+        assert!(MountPath::new("secret//database").is_err());
+        assert!(SecretPath::new("database/../other").is_err());
+        assert!(SecretPath::new("database?version=1").is_err());
+        assert!(SecretPath::new("database\nprimary").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Pool of child tokens, one per tenant
+//!
+//! Job runners and multi-tenant services often need a distinct Vault identity for each tenant or
+//! task, so that the audit log attributes requests to them, and a compromised task only exposes
+//! its own secrets. [`TokenPool`] creates a child token for each tenant from a token role, with
+//! the token of its client as the parent, renews the tokens before they expire, and revokes them
+//! when they are released.
+//!
+//! The pool does not run any background task, so renewal is lazy. Tokens are renewed when they
+//! are acquired close to their expiry, and **tokens of idle tenants expire** unless callers
+//! call [`TokenPool::renew_expiring`] periodically, for example from a task of their runtime.
+use crate::auth::token::{CreateTokenRequest, Token};
+use crate::{Authentication, Error};
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use log::{debug, warn};
+
+/// Metadata key holding the tenant of the tokens created by a pool
+pub const TENANT_METADATA: &str = "tenant";
+
+/// Token of a tenant, with its expiry
+#[derive(Debug, Clone)]
+struct PooledToken {
+    auth: Authentication,
+    expires: Option<Instant>,
+}
+
+impl PooledToken {
+    fn new(auth: Authentication) -> Self {
+        let expires = match auth.lease_duration {
+            0 => None,
+            seconds => Some(Instant::now() + Duration::from_secs(seconds)),
+        };
+        Self { auth, expires }
+    }
+
+    fn expires_within(&self, duration: Duration) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= Instant::now() + duration)
+    }
+}
+
+/// Child tokens created from a token role, one per tenant
+pub struct TokenPool<C> {
+    client: C,
+    role: String,
+    request: CreateTokenRequest,
+    increment: Option<String>,
+    renew_before: Duration,
+    concurrency: usize,
+    tokens: Mutex<HashMap<String, PooledToken>>,
+}
+
+impl<C> std::fmt::Debug for TokenPool<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenPool")
+            .field("role", &self.role)
+            .field("tenants", &self.tenants())
+            .finish()
+    }
+}
+
+impl<C> TokenPool<C> {
+    /// Tenants holding a token
+    pub fn tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self.lock().keys().cloned().collect();
+        tenants.sort();
+        tenants
+    }
+
+    /// Number of tokens held
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no token is held
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, PooledToken>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<C> TokenPool<C>
+where
+    C: crate::Vault + Send + Sync,
+{
+    /// Create tokens against the token role `role`, with the token of `client` as their parent
+    pub fn new<S: Into<String>>(client: C, role: S) -> Self {
+        Self {
+            client,
+            role: role.into(),
+            request: Default::default(),
+            increment: None,
+            renew_before: Duration::from_secs(60),
+            concurrency: 8,
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the parameters of the tokens created. The tenant is added to their metadata, under
+    /// [`TENANT_METADATA`].
+    pub fn with_request(mut self, request: CreateTokenRequest) -> Self {
+        self.request = request;
+        self
+    }
+
+    /// Set the increment, like "1h", requested when renewing tokens
+    pub fn with_increment<S: Into<String>>(mut self, increment: S) -> Self {
+        self.increment = Some(increment.into());
+        self
+    }
+
+    /// Set how long before their expiry tokens are renewed when they are acquired.
+    /// Defaults to 60 seconds.
+    pub fn with_renew_before(mut self, duration: Duration) -> Self {
+        self.renew_before = duration;
+        self
+    }
+
+    /// Set the maximum number of requests made concurrently when renewing or releasing many
+    /// tokens. Defaults to 8.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the token of the tenant, creating it if the tenant has none, and renewing it if it
+    /// is about to expire.
+    ///
+    /// Tokens which cannot be renewed are replaced by a new token. Concurrent acquisitions for a
+    /// tenant without a token all return the same token.
+    ///
+    /// Tokens are only renewed when they are acquired: a token which is not acquired, or
+    /// renewed with [`TokenPool::renew_expiring`], before it expires is lost, and replaced by a
+    /// new token on its next acquisition.
+    pub async fn acquire(&self, tenant: &str) -> Result<Authentication, Error> {
+        let existing = self.lock().get(tenant).cloned();
+        if let Some(token) = existing {
+            if !token.expires_within(self.renew_before) {
+                return Ok(token.auth);
+            }
+            if let Some(token) = self.renew(tenant, &token).await {
+                return Ok(token.auth);
+            }
+        }
+
+        let token = self.create(tenant).await?;
+        // Another acquisition may have created a token for the tenant concurrently. The first
+        // token stored is kept, since it may already be in use, and the other one is revoked.
+        let winner = match self.lock().entry(tenant.to_string()) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => return Ok(entry.insert(token).auth.clone()),
+        };
+        debug!("Token for tenant {} created concurrently", tenant);
+        self.revoke(&token).await;
+        Ok(winner.auth)
+    }
+
+    /// Revoke the token of the tenant, and remove it from the pool.
+    ///
+    /// Returns whether the tenant held a token.
+    pub async fn release(&self, tenant: &str) -> Result<bool, Error> {
+        let token = match self.lock().remove(tenant) {
+            Some(token) => token,
+            None => return Ok(false),
+        };
+        self.revoke_token(&token).await?;
+        Ok(true)
+    }
+
+    /// Revoke the tokens of all tenants, and empty the pool.
+    ///
+    /// Every token is revoked even if some revocations fail. The first error is returned.
+    pub async fn release_all(&self) -> Result<(), Error> {
+        let tokens: Vec<PooledToken> = self.lock().drain().map(|(_, token)| token).collect();
+        let revocations: Vec<_> = tokens
+            .iter()
+            .map(|token| self.revoke_token(token))
+            .collect();
+        let results: Vec<Result<(), Error>> = stream::iter(revocations)
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        results.into_iter().collect()
+    }
+
+    /// Renew the tokens expiring within `duration`, returning the number of tokens renewed.
+    ///
+    /// Tokens which cannot be renewed are removed from the pool, and revoked. Their tenants get
+    /// a new token when they next acquire one.
+    pub async fn renew_expiring(&self, duration: Duration) -> usize {
+        let expiring: Vec<(String, PooledToken)> = self
+            .lock()
+            .iter()
+            .filter(|(_, token)| token.expires_within(duration))
+            .map(|(tenant, token)| (tenant.clone(), token.clone()))
+            .collect();
+        let renewals: Vec<_> = expiring
+            .iter()
+            .map(|(tenant, token)| self.renew(tenant, token))
+            .collect();
+        stream::iter(renewals)
+            .buffer_unordered(self.concurrency)
+            .filter(|renewed| futures::future::ready(renewed.is_some()))
+            .count()
+            .await
+    }
+
+    async fn create(&self, tenant: &str) -> Result<PooledToken, Error> {
+        let mut meta = self.request.meta.clone().unwrap_or_default();
+        let _ = meta.insert(TENANT_METADATA.to_string(), tenant.to_string());
+        let request = CreateTokenRequest {
+            meta: Some(meta),
+            ..self.request.clone()
+        };
+        debug!("Creating token for tenant {}", tenant);
+        let auth = self
+            .client
+            .create_token_with_role(&self.role, &request)
+            .await?;
+        Ok(PooledToken::new(auth))
+    }
+
+    /// Renew the token of the tenant, updating the pool. Tokens which cannot be renewed are
+    /// removed from the pool and revoked.
+    async fn renew(&self, tenant: &str, token: &PooledToken) -> Option<PooledToken> {
+        let renewed = if token.auth.is_renewable() {
+            self.client
+                .renew_accessor(&token.auth.accessor, self.increment.as_deref())
+                .await
+                .map_err(|e| warn!("Failed to renew token of tenant {}: {}", tenant, e))
+                .ok()
+        } else {
+            None
+        };
+
+        let renewed = renewed.map(|auth| {
+            PooledToken::new(Authentication {
+                client_token: token.auth.client_token.clone(),
+                ..auth
+            })
+        });
+        {
+            let mut tokens = self.lock();
+            // The token may have been released or replaced while it was renewed
+            let current = tokens
+                .get(tenant)
+                .is_some_and(|current| current.auth.accessor == token.auth.accessor);
+            if current {
+                let _ = match &renewed {
+                    Some(renewed) => tokens.insert(tenant.to_string(), renewed.clone()),
+                    None => tokens.remove(tenant),
+                };
+            }
+        }
+        if renewed.is_none() {
+            self.revoke(token).await;
+        }
+        renewed
+    }
+
+    /// Revoke a token which is no longer used, logging failures
+    async fn revoke(&self, token: &PooledToken) {
+        if let Err(e) = self.revoke_token(token).await {
+            warn!("Failed to revoke token {}: {}", token.auth.accessor, e);
+        }
+    }
+
+    async fn revoke_token(&self, token: &PooledToken) -> Result<(), Error> {
+        // Batch tokens cannot be revoked, and expire with their TTL
+        if token.auth.is_batch() {
+            return Ok(());
+        }
+        let _ = self.client.revoke_accessor(&token.auth.accessor).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::token::TokenRole;
+    use crate::tests::{uuid_prefix, vault_client};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_pool_tokens_per_tenant() {
+        let client = vault_client();
+        let role = uuid_prefix("pool");
+        let _ = Token::create_role(&client, &role, &TokenRole::default())
+            .await
+            .unwrap();
+
+        let pool = TokenPool::new(&client, role.as_str()).with_renew_before(Duration::from_secs(0));
+        let first = pool.acquire("first").await.unwrap();
+        let second = pool.acquire("second").await.unwrap();
+        assert_ne!(first.client_token, second.client_token);
+        assert_eq!(first.metadata[TENANT_METADATA], "first");
+        assert_eq!(
+            pool.acquire("first").await.unwrap().client_token,
+            first.client_token
+        );
+        assert_eq!(pool.tenants(), ["first", "second"]);
+
+        let (third, again) = futures::join!(pool.acquire("third"), pool.acquire("third"));
+        let third = third.unwrap();
+        assert_eq!(third.client_token, again.unwrap().client_token);
+        assert!(Token::lookup_accessor(&client, &third.accessor)
+            .await
+            .is_ok());
+        assert!(pool.release("third").await.unwrap());
+
+        assert!(pool.release("first").await.unwrap());
+        assert!(!pool.release("first").await.unwrap());
+        assert!(Token::lookup_accessor(&client, &first.accessor)
+            .await
+            .is_err());
+
+        pool.release_all().await.unwrap();
+        assert!(pool.is_empty());
+        assert!(Token::lookup_accessor(&client, &second.accessor)
+            .await
+            .is_err());
+
+        let _ = Token::delete_role(&client, &role).await.unwrap();
+    }
+}
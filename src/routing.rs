@@ -0,0 +1,155 @@
+//! Routing of requests to the nodes of a High Availability cluster
+//!
+//! By default, all requests are sent to the address the [`Client`](crate::Client) was created
+//! with, and standby nodes forward requests to the active node. In large clusters, the extra hop
+//! adds latency. With [`Routing::HighAvailability`], reads are spread across performance
+//! standby nodes, while writes are always sent directly to the active node.
+use crate::sys::health::HealthStatus;
+use crate::sys::Leader;
+use crate::{Client, Error, Method};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use log::{debug, info};
+
+/// How requests are routed to the nodes of a cluster
+#[derive(Clone, Debug, Default)]
+pub enum Routing {
+    /// Send all requests to the address of the client
+    #[default]
+    Single,
+    /// Send writes to the active node, and spread reads across the standby nodes
+    HighAvailability {
+        /// API address of the active node
+        active: String,
+        /// API addresses of the standby nodes that can serve reads. If empty, reads are sent to
+        /// the active node.
+        standbys: Vec<String>,
+        /// Index of the next standby to send a read to
+        next: Arc<AtomicUsize>,
+    },
+}
+
+impl Routing {
+    /// Route requests between the active node and the standby nodes that can serve reads
+    pub fn high_availability<S: AsRef<str>>(active: &str, standbys: &[S]) -> Self {
+        Routing::HighAvailability {
+            active: active.to_string(),
+            standbys: standbys.iter().map(|s| s.as_ref().to_string()).collect(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl Client {
+    /// Returns how requests are currently routed
+    pub fn routing(&self) -> &Routing {
+        &self.routing
+    }
+
+    /// Set how requests are routed
+    pub fn set_routing(&mut self, routing: Routing) {
+        self.routing = routing;
+    }
+
+    /// Discover the active node and the standbys that can serve reads among `nodes`, and route
+    /// requests accordingly.
+    ///
+    /// The active node is looked up from `sys/leader` using the address of the client. Each of
+    /// the `nodes` is then health checked, and performance standbys, reporting
+    /// [`HealthStatus::PerformanceStandby`], are used for reads. Regular standbys forward every
+    /// request to the active node, and are not used. If High Availability is not enabled,
+    /// requests are routed to the address of the client.
+    pub async fn discover_routing<S: AsRef<str>>(
+        &mut self,
+        nodes: &[S],
+    ) -> Result<&Routing, Error> {
+        let leader = Leader::leader(&*self).await?;
+        if !leader.ha_enabled {
+            info!("High Availability is not enabled. Routing all requests to the client address");
+            self.routing = Routing::Single;
+            return Ok(&self.routing);
+        }
+
+        let active = leader.leader_address.trim_end_matches('/').to_string();
+        let mut standbys = vec![];
+        for node in nodes {
+            let node = node.as_ref().trim_end_matches('/');
+            if node == active {
+                continue;
+            }
+
+            match self.node_health(node).await {
+                Ok(HealthStatus::PerformanceStandby) => standbys.push(node.to_string()),
+                Ok(status) => debug!("Not routing reads to {}: {:?}", node, status),
+                Err(e) => debug!("Not routing reads to {}: {}", node, e),
+            }
+        }
+
+        info!("Routing writes to {} and reads to {:?}", active, standbys);
+        self.routing = Routing::high_availability(&active, &standbys);
+        Ok(&self.routing)
+    }
+
+    async fn node_health(&self, node: &str) -> Result<HealthStatus, Error> {
+        let mut health = self.clone();
+        health.address = node.to_string();
+        health.routing = Routing::Single;
+        // The clone must not revoke the token it shares with this client
+        health.revoke_self_on_drop = false;
+        Ok(health.health().await?.status)
+    }
+
+    /// Address to send a request with the method to
+    pub(crate) fn address_for(&self, method: &Method) -> &str {
+        match &self.routing {
+            Routing::Single => &self.address,
+            Routing::HighAvailability {
+                active,
+                standbys,
+                next,
+            } => {
+                let is_read =
+                    *method == Method::GET || *method == Method::HEAD || method.as_str() == "LIST";
+                if !is_read || standbys.is_empty() {
+                    return active;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed) % standbys.len();
+                &standbys[index]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_writes_to_active_and_reads_to_standbys() {
+        let mut client = crate::tests::vault_client();
+        assert_eq!(client.address_for(&Method::POST), "http://127.0.0.1:8200");
+
+        client.set_routing(Routing::high_availability(
+            "http://active:8200",
+            &["http://standby-1:8200", "http://standby-2:8200"],
+        ));
+        assert_eq!(client.address_for(&Method::POST), "http://active:8200");
+        assert_eq!(client.address_for(&Method::DELETE), "http://active:8200");
+        assert_eq!(client.address_for(&Method::GET), "http://standby-1:8200");
+        let list = Method::from_bytes(b"LIST").unwrap();
+        assert_eq!(client.address_for(&list), "http://standby-2:8200");
+        assert_eq!(client.address_for(&Method::GET), "http://standby-1:8200");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn routes_to_client_address_without_high_availability() {
+        let mut client = crate::tests::vault_client();
+        let routing = client
+            .discover_routing(&["http://127.0.0.1:8200"])
+            .await
+            .unwrap();
+        assert!(matches!(routing, Routing::Single));
+    }
+}
@@ -0,0 +1,507 @@
+//! Validation of secrets against a schema
+//!
+//! [`SchemaValidator`] wraps any [`Vault`](crate::Vault) implementation, and checks the shape
+//! of secrets written to and read from the paths that have a [`Schema`] registered. A secret
+//! that does not match its schema results in an [`Error::SchemaViolation`] listing the missing
+//! and unexpected fields. Writes are rejected before they are sent to Vault.
+//!
+//! `PATCH` writes only carry the fields being changed, and are checked with
+//! [`Schema::check_patch`].
+//!
+//! Because the engine traits are implemented for every `Vault`, the validation applies to all
+//! of them when used through the validator.
+use crate::{Error, Method, RequestOptions, Response};
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Expected shape of a secret
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Schema {
+    required: BTreeSet<String>,
+    optional: BTreeSet<String>,
+    allow_extra: bool,
+}
+
+impl Schema {
+    /// Create an empty schema
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a schema requiring the fields of the struct `T`.
+    ///
+    /// Fields that are optional should be marked with [`Schema::optional`]. Structs with
+    /// flattened fields cannot be introspected, and result in an empty schema.
+    pub fn of<T: DeserializeOwned>() -> Self {
+        let mut introspector = Introspector::default();
+        let _ = T::deserialize(&mut introspector);
+        introspector
+            .fields
+            .iter()
+            .fold(Self::new(), |schema, field| schema.required(*field))
+    }
+
+    /// Require the field to be present
+    pub fn required<S: Into<String>>(mut self, field: S) -> Self {
+        let field = field.into();
+        let _ = self.optional.remove(&field);
+        let _ = self.required.insert(field);
+        self
+    }
+
+    /// Allow the field to be present
+    pub fn optional<S: Into<String>>(mut self, field: S) -> Self {
+        let field = field.into();
+        let _ = self.required.remove(&field);
+        let _ = self.optional.insert(field);
+        self
+    }
+
+    /// Allow fields that are neither required nor optional to be present
+    pub fn allow_extra(mut self, allow: bool) -> Self {
+        self.allow_extra = allow;
+        self
+    }
+
+    /// Check that the secret at `path` matches the schema
+    pub fn check(&self, path: &str, secret: &Value) -> Result<(), Error> {
+        self.check_fields(path, secret, false)
+    }
+
+    /// Check that a JSON merge patch of the secret at `path` keeps it matching the schema. Only
+    /// fields that are not in the schema, and required fields removed by setting them to
+    /// `null`, are violations.
+    pub fn check_patch(&self, path: &str, patch: &Value) -> Result<(), Error> {
+        self.check_fields(path, patch, true)
+    }
+
+    fn check_fields(&self, path: &str, secret: &Value, patch: bool) -> Result<(), Error> {
+        let fields: BTreeSet<&str> = match secret {
+            Value::Object(map) => map.keys().map(String::as_str).collect(),
+            _ => BTreeSet::new(),
+        };
+
+        let missing: Vec<String> = self
+            .required
+            .iter()
+            .filter(|field| {
+                if patch {
+                    secret.get(field.as_str()).is_some_and(Value::is_null)
+                } else {
+                    !fields.contains(field.as_str())
+                }
+            })
+            .cloned()
+            .collect();
+        let extra: Vec<String> = if self.allow_extra {
+            vec![]
+        } else {
+            fields
+                .iter()
+                .filter(|field| {
+                    !self.required.contains(**field) && !self.optional.contains(**field)
+                })
+                .map(|field| (*field).to_string())
+                .collect()
+        };
+
+        if missing.is_empty() && extra.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::SchemaViolation {
+                path: path.to_string(),
+                missing,
+                extra,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Registration {
+    pattern: String,
+    schema: Schema,
+    // Secret is nested under a `data` key, like in the KV v2 engine
+    nested: bool,
+}
+
+impl Registration {
+    fn matches(&self, path: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.pattern,
+        }
+    }
+
+    fn check(&self, path: &str, value: &Value, patch: bool) -> Result<(), Error> {
+        let secret = if self.nested {
+            value.get("data").unwrap_or(&Value::Null)
+        } else {
+            value
+        };
+        self.schema.check_fields(path, secret, patch)
+    }
+}
+
+/// Wraps a [`Vault`](crate::Vault) implementation, validating secrets against the schemas
+/// registered for their paths
+#[derive(Debug, Clone)]
+pub struct SchemaValidator<C> {
+    inner: C,
+    registrations: Vec<Registration>,
+}
+
+impl<C> SchemaValidator<C> {
+    /// Wrap the client, without any schema registered
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            registrations: vec![],
+        }
+    }
+
+    /// Register the schema for secrets at `path`, like `secret/database`. Paths ending with
+    /// `*` match every path with the same prefix.
+    ///
+    /// When several registered paths match, the one registered first is used.
+    pub fn register(&mut self, path: &str, schema: Schema) -> &mut Self {
+        self.registrations.push(Registration {
+            pattern: path.trim_start_matches('/').to_string(),
+            schema,
+            nested: false,
+        });
+        self
+    }
+
+    /// Register the schema for secrets at `path` in the KV v2 engine mounted at `mount`.
+    ///
+    /// KV v2 secrets are read from and written to `<mount>/data/<path>`, nested under a `data`
+    /// key.
+    pub fn register_kv2(&mut self, mount: &str, path: &str, schema: Schema) -> &mut Self {
+        self.registrations.push(Registration {
+            pattern: format!(
+                "{}/data/{}",
+                mount.trim_matches('/'),
+                path.trim_start_matches('/')
+            ),
+            schema,
+            nested: true,
+        });
+        self
+    }
+
+    /// Returns the wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Unwrap the client
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn registration(&self, path: &str, method: &Method) -> Option<&Registration> {
+        if method.as_str() == "LIST" || *method == Method::DELETE {
+            return None;
+        }
+        let path = path.trim_start_matches('/');
+        self.registrations.iter().find(|r| r.matches(path))
+    }
+
+    fn check_write<T: Serialize>(
+        &self,
+        path: &str,
+        method: &Method,
+        payload: &T,
+    ) -> Result<(), Error> {
+        match self.registration(path, method) {
+            Some(registration) => registration.check(
+                path,
+                &serde_json::to_value(payload)?,
+                *method == Method::PATCH,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn check_response(
+        &self,
+        path: &str,
+        method: &Method,
+        response: &Response,
+    ) -> Result<(), Error> {
+        match (self.registration(path, method), response) {
+            (Some(registration), Response::Response(data)) => match &data.data {
+                Some(value) => registration.check(path, value, false),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<C> crate::Vault for SchemaValidator<C>
+where
+    C: crate::Vault + Send + Sync,
+{
+    async fn read(&self, path: &str, method: Method) -> Result<Response, Error> {
+        let response = self.inner.read(path, method.clone()).await?;
+        self.check_response(path, &method, &response)?;
+        Ok(response)
+    }
+
+    async fn read_with_query<T: Serialize + Send + Sync + ?Sized>(
+        &self,
+        path: &str,
+        method: Method,
+        query: &T,
+    ) -> Result<Response, Error> {
+        let response = self
+            .inner
+            .read_with_query(path, method.clone(), query)
+            .await?;
+        self.check_response(path, &method, &response)?;
+        Ok(response)
+    }
+
+    async fn read_with_options<R>(
+        &self,
+        path: &str,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned + Debug + Send,
+    {
+        if self.registration(path, &method).is_none() {
+            return self.inner.read_with_options(path, method, options).await;
+        }
+
+        let value: Value = self
+            .inner
+            .read_with_options(path, method.clone(), options)
+            .await?;
+        if let Ok(response) = serde_json::from_value::<Response>(value.clone()) {
+            self.check_response(path, &method, &response)?;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+    ) -> Result<Response, Error> {
+        self.check_write(path, &method, payload)?;
+        self.inner
+            .write(path, payload, method, response_expected)
+            .await
+    }
+
+    async fn write_with_options<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        response_expected: bool,
+        options: &RequestOptions,
+    ) -> Result<Response, Error> {
+        self.check_write(path, &method, payload)?;
+        self.inner
+            .write_with_options(path, payload, method, response_expected, options)
+            .await
+    }
+
+    async fn write_raw<T, R>(
+        &self,
+        path: &str,
+        payload: &T,
+        method: Method,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        T: Serialize + Send + Sync,
+        R: DeserializeOwned + Debug + Send,
+    {
+        self.check_write(path, &method, payload)?;
+        self.inner.write_raw(path, payload, method, options).await
+    }
+}
+
+/// Deserializer that records the field names of the struct being deserialized
+#[derive(Default)]
+struct Introspector {
+    fields: &'static [&'static str],
+}
+
+impl<'de> Deserializer<'de> for &mut Introspector {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.fields = fields;
+        Err(de::Error::custom("introspected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::mounts::tests::Mount;
+    use crate::Vault;
+
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Database {
+        username: String,
+        password: String,
+        port: Option<u16>,
+    }
+
+    #[test]
+    fn schema_of_struct_requires_its_fields() {
+        let schema = Schema::of::<Database>().optional("port");
+        let expected = Schema::new()
+            .required("username")
+            .required("password")
+            .optional("port");
+        assert_eq!(schema, expected);
+    }
+
+    #[test]
+    fn check_lists_missing_and_extra_fields() {
+        let schema = Schema::of::<Database>().optional("port");
+        schema
+            .check("db", &json!({"username": "a", "password": "b"}))
+            .unwrap();
+
+        match schema.check("db", &json!({"username": "a", "host": "b"})) {
+            Err(Error::SchemaViolation {
+                path,
+                missing,
+                extra,
+            }) => {
+                assert_eq!(path, "db");
+                assert_eq!(missing, vec!["password"]);
+                assert_eq!(extra, vec!["host"]);
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+
+        let schema = schema.allow_extra(true);
+        assert!(schema
+            .check(
+                "db",
+                &json!({"username": "a", "password": "b", "host": "c"})
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn patches_are_checked_for_extra_and_removed_fields() {
+        let schema = Schema::of::<Database>().optional("port");
+        schema.check_patch("db", &json!({"password": "c"})).unwrap();
+        schema.check_patch("db", &json!({"port": null})).unwrap();
+
+        match schema.check_patch("db", &json!({"password": null, "host": "b"})) {
+            Err(Error::SchemaViolation { missing, extra, .. }) => {
+                assert_eq!(missing, vec!["password"]);
+                assert_eq!(extra, vec!["host"]);
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn patches_are_not_checked_as_whole_secrets() {
+        // Nothing listens on the port: writes passing validation fail to connect
+        let client = crate::Client::from_environment(
+            Some("http://127.0.0.1:9"),
+            Some("token"),
+            None::<&str>,
+        )
+        .unwrap();
+        let mut validator = SchemaValidator::new(client);
+        let _ = validator.register_kv2("secret", "database", Schema::of::<Database>());
+
+        let patch = json!({"data": {"password": "c"}});
+        let result = validator
+            .write("secret/data/database", &patch, Method::PATCH, false)
+            .await;
+        assert!(matches!(result, Err(Error::ReqwestError(_))));
+
+        let result = validator
+            .write("secret/data/database", &patch, Method::POST, false)
+            .await;
+        assert!(matches!(result, Err(Error::SchemaViolation { .. })));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn validates_writes_and_reads() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("kv");
+        let engine = crate::sys::mounts::SecretEngine {
+            path: path.clone(),
+            r#type: "kv".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+
+        let mut validator = SchemaValidator::new(client.clone());
+        let _ = validator.register(
+            &format!("{}/database/*", mount.path),
+            Schema::of::<Database>().optional("port"),
+        );
+
+        let secret_path = format!("{}/database/primary", mount.path);
+        let result = validator
+            .post(&secret_path, &json!({"username": "a"}), false)
+            .await;
+        assert!(matches!(result, Err(Error::SchemaViolation { .. })));
+
+        let _ = validator
+            .post(
+                &secret_path,
+                &json!({"username": "a", "password": "b"}),
+                false,
+            )
+            .await
+            .unwrap();
+        let _ = validator.get(&secret_path).await.unwrap();
+
+        // Secrets written without validation are rejected on read
+        let _ = client
+            .post(&secret_path, &json!({"username": "a"}), false)
+            .await
+            .unwrap();
+        let result = validator.get(&secret_path).await;
+        assert!(matches!(result, Err(Error::SchemaViolation { .. })));
+    }
+}
@@ -1,7 +1,9 @@
 //! AWS Secrets Engine
 //!
 //! See the [documentation](https://www.vaultproject.io/api/secret/aws/index.html).
-use crate::{Error, LeasedData, Response};
+use crate::{Error, Keys, LeasedData, Response};
+
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use reqwest::Method;
@@ -41,9 +43,54 @@ pub struct Lease {
     pub lease_max: String,
 }
 
+/// Type of credentials generated for a role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialType {
+    /// IAM user, with an access key. Credentials are deleted when their lease is revoked.
+    IamUser,
+    /// Credentials of an assumed role, from STS `AssumeRole`
+    AssumedRole,
+    /// Federation token, from STS `GetFederationToken`
+    FederationToken,
+    /// Session token, from STS `GetSessionToken`
+    SessionToken,
+}
+
 /// AWS Secrets Engine Role
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default)]
-pub struct Role {}
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Role {
+    /// Type of credentials generated for the role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_type: Option<CredentialType>,
+    /// IAM policy document, in JSON. Used as an inline policy of IAM users, or to scope down
+    /// the permissions of STS credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_document: Option<String>,
+    /// ARNs of the AWS managed policies attached to IAM users, or scoping down the permissions
+    /// of STS credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_arns: Option<Vec<String>>,
+    /// ARNs of the AWS roles allowed to be assumed, for `assumed_role` credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_arns: Option<Vec<String>>,
+    /// Names of the IAM groups that IAM users are added to, or whose policies scope down the
+    /// permissions of STS credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iam_groups: Option<Vec<String>>,
+    /// Default TTL of STS credentials, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_sts_ttl: Option<u64>,
+    /// Maximum TTL of STS credentials, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sts_ttl: Option<u64>,
+    /// Path of IAM users, for `iam_user` credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_path: Option<String>,
+    /// ARN of the permissions boundary of IAM users, for `iam_user` credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions_boundary_arn: Option<String>,
+}
 
 /// Request to Generate Credentials
 #[derive(Serialize, Debug, Eq, PartialEq, Default)]
@@ -57,6 +104,10 @@ pub struct CredentialsRequest {
     /// duration suffix. Valid only when credential_type is assumed_role or federation_token.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl: Option<String>,
+    /// Name of the session of the assumed role, shown in CloudTrail. Valid only when
+    /// credential_type is assumed_role. Vault generates a name if it is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_session_name: Option<String>,
 }
 
 /// Credentials Returned from Vault
@@ -69,6 +120,41 @@ pub struct Credentials {
     /// AWS Security Token, if any
     #[serde(default)]
     pub security_token: Option<String>,
+    /// ARN of the IAM user or assumed role, if returned by Vault
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arn: Option<String>,
+    /// Time at which STS credentials expire, in RFC 3339 format, if returned by Vault
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<String>,
+    /// TTL of STS credentials, in seconds, if returned by Vault
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+impl Credentials {
+    /// Time at which the credentials expire, if Vault returned it
+    pub fn expiration_time(&self) -> Option<SystemTime> {
+        self.expiration
+            .as_deref()
+            .and_then(crate::utils::parse_rfc3339)
+    }
+}
+
+impl LeasedData<Credentials> {
+    /// Time at which the credentials expire, and need to be refreshed.
+    ///
+    /// This is the expiration returned by Vault for STS credentials, or else the end of the
+    /// lease, counted from `received`, the time at which the credentials were received.
+    pub fn expires_at(&self, received: SystemTime) -> SystemTime {
+        if let Some(expiration) = self.data.expiration_time() {
+            return expiration;
+        }
+        let duration = match (self.lease_duration, self.data.ttl) {
+            (0, Some(ttl)) => ttl,
+            (lease_duration, _) => lease_duration,
+        };
+        received + Duration::from_secs(duration)
+    }
 }
 
 /// AWS Secrets Engine
@@ -89,24 +175,45 @@ pub trait Aws {
     /// Reads the Lease for the AWS Secrets Engine
     async fn read_lease(&self, path: &str) -> Result<Lease, Error>;
     /// Create role
-    async fn create_role(&self, path: &str, role: &Role) -> Result<Response, Error>;
+    async fn create_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error>;
     /// Update Role
-    async fn update_role(&self, path: &str, role: &Role) -> Result<Response, Error> {
-        self.create_role(path, role).await
+    async fn update_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error> {
+        self.create_role(path, name, role).await
     }
     /// Read Role
-    async fn read_role(&self, path: &str, role: &str) -> Result<Role, Error>;
+    async fn read_role(&self, path: &str, name: &str) -> Result<Role, Error>;
     /// List Roles
     async fn list_roles(&self, path: &str) -> Result<Vec<String>, Error>;
     /// Delete Role
-    async fn delete_role(&self, path: &str, role: &str) -> Result<Response, Error>;
-    /// Generate Credentials
+    async fn delete_role(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Generate Credentials, with the parameters of the request in the query string
     async fn generate_credentials(
         &self,
         path: &str,
         role: &str,
         request: &CredentialsRequest,
     ) -> Result<LeasedData<Credentials>, Error>;
+    /// Generate Credentials, with the parameters of the request in the body of a POST request.
+    ///
+    /// Vault documents this form for `assumed_role` credentials, to choose the role to assume
+    /// and the name of the session.
+    async fn generate_credentials_with_post(
+        &self,
+        path: &str,
+        role: &str,
+        request: &CredentialsRequest,
+    ) -> Result<LeasedData<Credentials>, Error>;
+    /// Generate STS credentials for a role with the `assumed_role` or `federation_token`
+    /// credential type, with a POST request to the `sts` endpoint.
+    ///
+    /// The TTL of the credentials is requested with `request.ttl`, and is limited by the
+    /// maximum STS TTL of the role.
+    async fn generate_sts_credentials(
+        &self,
+        path: &str,
+        role: &str,
+        request: &CredentialsRequest,
+    ) -> Result<LeasedData<Credentials>, Error>;
 }
 
 #[async_trait]
@@ -141,20 +248,25 @@ where
         Ok(data)
     }
 
-    async fn create_role(&self, _path: &str, _role: &Role) -> Result<Response, Error> {
-        unimplemented!()
+    async fn create_role(&self, path: &str, name: &str, role: &Role) -> Result<Response, Error> {
+        let path = format!("{}/roles/{}", path, name);
+        self.post(&path, role, false).await
     }
 
-    async fn read_role(&self, _path: &str, _role: &str) -> Result<Role, Error> {
-        unimplemented!()
+    async fn read_role(&self, path: &str, name: &str) -> Result<Role, Error> {
+        let path = format!("{}/roles/{}", path, name);
+        self.get(&path).await?.data()
     }
 
-    async fn list_roles(&self, _path: &str) -> Result<Vec<String>, Error> {
-        unimplemented!()
+    async fn list_roles(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/roles", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
     }
 
-    async fn delete_role(&self, _path: &str, _role: &str) -> Result<Response, Error> {
-        unimplemented!()
+    async fn delete_role(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/roles/{}", path, name);
+        self.delete(&path, false).await
     }
 
     async fn generate_credentials(
@@ -166,6 +278,26 @@ where
         let path = format!("{}/creds/{}", path, role);
         self.get_with_query(&path, request).await?.leased_data()
     }
+
+    async fn generate_credentials_with_post(
+        &self,
+        path: &str,
+        role: &str,
+        request: &CredentialsRequest,
+    ) -> Result<LeasedData<Credentials>, Error> {
+        let path = format!("{}/creds/{}", path, role);
+        self.post(&path, request, true).await?.leased_data()
+    }
+
+    async fn generate_sts_credentials(
+        &self,
+        path: &str,
+        role: &str,
+        request: &CredentialsRequest,
+    ) -> Result<LeasedData<Credentials>, Error> {
+        let path = format!("{}/sts/{}", path, role);
+        self.post(&path, request, true).await?.leased_data()
+    }
 }
 
 #[allow(dead_code)]
@@ -217,4 +349,76 @@ mod tests {
         assert_eq!(actual_lease.lease, "1h0m0s");
         assert_eq!(actual_lease.lease_max, "24h0m0s");
     }
+
+    #[test]
+    fn credentials_expire_at_their_expiration_or_lease_end() {
+        let received = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut credentials = LeasedData {
+            lease_id: "aws/creds/app/abcd".to_string(),
+            renewable: true,
+            lease_duration: 3600,
+            data: Credentials::default(),
+        };
+        assert_eq!(
+            credentials.expires_at(received),
+            received + Duration::from_secs(3600)
+        );
+
+        credentials.data.expiration = Some("2023-11-14T23:13:20Z".to_string());
+        assert_eq!(
+            credentials.expires_at(received),
+            std::time::UNIX_EPOCH + Duration::from_secs(1_700_003_600)
+        );
+    }
+
+    #[test]
+    fn credentials_requests_are_serialized() {
+        let request = CredentialsRequest {
+            role_arn: Some("arn:aws:iam::123456789012:role/app".to_string()),
+            role_session_name: Some("job-42".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "role_arn": "arn:aws:iam::123456789012:role/app",
+                "role_session_name": "job-42"
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_roles() {
+        let client = crate::tests::vault_client();
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("aws"),
+            r#type: "aws".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+
+        let role = Role {
+            credential_type: Some(CredentialType::AssumedRole),
+            role_arns: Some(vec!["arn:aws:iam::123456789012:role/app".to_string()]),
+            default_sts_ttl: Some(900),
+            max_sts_ttl: Some(3600),
+            ..Default::default()
+        };
+        let response = Aws::create_role(&client, &mount.path, "app", &role)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Aws::read_role(&client, &mount.path, "app").await.unwrap();
+        assert_eq!(actual.credential_type, role.credential_type);
+        assert_eq!(actual.role_arns, role.role_arns);
+        assert_eq!(actual.default_sts_ttl, Some(900));
+        assert_eq!(actual.max_sts_ttl, Some(3600));
+
+        let roles = Aws::list_roles(&client, &mount.path).await.unwrap();
+        assert_eq!(roles, ["app"]);
+
+        let _ = Aws::delete_role(&client, &mount.path, "app").await.unwrap();
+        assert!(Aws::read_role(&client, &mount.path, "app").await.is_err());
+    }
 }
@@ -0,0 +1,657 @@
+//! Identity Secrets Engine
+//!
+//! The Identity Secrets Engine is always mounted at `identity`, and maintains the clients known
+//! to Vault as entities, and groups of entities. Aliases tie the identities of auth methods, like
+//! LDAP users or groups, to entities and groups.
+//!
+//! See the [documentation](https://www.vaultproject.io/api-docs/secret/identity).
+use crate::{Error, Keys, Response};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters to create or update an entity. Fields left out are not changed on update.
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct EntityRequest {
+    /// Name of the entity. Generated by Vault if missing on creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Metadata of the entity, replacing any existing metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Policies of the entity, replacing any existing policies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policies: Option<Vec<String>>,
+    /// Disable the entity. Tokens of a disabled entity are denied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+}
+
+/// Identifiers of a created entity
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct EntityId {
+    /// ID of the entity
+    pub id: String,
+    /// Name of the entity
+    pub name: String,
+}
+
+/// Entity
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Entity {
+    /// ID of the entity
+    pub id: String,
+    /// Name of the entity
+    pub name: String,
+    /// Metadata of the entity
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub metadata: HashMap<String, String>,
+    /// Policies of the entity, excluding those inherited from groups
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub policies: Vec<String>,
+    /// Whether the entity is disabled
+    #[serde(default)]
+    pub disabled: bool,
+    /// IDs of the groups the entity is a direct member of
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub direct_group_ids: Vec<String>,
+    /// IDs of all the groups the entity is a member of, directly or not
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub group_ids: Vec<String>,
+    /// IDs of the entities merged into this entity
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub merged_entity_ids: Vec<String>,
+    /// Aliases of the entity in auth methods
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub aliases: Vec<Alias>,
+    /// Creation time, in RFC 3339 format
+    #[serde(default)]
+    pub creation_time: String,
+    /// Time of the last update, in RFC 3339 format
+    #[serde(default)]
+    pub last_update_time: String,
+}
+
+/// Type of a group
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupType {
+    /// Members of the group are managed in Vault
+    #[default]
+    Internal,
+    /// Members of the group are managed by an auth method, like LDAP groups or OIDC claims,
+    /// through a group alias
+    External,
+}
+
+/// Parameters to create or update a group. Fields left out are not changed on update.
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct GroupRequest {
+    /// Name of the group. Generated by Vault if missing on creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Type of the group. Cannot be changed once the group is created.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub group_type: Option<GroupType>,
+    /// Metadata of the group, replacing any existing metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Policies of the group, granted to its members
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policies: Option<Vec<String>>,
+    /// IDs of the entities members of the group. Only for internal groups.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_entity_ids: Option<Vec<String>>,
+    /// IDs of the groups members of the group. Only for internal groups.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_group_ids: Option<Vec<String>>,
+}
+
+/// Identifiers of a created group
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct GroupId {
+    /// ID of the group
+    pub id: String,
+    /// Name of the group
+    pub name: String,
+}
+
+/// Group of entities
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Group {
+    /// ID of the group
+    pub id: String,
+    /// Name of the group
+    pub name: String,
+    /// Type of the group
+    #[serde(rename = "type", default)]
+    pub group_type: GroupType,
+    /// Metadata of the group
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub metadata: HashMap<String, String>,
+    /// Policies of the group
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub policies: Vec<String>,
+    /// IDs of the entities members of the group
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub member_entity_ids: Vec<String>,
+    /// IDs of the groups members of the group
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub member_group_ids: Vec<String>,
+    /// IDs of the groups the group is a member of
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub parent_group_ids: Vec<String>,
+    /// Alias of an external group in an auth method
+    #[serde(default, deserialize_with = "deserialize_group_alias")]
+    pub alias: Option<Alias>,
+    /// Creation time, in RFC 3339 format
+    #[serde(default)]
+    pub creation_time: String,
+    /// Time of the last update, in RFC 3339 format
+    #[serde(default)]
+    pub last_update_time: String,
+}
+
+/// Parameters to create or update an alias. Fields left out are not changed on update.
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct AliasRequest {
+    /// Name of the identity in the auth method, like the user name or the group name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// ID of the entity or group the alias belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_id: Option<String>,
+    /// Accessor of the mount of the auth method
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mount_accessor: Option<String>,
+    /// Metadata of the alias set by operators. Only for entity aliases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_metadata: Option<HashMap<String, String>>,
+}
+
+/// Identifiers of a created alias
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct AliasId {
+    /// ID of the alias
+    pub id: String,
+    /// ID of the entity or group the alias belongs to
+    pub canonical_id: String,
+}
+
+/// Alias of an entity or a group in an auth method
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Alias {
+    /// ID of the alias
+    pub id: String,
+    /// Name of the identity in the auth method
+    pub name: String,
+    /// ID of the entity or group the alias belongs to
+    #[serde(default)]
+    pub canonical_id: String,
+    /// Accessor of the mount of the auth method
+    pub mount_accessor: String,
+    /// Path of the mount of the auth method
+    #[serde(default)]
+    pub mount_path: String,
+    /// Type of the auth method
+    #[serde(default)]
+    pub mount_type: String,
+    /// Metadata of the alias set by the auth method
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub metadata: HashMap<String, String>,
+    /// Metadata of the alias set by operators
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub custom_metadata: HashMap<String, String>,
+    /// Creation time, in RFC 3339 format
+    #[serde(default)]
+    pub creation_time: String,
+    /// Time of the last update, in RFC 3339 format
+    #[serde(default)]
+    pub last_update_time: String,
+}
+
+/// Vault returns an empty object for groups without alias
+fn deserialize_group_alias<'de, D>(deserializer: D) -> Result<Option<Alias>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let alias: Option<serde_json::Map<String, serde_json::Value>> =
+        Deserialize::deserialize(deserializer)?;
+    match alias {
+        Some(alias) if !alias.is_empty() => serde_json::from_value(alias.into())
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+/// Identity Secrets Engine
+///
+/// See the [documentation](https://www.vaultproject.io/api-docs/secret/identity).
+#[async_trait]
+pub trait Identity {
+    /// Create an entity
+    async fn create_entity(&self, entity: &EntityRequest) -> Result<EntityId, Error>;
+    /// Read the entity with the ID
+    async fn read_entity(&self, id: &str) -> Result<Entity, Error>;
+    /// Update the entity with the ID
+    async fn update_entity(&self, id: &str, entity: &EntityRequest) -> Result<Response, Error>;
+    /// Delete the entity with the ID
+    async fn delete_entity(&self, id: &str) -> Result<Response, Error>;
+    /// List the IDs of the entities
+    async fn list_entities(&self) -> Result<Vec<String>, Error>;
+    /// Create the entity with the name, or update it if it exists. The name in `entity` is
+    /// ignored.
+    async fn write_entity_by_name(
+        &self,
+        name: &str,
+        entity: &EntityRequest,
+    ) -> Result<Response, Error>;
+    /// Read the entity with the name
+    async fn read_entity_by_name(&self, name: &str) -> Result<Entity, Error>;
+    /// Delete the entity with the name
+    async fn delete_entity_by_name(&self, name: &str) -> Result<Response, Error>;
+    /// List the names of the entities
+    async fn list_entity_names(&self) -> Result<Vec<String>, Error>;
+    /// Add policies to the entity with the ID, keeping its existing policies
+    async fn add_entity_policies(&self, id: &str, policies: &[&str]) -> Result<Response, Error> {
+        let mut current = self.read_entity(id).await?.policies;
+        for policy in policies {
+            if !current.iter().any(|p| p == policy) {
+                current.push(policy.to_string());
+            }
+        }
+        let request = EntityRequest {
+            policies: Some(current),
+            ..Default::default()
+        };
+        self.update_entity(id, &request).await
+    }
+    /// Remove policies from the entity with the ID, keeping its other policies
+    async fn remove_entity_policies(&self, id: &str, policies: &[&str]) -> Result<Response, Error> {
+        let mut current = self.read_entity(id).await?.policies;
+        current.retain(|p| !policies.contains(&p.as_str()));
+        let request = EntityRequest {
+            policies: Some(current),
+            ..Default::default()
+        };
+        self.update_entity(id, &request).await
+    }
+    /// Create a group
+    async fn create_group(&self, group: &GroupRequest) -> Result<GroupId, Error>;
+    /// Read the group with the ID
+    async fn read_group(&self, id: &str) -> Result<Group, Error>;
+    /// Update the group with the ID
+    async fn update_group(&self, id: &str, group: &GroupRequest) -> Result<Response, Error>;
+    /// Delete the group with the ID
+    async fn delete_group(&self, id: &str) -> Result<Response, Error>;
+    /// List the IDs of the groups
+    async fn list_groups(&self) -> Result<Vec<String>, Error>;
+    /// Create the group with the name, or update it if it exists. The name in `group` is ignored.
+    async fn write_group_by_name(
+        &self,
+        name: &str,
+        group: &GroupRequest,
+    ) -> Result<Response, Error>;
+    /// Read the group with the name
+    async fn read_group_by_name(&self, name: &str) -> Result<Group, Error>;
+    /// Delete the group with the name
+    async fn delete_group_by_name(&self, name: &str) -> Result<Response, Error>;
+    /// List the names of the groups
+    async fn list_group_names(&self) -> Result<Vec<String>, Error>;
+    /// Create an alias of an entity
+    async fn create_entity_alias(&self, alias: &AliasRequest) -> Result<AliasId, Error>;
+    /// Read the entity alias with the ID
+    async fn read_entity_alias(&self, id: &str) -> Result<Alias, Error>;
+    /// Update the entity alias with the ID
+    async fn update_entity_alias(&self, id: &str, alias: &AliasRequest) -> Result<Response, Error>;
+    /// Delete the entity alias with the ID
+    async fn delete_entity_alias(&self, id: &str) -> Result<Response, Error>;
+    /// List the IDs of the entity aliases
+    async fn list_entity_aliases(&self) -> Result<Vec<String>, Error>;
+    /// Create an alias of an external group
+    async fn create_group_alias(&self, alias: &AliasRequest) -> Result<AliasId, Error>;
+    /// Read the group alias with the ID
+    async fn read_group_alias(&self, id: &str) -> Result<Alias, Error>;
+    /// Update the group alias with the ID
+    async fn update_group_alias(&self, id: &str, alias: &AliasRequest) -> Result<Response, Error>;
+    /// Delete the group alias with the ID
+    async fn delete_group_alias(&self, id: &str) -> Result<Response, Error>;
+    /// List the IDs of the group aliases
+    async fn list_group_aliases(&self) -> Result<Vec<String>, Error>;
+}
+
+#[async_trait]
+impl<T> Identity for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn create_entity(&self, entity: &EntityRequest) -> Result<EntityId, Error> {
+        self.post("identity/entity", entity, false).await?.data()
+    }
+
+    async fn read_entity(&self, id: &str) -> Result<Entity, Error> {
+        let path = format!("identity/entity/id/{}", id);
+        self.get(&path).await?.data()
+    }
+
+    async fn update_entity(&self, id: &str, entity: &EntityRequest) -> Result<Response, Error> {
+        let path = format!("identity/entity/id/{}", id);
+        self.post(&path, entity, false).await
+    }
+
+    async fn delete_entity(&self, id: &str) -> Result<Response, Error> {
+        let path = format!("identity/entity/id/{}", id);
+        self.delete(&path, false).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/entity/id").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn write_entity_by_name(
+        &self,
+        name: &str,
+        entity: &EntityRequest,
+    ) -> Result<Response, Error> {
+        let path = format!("identity/entity/name/{}", name);
+        self.post(&path, entity, false).await
+    }
+
+    async fn read_entity_by_name(&self, name: &str) -> Result<Entity, Error> {
+        let path = format!("identity/entity/name/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_entity_by_name(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("identity/entity/name/{}", name);
+        self.delete(&path, false).await
+    }
+
+    async fn list_entity_names(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/entity/name").await?.data()?;
+        Ok(keys.keys)
+    }
+    async fn create_group(&self, group: &GroupRequest) -> Result<GroupId, Error> {
+        self.post("identity/group", group, false).await?.data()
+    }
+
+    async fn read_group(&self, id: &str) -> Result<Group, Error> {
+        let path = format!("identity/group/id/{}", id);
+        self.get(&path).await?.data()
+    }
+
+    async fn update_group(&self, id: &str, group: &GroupRequest) -> Result<Response, Error> {
+        let path = format!("identity/group/id/{}", id);
+        self.post(&path, group, false).await
+    }
+
+    async fn delete_group(&self, id: &str) -> Result<Response, Error> {
+        let path = format!("identity/group/id/{}", id);
+        self.delete(&path, false).await
+    }
+
+    async fn list_groups(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/group/id").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn write_group_by_name(
+        &self,
+        name: &str,
+        group: &GroupRequest,
+    ) -> Result<Response, Error> {
+        let path = format!("identity/group/name/{}", name);
+        self.post(&path, group, false).await
+    }
+
+    async fn read_group_by_name(&self, name: &str) -> Result<Group, Error> {
+        let path = format!("identity/group/name/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_group_by_name(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("identity/group/name/{}", name);
+        self.delete(&path, false).await
+    }
+
+    async fn list_group_names(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/group/name").await?.data()?;
+        Ok(keys.keys)
+    }
+    async fn create_entity_alias(&self, alias: &AliasRequest) -> Result<AliasId, Error> {
+        self.post("identity/entity-alias", alias, false)
+            .await?
+            .data()
+    }
+
+    async fn read_entity_alias(&self, id: &str) -> Result<Alias, Error> {
+        let path = format!("identity/entity-alias/id/{}", id);
+        self.get(&path).await?.data()
+    }
+
+    async fn update_entity_alias(&self, id: &str, alias: &AliasRequest) -> Result<Response, Error> {
+        let path = format!("identity/entity-alias/id/{}", id);
+        self.post(&path, alias, false).await
+    }
+
+    async fn delete_entity_alias(&self, id: &str) -> Result<Response, Error> {
+        let path = format!("identity/entity-alias/id/{}", id);
+        self.delete(&path, false).await
+    }
+
+    async fn list_entity_aliases(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/entity-alias/id").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn create_group_alias(&self, alias: &AliasRequest) -> Result<AliasId, Error> {
+        self.post("identity/group-alias", alias, false)
+            .await?
+            .data()
+    }
+
+    async fn read_group_alias(&self, id: &str) -> Result<Alias, Error> {
+        let path = format!("identity/group-alias/id/{}", id);
+        self.get(&path).await?.data()
+    }
+
+    async fn update_group_alias(&self, id: &str, alias: &AliasRequest) -> Result<Response, Error> {
+        let path = format!("identity/group-alias/id/{}", id);
+        self.post(&path, alias, false).await
+    }
+
+    async fn delete_group_alias(&self, id: &str) -> Result<Response, Error> {
+        let path = format!("identity/group-alias/id/{}", id);
+        self.delete(&path, false).await
+    }
+
+    async fn list_group_aliases(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("identity/group-alias/id").await?.data()?;
+        Ok(keys.keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_entities() {
+        let client = crate::tests::vault_client();
+        let name = crate::tests::uuid_prefix("entity");
+
+        let request = EntityRequest {
+            name: Some(name.clone()),
+            metadata: Some(
+                vec![("team".to_string(), "ops".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            policies: Some(vec!["default".to_string()]),
+            ..Default::default()
+        };
+        let created = Identity::create_entity(&client, &request).await.unwrap();
+        assert_eq!(created.name, name);
+
+        let entity = Identity::read_entity_by_name(&client, &name).await.unwrap();
+        assert_eq!(entity.id, created.id);
+        assert_eq!(entity.metadata["team"], "ops");
+        assert!(Identity::list_entities(&client)
+            .await
+            .unwrap()
+            .contains(&created.id));
+        assert!(Identity::list_entity_names(&client)
+            .await
+            .unwrap()
+            .contains(&name));
+
+        let _ = Identity::add_entity_policies(&client, &created.id, &["admin", "default"])
+            .await
+            .unwrap();
+        let entity = Identity::read_entity(&client, &created.id).await.unwrap();
+        assert_eq!(entity.policies, ["default", "admin"]);
+
+        let _ = Identity::remove_entity_policies(&client, &created.id, &["default"])
+            .await
+            .unwrap();
+        let entity = Identity::read_entity(&client, &created.id).await.unwrap();
+        assert_eq!(entity.policies, ["admin"]);
+        assert_eq!(entity.metadata["team"], "ops");
+
+        let _ = Identity::delete_entity(&client, &created.id).await.unwrap();
+        assert!(Identity::read_entity(&client, &created.id).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_groups() {
+        let client = crate::tests::vault_client();
+
+        let entity = EntityRequest {
+            name: Some(crate::tests::uuid_prefix("member")),
+            ..Default::default()
+        };
+        let member = Identity::create_entity(&client, &entity).await.unwrap();
+
+        let name = crate::tests::uuid_prefix("group");
+        let request = GroupRequest {
+            name: Some(name.clone()),
+            policies: Some(vec!["default".to_string()]),
+            member_entity_ids: Some(vec![member.id.clone()]),
+            ..Default::default()
+        };
+        let created = Identity::create_group(&client, &request).await.unwrap();
+
+        let group = Identity::read_group_by_name(&client, &name).await.unwrap();
+        assert_eq!(group.id, created.id);
+        assert_eq!(group.group_type, GroupType::Internal);
+        assert_eq!(group.member_entity_ids, [member.id.as_str()]);
+        assert!(Identity::list_groups(&client)
+            .await
+            .unwrap()
+            .contains(&created.id));
+        assert!(Identity::list_group_names(&client)
+            .await
+            .unwrap()
+            .contains(&name));
+        let entity = Identity::read_entity(&client, &member.id).await.unwrap();
+        assert_eq!(entity.direct_group_ids, [created.id.as_str()]);
+
+        let external = GroupRequest {
+            group_type: Some(GroupType::External),
+            ..Default::default()
+        };
+        let external_name = crate::tests::uuid_prefix("external");
+        let _ = Identity::write_group_by_name(&client, &external_name, &external)
+            .await
+            .unwrap();
+        let group = Identity::read_group_by_name(&client, &external_name)
+            .await
+            .unwrap();
+        assert_eq!(group.group_type, GroupType::External);
+
+        let _ = Identity::delete_group_by_name(&client, &external_name)
+            .await
+            .unwrap();
+        let _ = Identity::delete_group(&client, &created.id).await.unwrap();
+        let _ = Identity::delete_entity(&client, &member.id).await.unwrap();
+    }
+
+    #[test]
+    fn groups_without_alias_have_none() {
+        let group: Group = serde_json::from_value(serde_json::json!({
+            "id": "0b8f5b3e",
+            "name": "ops",
+            "type": "external",
+            "alias": {}
+        }))
+        .unwrap();
+        assert!(group.alias.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_aliases() {
+        let client = crate::tests::vault_client();
+        let accessor = crate::sys::accessors::mount_accessors(&client)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|mount| {
+                mount.kind == crate::sys::accessors::MountKind::Auth && mount.path == "token/"
+            })
+            .unwrap()
+            .accessor;
+
+        let entity = EntityRequest::default();
+        let entity = Identity::create_entity(&client, &entity).await.unwrap();
+        let request = AliasRequest {
+            name: Some(crate::tests::uuid_prefix("user")),
+            canonical_id: Some(entity.id.clone()),
+            mount_accessor: Some(accessor.clone()),
+            ..Default::default()
+        };
+        let alias = Identity::create_entity_alias(&client, &request)
+            .await
+            .unwrap();
+        assert_eq!(alias.canonical_id, entity.id);
+        assert!(Identity::list_entity_aliases(&client)
+            .await
+            .unwrap()
+            .contains(&alias.id));
+        let read = Identity::read_entity(&client, &entity.id).await.unwrap();
+        assert_eq!(read.aliases[0].id, alias.id);
+
+        let group = GroupRequest {
+            group_type: Some(GroupType::External),
+            ..Default::default()
+        };
+        let group = Identity::create_group(&client, &group).await.unwrap();
+        let request = AliasRequest {
+            name: Some(crate::tests::uuid_prefix("ldap-group")),
+            canonical_id: Some(group.id.clone()),
+            mount_accessor: Some(accessor),
+            ..Default::default()
+        };
+        let group_alias = Identity::create_group_alias(&client, &request)
+            .await
+            .unwrap();
+        let read = Identity::read_group(&client, &group.id).await.unwrap();
+        assert_eq!(read.alias.unwrap().id, group_alias.id);
+        let read = Identity::read_group_alias(&client, &group_alias.id)
+            .await
+            .unwrap();
+        assert_eq!(read.canonical_id, group.id);
+
+        let _ = Identity::delete_group_alias(&client, &group_alias.id)
+            .await
+            .unwrap();
+        let _ = Identity::delete_entity_alias(&client, &alias.id)
+            .await
+            .unwrap();
+        let _ = Identity::delete_group(&client, &group.id).await.unwrap();
+        let _ = Identity::delete_entity(&client, &entity.id).await.unwrap();
+    }
+}
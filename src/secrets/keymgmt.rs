@@ -0,0 +1,373 @@
+//! Key Management Secrets Engine
+//!
+//! Vault Enterprise generates keys, and distributes them to the key management services of cloud
+//! providers, like AWS KMS, Azure Key Vault or GCP Cloud KMS, so that their lifecycle is managed
+//! from Vault.
+//!
+//! See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/key-management).
+use crate::{Error, Keys, Response, Secret};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Type of a key
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum KeyType {
+    /// AES-256 in GCM mode
+    #[serde(rename = "aes256-gcm96")]
+    Aes256Gcm96,
+    /// RSA, 2048 bits
+    #[serde(rename = "rsa-2048")]
+    Rsa2048,
+    /// RSA, 3072 bits
+    #[serde(rename = "rsa-3072")]
+    Rsa3072,
+    /// RSA, 4096 bits
+    #[serde(rename = "rsa-4096")]
+    Rsa4096,
+    /// ECDSA on the P-256 curve
+    #[serde(rename = "ecdsa-p256")]
+    EcdsaP256,
+    /// ECDSA on the P-384 curve
+    #[serde(rename = "ecdsa-p384")]
+    EcdsaP384,
+    /// ECDSA on the P-521 curve
+    #[serde(rename = "ecdsa-p521")]
+    EcdsaP521,
+}
+
+/// Parameters for updating a key
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct UpdateKey {
+    /// Minimum version of the key enabled. Older versions are disabled in the KMS providers the
+    /// key is distributed to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_enabled_version: Option<u64>,
+    /// Whether the key can be deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_allowed: Option<bool>,
+}
+
+/// Version of a key
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct KeyVersion {
+    /// Time at which the version was created, in RFC 3339 format
+    pub creation_time: String,
+}
+
+/// Key of the Key Management secrets engine
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Key {
+    /// Name of the key
+    pub name: String,
+    /// Type of the key
+    pub r#type: KeyType,
+    /// Whether the key can be deleted
+    #[serde(default)]
+    pub deletion_allowed: bool,
+    /// Latest version of the key
+    pub latest_version: u64,
+    /// Minimum version of the key enabled
+    #[serde(default)]
+    pub min_enabled_version: u64,
+    /// Versions of the key, keyed by their number
+    #[serde(default)]
+    pub versions: HashMap<String, KeyVersion>,
+}
+
+/// Key management service of a cloud provider
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// AWS KMS
+    AwsKms,
+    /// Azure Key Vault
+    AzureKeyVault,
+    /// GCP Cloud KMS
+    GcpCkms,
+}
+
+/// Key management service keys are distributed to
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Kms {
+    /// Key management service
+    pub provider: Provider,
+    /// Collection the keys are distributed to, like the region of AWS KMS, the name of the Azure
+    /// Key Vault, or the key ring of GCP Cloud KMS
+    pub key_collection: String,
+    /// Credentials of the provider. Never returned by Vault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<HashMap<String, Secret>>,
+}
+
+/// Purpose a distributed key may be used for
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Purpose {
+    /// Encryption
+    Encrypt,
+    /// Decryption
+    Decrypt,
+    /// Signing
+    Sign,
+    /// Verification of signatures
+    Verify,
+    /// Wrapping of other keys
+    Wrap,
+    /// Unwrapping of other keys
+    Unwrap,
+}
+
+/// Protection of a distributed key in the key management service
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Protection {
+    /// Stored in a hardware security module
+    Hsm,
+    /// Stored in software
+    Software,
+}
+
+/// Distribution of a key to a key management service
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Distribution {
+    /// Purposes the key may be used for
+    pub purpose: Vec<Purpose>,
+    /// Protection of the key
+    pub protection: Protection,
+}
+
+/// Key distributed to a key management service
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DistributedKey {
+    /// Name of the key in the key management service
+    pub name: String,
+    /// Purposes the key may be used for
+    pub purpose: Vec<Purpose>,
+    /// Protection of the key
+    pub protection: Protection,
+    /// Versions of the key, keyed by their number, with their ID in the key management service
+    #[serde(default)]
+    pub versions: HashMap<String, String>,
+}
+
+#[derive(Serialize, Debug)]
+struct CreateKey {
+    r#type: KeyType,
+}
+
+/// Key Management Secrets Engine
+///
+/// See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/key-management).
+#[async_trait]
+pub trait KeyManagement {
+    /// Create a key
+    async fn create_key(&self, path: &str, name: &str, r#type: KeyType) -> Result<Response, Error>;
+    /// Update a key
+    async fn update_key(&self, path: &str, name: &str, key: &UpdateKey) -> Result<Response, Error>;
+    /// Read a key
+    async fn read_key(&self, path: &str, name: &str) -> Result<Key, Error>;
+    /// List keys
+    async fn list_keys(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete a key. Deletion must be allowed, and the key must not be distributed.
+    async fn delete_key(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Rotate a key to a new version, in every key management service it is distributed to
+    async fn rotate_key(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// List the key management services a key is distributed to
+    async fn list_key_kms(&self, path: &str, name: &str) -> Result<Vec<String>, Error>;
+    /// Configure a key management service
+    async fn configure_kms(&self, path: &str, name: &str, kms: &Kms) -> Result<Response, Error>;
+    /// Read the configuration of a key management service. The credentials are not returned.
+    async fn read_kms(&self, path: &str, name: &str) -> Result<Kms, Error>;
+    /// List key management services
+    async fn list_kms(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete a key management service. Keys must not be distributed to it.
+    async fn delete_kms(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Distribute a key to a key management service
+    async fn distribute_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+        distribution: &Distribution,
+    ) -> Result<Response, Error>;
+    /// Read a key distributed to a key management service
+    async fn read_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<DistributedKey, Error>;
+    /// List the keys distributed to a key management service
+    async fn list_distributed_keys(&self, path: &str, kms: &str) -> Result<Vec<String>, Error>;
+    /// Remove a key from a key management service, destroying it there
+    async fn remove_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<Response, Error>;
+    /// Rotate a key in a key management service only
+    async fn rotate_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> KeyManagement for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn create_key(&self, path: &str, name: &str, r#type: KeyType) -> Result<Response, Error> {
+        let path = format!("{}/key/{}", path, name);
+        self.post(&path, &CreateKey { r#type }, false).await
+    }
+
+    async fn update_key(&self, path: &str, name: &str, key: &UpdateKey) -> Result<Response, Error> {
+        let path = format!("{}/key/{}", path, name);
+        self.post(&path, key, false).await
+    }
+
+    async fn read_key(&self, path: &str, name: &str) -> Result<Key, Error> {
+        let path = format!("{}/key/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_keys(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/key", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_key(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/key/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn rotate_key(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/key/{}/rotate", path, name);
+        self.read(&path, Method::POST).await
+    }
+
+    async fn list_key_kms(&self, path: &str, name: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/key/{}/kms", path, name);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn configure_kms(&self, path: &str, name: &str, kms: &Kms) -> Result<Response, Error> {
+        let path = format!("{}/kms/{}", path, name);
+        self.post(&path, kms, false).await
+    }
+
+    async fn read_kms(&self, path: &str, name: &str) -> Result<Kms, Error> {
+        let path = format!("{}/kms/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_kms(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/kms", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_kms(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/kms/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn distribute_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+        distribution: &Distribution,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/kms/{}/key/{}", path, kms, key);
+        self.put(&path, distribution, false).await
+    }
+
+    async fn read_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<DistributedKey, Error> {
+        let path = format!("{}/kms/{}/key/{}", path, kms, key);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_distributed_keys(&self, path: &str, kms: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/kms/{}/key", path, kms);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn remove_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/kms/{}/key/{}", path, kms, key);
+        self.delete(&path, false).await
+    }
+
+    async fn rotate_distributed_key(
+        &self,
+        path: &str,
+        kms: &str,
+        key: &str,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/kms/{}/key/{}/rotate", path, kms, key);
+        self.read(&path, Method::POST).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_and_distributions_are_parsed() {
+        let key: Key = serde_json::from_value(serde_json::json!({
+            "deletion_allowed": false,
+            "latest_version": 2,
+            "min_enabled_version": 1,
+            "name": "app",
+            "type": "ecdsa-p256",
+            "versions": {
+                "1": {"creation_time": "2023-09-12T15:19:49.394915-07:00"},
+                "2": {"creation_time": "2023-09-13T15:19:49.394915-07:00"}
+            }
+        }))
+        .unwrap();
+        assert_eq!(key.r#type, KeyType::EcdsaP256);
+        assert_eq!(key.versions.len(), 2);
+
+        let distribution = Distribution {
+            purpose: vec![Purpose::Sign, Purpose::Verify],
+            protection: Protection::Hsm,
+        };
+        assert_eq!(
+            serde_json::to_value(&distribution).unwrap(),
+            serde_json::json!({"purpose": ["sign", "verify"], "protection": "hsm"})
+        );
+
+        let kms = Kms {
+            provider: Provider::AzureKeyVault,
+            key_collection: "keyvault-name".to_string(),
+            credentials: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&kms).unwrap()["provider"],
+            "azurekeyvault"
+        );
+    }
+}
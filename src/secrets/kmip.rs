@@ -0,0 +1,375 @@
+//! KMIP Secrets Engine
+//!
+//! Vault Enterprise serves the Key Management Interoperability Protocol to KMIP clients, which
+//! authenticate with client certificates. Clients are grouped into scopes, and the operations
+//! they are allowed are granted by roles within each scope.
+//!
+//! See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/kmip).
+use crate::{Error, Keys, Response, Secret};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Type of the keys of certificates
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+    /// RSA
+    Rsa,
+    /// Elliptic curve
+    Ec,
+}
+
+/// Configuration of the KMIP server
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct KmipConfig {
+    /// Addresses the KMIP server listens on, like `0.0.0.0:5696`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen_addrs: Option<Vec<String>>,
+    /// Timeout of connections, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_timeout: Option<u64>,
+    /// Hostnames in the certificate of the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_hostnames: Option<Vec<String>>,
+    /// IP addresses in the certificate of the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_ips: Option<Vec<String>>,
+    /// Type of the key of the CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_ca_key_type: Option<KeyType>,
+    /// Size of the key of the CA, in bits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_ca_key_bits: Option<u64>,
+    /// Minimum TLS version accepted, like `tls12`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_min_version: Option<String>,
+    /// Default type of the keys of client certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tls_client_key_type: Option<KeyType>,
+    /// Default size of the keys of client certificates, in bits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tls_client_key_bits: Option<u64>,
+    /// Default TTL of client certificates, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tls_client_ttl: Option<u64>,
+}
+
+/// Role granting KMIP operations to the clients of a scope
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct KmipRole {
+    /// Type of the keys of client certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_key_type: Option<KeyType>,
+    /// Size of the keys of client certificates, in bits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_key_bits: Option<u64>,
+    /// TTL of client certificates, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_ttl: Option<u64>,
+    /// Grant all operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_all: Option<bool>,
+    /// Grant no operation, overriding the others
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_none: Option<bool>,
+    /// Individual operations granted, keyed by their parameter, like `operation_get` or
+    /// `operation_create`
+    #[serde(flatten)]
+    pub operations: HashMap<String, bool>,
+}
+
+/// Format of generated certificates
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateFormat {
+    /// PEM
+    Pem,
+    /// PEM, with the private key appended to the certificate
+    PemBundle,
+    /// Base64 encoded DER
+    Der,
+}
+
+/// Client certificate generated for a role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct KmipCredentials {
+    /// Certificates of the CA chain
+    #[serde(default)]
+    pub ca_chain: Vec<String>,
+    /// Client certificate
+    pub certificate: String,
+    /// Private key of the certificate. Not returned when a CSR is signed.
+    #[serde(default)]
+    pub private_key: Option<Secret>,
+    /// Serial number of the certificate
+    pub serial_number: String,
+}
+
+#[derive(Serialize, Debug)]
+struct Generate {
+    format: CertificateFormat,
+}
+
+#[derive(Serialize, Debug)]
+struct Sign<'a> {
+    csr: &'a str,
+    format: CertificateFormat,
+}
+
+#[derive(Serialize, Debug)]
+struct SerialNumber<'a> {
+    serial_number: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+struct Ca {
+    ca_pem: String,
+}
+
+/// KMIP Secrets Engine
+///
+/// See the [documentation](https://developer.hashicorp.com/vault/api-docs/secret/kmip).
+#[async_trait]
+pub trait Kmip {
+    /// Configure the KMIP server
+    async fn configure(&self, path: &str, config: &KmipConfig) -> Result<Response, Error>;
+    /// Read the configuration of the KMIP server
+    async fn read_config(&self, path: &str) -> Result<KmipConfig, Error>;
+    /// Read the PEM encoded certificate of the CA of the KMIP server
+    async fn read_ca(&self, path: &str) -> Result<String, Error>;
+    /// Create a scope
+    async fn create_scope(&self, path: &str, scope: &str) -> Result<Response, Error>;
+    /// List scopes
+    async fn list_scopes(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete a scope. With `force`, the scope is deleted with its roles and the objects
+    /// managed by its clients.
+    async fn delete_scope(&self, path: &str, scope: &str, force: bool) -> Result<Response, Error>;
+    /// Create a role in a scope
+    async fn create_role(
+        &self,
+        path: &str,
+        scope: &str,
+        name: &str,
+        role: &KmipRole,
+    ) -> Result<Response, Error>;
+    /// Update a role in a scope
+    async fn update_role(
+        &self,
+        path: &str,
+        scope: &str,
+        name: &str,
+        role: &KmipRole,
+    ) -> Result<Response, Error> {
+        self.create_role(path, scope, name, role).await
+    }
+    /// Read a role in a scope
+    async fn read_role(&self, path: &str, scope: &str, name: &str) -> Result<KmipRole, Error>;
+    /// List the roles of a scope
+    async fn list_roles(&self, path: &str, scope: &str) -> Result<Vec<String>, Error>;
+    /// Delete a role in a scope
+    async fn delete_role(&self, path: &str, scope: &str, name: &str) -> Result<Response, Error>;
+    /// Generate a client certificate and private key for a role
+    async fn generate_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        format: CertificateFormat,
+    ) -> Result<KmipCredentials, Error>;
+    /// Sign the CSR of a client for a role
+    async fn sign_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        csr: &str,
+        format: CertificateFormat,
+    ) -> Result<KmipCredentials, Error>;
+    /// Look up the client certificate of a role by its serial number
+    async fn lookup_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        serial_number: &str,
+    ) -> Result<KmipCredentials, Error>;
+    /// List the serial numbers of the client certificates of a role
+    async fn list_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+    ) -> Result<Vec<String>, Error>;
+    /// Revoke the client certificate of a role
+    async fn revoke_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        serial_number: &str,
+    ) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Kmip for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn configure(&self, path: &str, config: &KmipConfig) -> Result<Response, Error> {
+        let path = format!("{}/config", path);
+        self.post(&path, config, false).await
+    }
+
+    async fn read_config(&self, path: &str) -> Result<KmipConfig, Error> {
+        let path = format!("{}/config", path);
+        self.get(&path).await?.data()
+    }
+
+    async fn read_ca(&self, path: &str) -> Result<String, Error> {
+        let path = format!("{}/ca", path);
+        let ca: Ca = self.get(&path).await?.data()?;
+        Ok(ca.ca_pem)
+    }
+
+    async fn create_scope(&self, path: &str, scope: &str) -> Result<Response, Error> {
+        let path = format!("{}/scope/{}", path, scope);
+        self.post(&path, &crate::Empty, false).await
+    }
+
+    async fn list_scopes(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/scope", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_scope(&self, path: &str, scope: &str, force: bool) -> Result<Response, Error> {
+        let path = if force {
+            format!("{}/scope/{}?force=true", path, scope)
+        } else {
+            format!("{}/scope/{}", path, scope)
+        };
+        self.delete(&path, false).await
+    }
+
+    async fn create_role(
+        &self,
+        path: &str,
+        scope: &str,
+        name: &str,
+        role: &KmipRole,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/scope/{}/role/{}", path, scope, name);
+        self.post(&path, role, false).await
+    }
+
+    async fn read_role(&self, path: &str, scope: &str, name: &str) -> Result<KmipRole, Error> {
+        let path = format!("{}/scope/{}/role/{}", path, scope, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_roles(&self, path: &str, scope: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/scope/{}/role", path, scope);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_role(&self, path: &str, scope: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/scope/{}/role/{}", path, scope, name);
+        self.delete(&path, false).await
+    }
+
+    async fn generate_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        format: CertificateFormat,
+    ) -> Result<KmipCredentials, Error> {
+        let path = format!("{}/scope/{}/role/{}/credential/generate", path, scope, role);
+        self.post(&path, &Generate { format }, true).await?.data()
+    }
+
+    async fn sign_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        csr: &str,
+        format: CertificateFormat,
+    ) -> Result<KmipCredentials, Error> {
+        let path = format!("{}/scope/{}/role/{}/credential/sign", path, scope, role);
+        self.post(&path, &Sign { csr, format }, true).await?.data()
+    }
+
+    async fn lookup_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        serial_number: &str,
+    ) -> Result<KmipCredentials, Error> {
+        let path = format!("{}/scope/{}/role/{}/credential/lookup", path, scope, role);
+        self.get_with_query(&path, &SerialNumber { serial_number })
+            .await?
+            .data()
+    }
+
+    async fn list_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+    ) -> Result<Vec<String>, Error> {
+        let path = format!("{}/scope/{}/role/{}/credential", path, scope, role);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn revoke_credentials(
+        &self,
+        path: &str,
+        scope: &str,
+        role: &str,
+        serial_number: &str,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/scope/{}/role/{}/credential/revoke", path, scope, role);
+        self.post(&path, &SerialNumber { serial_number }, false)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn role_operations_are_flattened() {
+        let role = KmipRole {
+            tls_client_key_type: Some(KeyType::Ec),
+            tls_client_key_bits: Some(256),
+            operations: vec![
+                ("operation_get".to_string(), true),
+                ("operation_create".to_string(), true),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let values = serde_json::to_value(&role).unwrap();
+        assert_eq!(
+            values,
+            serde_json::json!({
+                "tls_client_key_type": "ec",
+                "tls_client_key_bits": 256,
+                "operation_get": true,
+                "operation_create": true
+            })
+        );
+
+        let actual: KmipRole = serde_json::from_value(values).unwrap();
+        assert_eq!(actual, role);
+    }
+}
@@ -0,0 +1,120 @@
+//! Key/Value Secrets Engine Version 1
+//!
+//! See the [documentation](https://www.vaultproject.io/api/secret/kv/kv-v1.html).
+use crate::{Error, Keys, Response};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Key/Value Secrets Engine Version 1
+///
+/// Secrets are stored as JSON objects, and can be (de)serialized to and from any type
+/// implementing the `serde` traits.
+///
+/// See the [documentation](https://www.vaultproject.io/api/secret/kv/kv-v1.html).
+#[async_trait]
+pub trait Kv1 {
+    /// Read the secret at `key`
+    async fn read<T: DeserializeOwned + Send>(&self, path: &str, key: &str) -> Result<T, Error>;
+    /// Write the secret at `key`, replacing any existing secret. The secret must serialize to a
+    /// JSON object.
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        key: &str,
+        secret: &T,
+    ) -> Result<Response, Error>;
+    /// Delete the secret at `key`
+    async fn delete(&self, path: &str, key: &str) -> Result<Response, Error>;
+    /// List the keys under `prefix`. Keys ending with `/` are folders.
+    async fn list(&self, path: &str, prefix: &str) -> Result<Vec<String>, Error>;
+}
+
+#[async_trait]
+impl<C> Kv1 for C
+where
+    C: crate::Vault + Send + Sync,
+{
+    async fn read<T: DeserializeOwned + Send>(&self, path: &str, key: &str) -> Result<T, Error> {
+        let path = format!("{}/{}", path, key);
+        self.get(&path).await?.data()
+    }
+
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        key: &str,
+        secret: &T,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/{}", path, key);
+        self.post(&path, secret, false).await
+    }
+
+    async fn delete(&self, path: &str, key: &str) -> Result<Response, Error> {
+        let path = format!("{}/{}", path, key);
+        crate::Vault::delete(self, &path, false).await
+    }
+
+    async fn list(&self, path: &str, prefix: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/{}", path, prefix);
+        let keys: Keys = crate::Vault::list(self, &path).await?.data()?;
+        Ok(keys.keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::mounts::tests::Mount;
+
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Database {
+        username: String,
+        password: String,
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_write_list_and_delete() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("kv");
+        let engine = crate::sys::mounts::SecretEngine {
+            path,
+            r#type: "kv".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        let response = Kv1::write(&client, &mount.path, "databases/primary", &secret)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual: Database = Kv1::read(&client, &mount.path, "databases/primary")
+            .await
+            .unwrap();
+        assert_eq!(actual, secret);
+
+        let keys = Kv1::list(&client, &mount.path, "").await.unwrap();
+        assert_eq!(keys, vec!["databases/"]);
+        let keys = Kv1::list(&client, &mount.path, "databases").await.unwrap();
+        assert_eq!(keys, vec!["primary"]);
+
+        let response = Kv1::delete(&client, &mount.path, "databases/primary")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(
+            Kv1::read::<Database>(&client, &mount.path, "databases/primary")
+                .await
+                .is_err()
+        );
+    }
+}
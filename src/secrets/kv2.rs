@@ -0,0 +1,860 @@
+//! Key/Value Secrets Engine Version 2
+//!
+//! See the [documentation](https://www.vaultproject.io/api/secret/kv/kv-v2.html).
+use crate::{Error, Keys, Method, Response};
+
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a version of a secret
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VersionMetadata {
+    /// Time the version was created, in RFC 3339 format
+    pub created_time: String,
+    /// Time the version was deleted, in RFC 3339 format. Empty if the version is not deleted.
+    #[serde(default)]
+    pub deletion_time: String,
+    /// Whether the version has been permanently destroyed
+    #[serde(default)]
+    pub destroyed: bool,
+    /// The version number
+    pub version: u64,
+    /// Custom metadata of the secret, as returned by Vault 1.9 and later
+    #[serde(default)]
+    pub custom_metadata: Option<HashMap<String, String>>,
+}
+
+/// A version of a secret, with its metadata
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VersionedSecret<T> {
+    /// The secret
+    pub data: T,
+    /// Metadata of the version
+    pub metadata: VersionMetadata,
+}
+
+/// State of a version of a secret, as listed in its metadata
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VersionState {
+    /// Time the version was created, in RFC 3339 format
+    pub created_time: String,
+    /// Time the version was deleted, in RFC 3339 format. Empty if the version is not deleted.
+    #[serde(default)]
+    pub deletion_time: String,
+    /// Whether the version has been permanently destroyed
+    #[serde(default)]
+    pub destroyed: bool,
+}
+
+/// Metadata of a secret and its versions
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct SecretMetadata {
+    /// Whether writes to the secret require the `cas` parameter
+    #[serde(default)]
+    pub cas_required: bool,
+    /// Time the secret was created, in RFC 3339 format
+    pub created_time: String,
+    /// The latest version of the secret
+    pub current_version: u64,
+    /// Duration after which versions are deleted, like "3h25m19s". "0s" if versions are kept.
+    #[serde(default)]
+    pub delete_version_after: String,
+    /// Number of versions kept. 0 if the setting of the engine is used.
+    #[serde(default)]
+    pub max_versions: u64,
+    /// The oldest version of the secret still kept
+    #[serde(default)]
+    pub oldest_version: u64,
+    /// Time the secret was last updated, in RFC 3339 format
+    pub updated_time: String,
+    /// Custom metadata of the secret, as returned by Vault 1.9 and later
+    #[serde(default)]
+    pub custom_metadata: Option<HashMap<String, String>>,
+    /// State of the versions kept, by version number
+    #[serde(default)]
+    pub versions: BTreeMap<u64, VersionState>,
+}
+
+/// How [`Kv2::purge`] removes a secret
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PurgeMode {
+    /// Destroy the data of every version, keeping the metadata of the secret
+    DestroyVersions,
+    /// Delete the metadata of the secret, with all of its versions
+    DeleteMetadata,
+}
+
+/// What [`Kv2::purge`] removed, or would remove in a dry run
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct Purge {
+    /// Versions whose data was not already destroyed
+    pub versions: Vec<u64>,
+    /// Whether the metadata of the secret was deleted
+    pub metadata_deleted: bool,
+}
+
+/// Settings of a secret. Settings that are not set are left unchanged.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Metadata {
+    /// Number of versions to keep. Once exceeded, the oldest versions are permanently deleted.
+    /// 0 uses the setting of the engine.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_versions: Option<u64>,
+    /// Require the `cas` parameter for writes to the secret
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cas_required: Option<bool>,
+    /// Duration after which versions are deleted, like "3h25m19s". "0s" keeps versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_version_after: Option<String>,
+    /// Custom metadata of the secret. Requires Vault 1.9 or later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_metadata: Option<HashMap<String, String>>,
+}
+
+/// The latest version of a secret, with the custom metadata of the secret
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct SecretWithMetadata<T> {
+    /// The secret
+    pub data: T,
+    /// The version of the secret read
+    pub version: u64,
+    /// Custom metadata of the secret
+    pub custom_metadata: HashMap<String, String>,
+}
+
+#[derive(Serialize, Debug)]
+struct WriteOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cas: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct Write<'a, T> {
+    options: WriteOptions,
+    data: &'a T,
+}
+
+#[derive(Serialize, Debug)]
+struct Versions<'a> {
+    versions: &'a [u64],
+}
+
+/// Structure of a secret, with the values of its keys stripped
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Subkeys {
+    /// Keys of the secret. Values are `null`, or objects with the subkeys of nested objects.
+    pub subkeys: serde_json::Value,
+    /// Metadata of the version
+    pub metadata: VersionMetadata,
+}
+
+#[derive(Serialize, Debug)]
+struct SubkeysQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depth: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct ReadVersion {
+    version: u64,
+}
+
+/// Key/Value Secrets Engine Version 2
+///
+/// Secrets are stored as JSON objects, and can be (de)serialized to and from any type
+/// implementing the `serde` traits. Every write creates a new version of the secret.
+///
+/// See the [documentation](https://www.vaultproject.io/api/secret/kv/kv-v2.html).
+#[async_trait]
+pub trait Kv2 {
+    /// Read the latest version of the secret at `key`
+    async fn read<T: DeserializeOwned + Send>(
+        &self,
+        path: &str,
+        key: &str,
+    ) -> Result<VersionedSecret<T>, Error>;
+    /// Read a version of the secret at `key`
+    async fn read_version<T: DeserializeOwned + Send>(
+        &self,
+        path: &str,
+        key: &str,
+        version: u64,
+    ) -> Result<VersionedSecret<T>, Error>;
+    /// Write a new version of the secret at `key`. The secret must serialize to a JSON object.
+    ///
+    /// With `cas` set, the write only succeeds if the current version of the secret is `cas`.
+    /// A `cas` of 0 only allows the write if the secret does not exist.
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        key: &str,
+        secret: &T,
+        cas: Option<u64>,
+    ) -> Result<VersionMetadata, Error>;
+    /// Read the keys of a version of the secret at `key`, without their values. Defaults to the
+    /// latest version.
+    ///
+    /// Nested objects are returned up to `depth` levels deep, or entirely if not set. Requires
+    /// Vault 1.10 or later.
+    async fn read_subkeys(
+        &self,
+        path: &str,
+        key: &str,
+        version: Option<u64>,
+        depth: Option<u64>,
+    ) -> Result<Subkeys, Error>;
+    /// Soft delete the latest version of the secret at `key`. The version can be undeleted.
+    async fn delete_latest(&self, path: &str, key: &str) -> Result<Response, Error>;
+    /// Soft delete versions of the secret at `key`. The versions can be undeleted.
+    async fn delete_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error>;
+    /// Restore soft deleted versions of the secret at `key`
+    async fn undelete_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error>;
+    /// Permanently remove the data of versions of the secret at `key`. The metadata of the
+    /// versions is kept, and marked as destroyed.
+    async fn destroy_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error>;
+    /// List the keys under `prefix`. Keys ending with `/` are folders.
+    async fn list(&self, path: &str, prefix: &str) -> Result<Vec<String>, Error>;
+    /// Read the metadata of the secret at `key`, and the state of its versions
+    async fn read_metadata(&self, path: &str, key: &str) -> Result<SecretMetadata, Error>;
+    /// Update the settings of the secret at `key`. The custom metadata is replaced.
+    async fn write_metadata(
+        &self,
+        path: &str,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<Response, Error>;
+    /// Update the settings of the secret at `key`. The custom metadata is merged with the
+    /// existing custom metadata. Keys of the custom metadata can only be removed by replacing
+    /// it with [`Kv2::write_metadata`].
+    ///
+    /// Requires Vault 1.9 or later.
+    async fn patch_metadata(
+        &self,
+        path: &str,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<Response, Error>;
+    /// Permanently delete the metadata and all the versions of the secret at `key`
+    async fn delete_metadata(&self, path: &str, key: &str) -> Result<Response, Error>;
+    /// Read the latest version of the secret at `key`, and the custom metadata of the secret.
+    ///
+    /// The secret and its metadata are read with separate requests. Requires Vault 1.9 or
+    /// later.
+    async fn read_with_metadata<T: DeserializeOwned + Send>(
+        &self,
+        path: &str,
+        key: &str,
+    ) -> Result<SecretWithMetadata<T>, Error> {
+        let secret: VersionedSecret<T> = Kv2::read(self, path, key).await?;
+        let metadata = self.read_metadata(path, key).await?;
+        Ok(SecretWithMetadata {
+            data: secret.data,
+            version: secret.metadata.version,
+            custom_metadata: metadata.custom_metadata.unwrap_or_default(),
+        })
+    }
+    /// Write a new version of the secret at `key`, then replace the custom metadata of the
+    /// secret. Other settings of the secret are left unchanged.
+    ///
+    /// The secret and its metadata are written with separate requests. The secret is written
+    /// first, with `cas` set to `version` if given, so that a concurrent change fails the write
+    /// before the metadata is modified. Requires Vault 1.9 or later.
+    async fn write_with_metadata<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        key: &str,
+        secret: &T,
+        custom_metadata: &HashMap<String, String>,
+        version: Option<u64>,
+    ) -> Result<VersionMetadata, Error> {
+        let written = Kv2::write(self, path, key, secret, version).await?;
+        let metadata = Metadata {
+            custom_metadata: Some(custom_metadata.clone()),
+            ..Default::default()
+        };
+        let _ = self.write_metadata(path, key, &metadata).await?;
+        Ok(VersionMetadata {
+            custom_metadata: Some(custom_metadata.clone()),
+            ..written
+        })
+    }
+    /// Permanently remove every version of the secret at `key`, as listed in its metadata.
+    ///
+    /// With `dry_run` set, nothing is removed, and the versions that would be removed are
+    /// returned.
+    async fn purge(
+        &self,
+        path: &str,
+        key: &str,
+        mode: PurgeMode,
+        dry_run: bool,
+    ) -> Result<Purge, Error> {
+        let metadata = self.read_metadata(path, key).await?;
+        let versions: Vec<u64> = metadata
+            .versions
+            .iter()
+            .filter(|(_, state)| !state.destroyed)
+            .map(|(version, _)| *version)
+            .collect();
+        let metadata_deleted = mode == PurgeMode::DeleteMetadata;
+
+        if !dry_run {
+            match mode {
+                PurgeMode::DeleteMetadata => {
+                    let _ = self.delete_metadata(path, key).await?;
+                }
+                PurgeMode::DestroyVersions if !versions.is_empty() => {
+                    let _ = self.destroy_versions(path, key, &versions).await?;
+                }
+                PurgeMode::DestroyVersions => {}
+            }
+        }
+        Ok(Purge {
+            versions,
+            metadata_deleted,
+        })
+    }
+}
+
+#[async_trait]
+impl<C> Kv2 for C
+where
+    C: crate::Vault + Send + Sync,
+{
+    async fn read<T: DeserializeOwned + Send>(
+        &self,
+        path: &str,
+        key: &str,
+    ) -> Result<VersionedSecret<T>, Error> {
+        let path = format!("{}/data/{}", path, key);
+        self.get(&path).await?.data()
+    }
+
+    async fn read_version<T: DeserializeOwned + Send>(
+        &self,
+        path: &str,
+        key: &str,
+        version: u64,
+    ) -> Result<VersionedSecret<T>, Error> {
+        let path = format!("{}/data/{}", path, key);
+        self.read_with_query(&path, Method::GET, &ReadVersion { version })
+            .await?
+            .data()
+    }
+
+    async fn write<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        key: &str,
+        secret: &T,
+        cas: Option<u64>,
+    ) -> Result<VersionMetadata, Error> {
+        let path = format!("{}/data/{}", path, key);
+        let payload = Write {
+            options: WriteOptions { cas },
+            data: secret,
+        };
+        self.post(&path, &payload, true).await?.data()
+    }
+
+    async fn read_subkeys(
+        &self,
+        path: &str,
+        key: &str,
+        version: Option<u64>,
+        depth: Option<u64>,
+    ) -> Result<Subkeys, Error> {
+        let path = format!("{}/subkeys/{}", path, key);
+        self.read_with_query(&path, Method::GET, &SubkeysQuery { version, depth })
+            .await?
+            .data()
+    }
+
+    async fn delete_latest(&self, path: &str, key: &str) -> Result<Response, Error> {
+        let path = format!("{}/data/{}", path, key);
+        crate::Vault::delete(self, &path, false).await
+    }
+
+    async fn delete_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error> {
+        let path = format!("{}/delete/{}", path, key);
+        self.post(&path, &Versions { versions }, false).await
+    }
+
+    async fn undelete_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error> {
+        let path = format!("{}/undelete/{}", path, key);
+        self.post(&path, &Versions { versions }, false).await
+    }
+
+    async fn destroy_versions(
+        &self,
+        path: &str,
+        key: &str,
+        versions: &[u64],
+    ) -> Result<Response, Error> {
+        let path = format!("{}/destroy/{}", path, key);
+        self.post(&path, &Versions { versions }, false).await
+    }
+
+    async fn list(&self, path: &str, prefix: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/metadata/{}", path, prefix);
+        let keys: Keys = crate::Vault::list(self, &path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn read_metadata(&self, path: &str, key: &str) -> Result<SecretMetadata, Error> {
+        let path = format!("{}/metadata/{}", path, key);
+        self.get(&path).await?.data()
+    }
+
+    async fn write_metadata(
+        &self,
+        path: &str,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/metadata/{}", path, key);
+        self.post(&path, metadata, false).await
+    }
+
+    async fn patch_metadata(
+        &self,
+        path: &str,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/metadata/{}", path, key);
+        crate::Vault::write(self, &path, metadata, Method::PATCH, false).await
+    }
+
+    async fn delete_metadata(&self, path: &str, key: &str) -> Result<Response, Error> {
+        let path = format!("{}/metadata/{}", path, key);
+        crate::Vault::delete(self, &path, false).await
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::sys::mounts::tests::Mount;
+    use crate::Vault;
+
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+    pub(crate) struct Database {
+        pub(crate) username: String,
+        pub(crate) password: String,
+    }
+
+    /// Mount a KV v2 secrets engine, and wait for it to be ready for writes
+    pub(crate) async fn kv2_mount<T>(client: &T) -> Mount<T>
+    where
+        T: Vault + Send + Sync + Clone,
+    {
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("kv2"),
+            r#type: "kv-v2".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(client, &engine).await;
+
+        // The engine is upgraded in the background after being mounted
+        for _ in 0..50 {
+            let config = client.get(&format!("{}/config", mount.path)).await;
+            if matches!(config, Ok(Response::Response(_))) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        mount
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_write_read_and_list_versions() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let first = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        let version = Kv2::write(&client, &mount.path, "databases/primary", &first, Some(0))
+            .await
+            .unwrap();
+        assert_eq!(version.version, 1);
+
+        // The secret already exists
+        assert!(
+            Kv2::write(&client, &mount.path, "databases/primary", &first, Some(0))
+                .await
+                .is_err()
+        );
+
+        let second = Database {
+            password: "correct horse".to_string(),
+            ..first.clone()
+        };
+        let version = Kv2::write(&client, &mount.path, "databases/primary", &second, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(version.version, 2);
+
+        let latest: VersionedSecret<Database> =
+            Kv2::read(&client, &mount.path, "databases/primary")
+                .await
+                .unwrap();
+        assert_eq!(latest.data, second);
+        assert_eq!(latest.metadata.version, 2);
+
+        let original: VersionedSecret<Database> =
+            Kv2::read_version(&client, &mount.path, "databases/primary", 1)
+                .await
+                .unwrap();
+        assert_eq!(original.data, first);
+
+        let keys = Kv2::list(&client, &mount.path, "databases").await.unwrap();
+        assert_eq!(keys, vec!["primary"]);
+
+        let response = Kv2::delete_latest(&client, &mount.path, "databases/primary")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(
+            Kv2::read::<Database>(&client, &mount.path, "databases/primary")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_metadata() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        for _ in 0..3 {
+            let _ = Kv2::write(&client, &mount.path, "database", &secret, None)
+                .await
+                .unwrap();
+        }
+
+        let metadata = Metadata {
+            max_versions: Some(2),
+            cas_required: Some(true),
+            ..Default::default()
+        };
+        let response = Kv2::write_metadata(&client, &mount.path, "database", &metadata)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Kv2::read_metadata(&client, &mount.path, "database")
+            .await
+            .unwrap();
+        assert_eq!(actual.current_version, 3);
+        assert_eq!(actual.max_versions, 2);
+        assert!(actual.cas_required);
+        assert!(actual.versions.contains_key(&3));
+
+        let response = Kv2::delete_metadata(&client, &mount.path, "database")
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Kv2::read_metadata(&client, &mount.path, "database")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_patch_metadata() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        let _ = Kv2::write(&client, &mount.path, "database", &secret, None)
+            .await
+            .unwrap();
+        let metadata = Metadata {
+            max_versions: Some(5),
+            custom_metadata: Some(
+                vec![
+                    ("owner".to_string(), "alice".to_string()),
+                    ("team".to_string(), "storage".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+        let _ = Kv2::write_metadata(&client, &mount.path, "database", &metadata)
+            .await
+            .unwrap();
+
+        // Custom metadata is merged
+        let patch = Metadata {
+            cas_required: Some(true),
+            custom_metadata: Some(
+                vec![("owner".to_string(), "bob".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let response = Kv2::patch_metadata(&client, &mount.path, "database", &patch)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Kv2::read_metadata(&client, &mount.path, "database")
+            .await
+            .unwrap();
+        assert_eq!(actual.max_versions, 5);
+        assert!(actual.cas_required);
+        let custom_metadata = actual.custom_metadata.unwrap();
+        assert_eq!(custom_metadata["owner"], "bob");
+        assert_eq!(custom_metadata["team"], "storage");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_write_and_read_with_metadata() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        let custom_metadata: HashMap<String, String> =
+            vec![("owner".to_string(), "alice".to_string())]
+                .into_iter()
+                .collect();
+        let written = Kv2::write_with_metadata(
+            &client,
+            &mount.path,
+            "database",
+            &secret,
+            &custom_metadata,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        assert_eq!(written.version, 1);
+        assert_eq!(written.custom_metadata.as_ref(), Some(&custom_metadata));
+
+        let read: SecretWithMetadata<Database> =
+            Kv2::read_with_metadata(&client, &mount.path, "database")
+                .await
+                .unwrap();
+        assert_eq!(read.data, secret);
+        assert_eq!(read.version, 1);
+        assert_eq!(read.custom_metadata, custom_metadata);
+
+        // A concurrent change fails the write before the metadata is modified
+        let replaced: HashMap<String, String> = vec![("owner".to_string(), "bob".to_string())]
+            .into_iter()
+            .collect();
+        assert!(Kv2::write_with_metadata(
+            &client,
+            &mount.path,
+            "database",
+            &secret,
+            &replaced,
+            Some(0),
+        )
+        .await
+        .is_err());
+        let read: SecretWithMetadata<Database> =
+            Kv2::read_with_metadata(&client, &mount.path, "database")
+                .await
+                .unwrap();
+        assert_eq!(read.custom_metadata, custom_metadata);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_subkeys() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = serde_json::json!({
+            "username": "alice",
+            "connection": {"host": "db.example.com", "tls": {"ca": "ca.pem"}}
+        });
+        let _ = Kv2::write(&client, &mount.path, "database", &secret, None)
+            .await
+            .unwrap();
+        let _ = Kv2::write(
+            &client,
+            &mount.path,
+            "database",
+            &serde_json::json!({"username": "bob"}),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let subkeys = Kv2::read_subkeys(&client, &mount.path, "database", None, None)
+            .await
+            .unwrap();
+        assert_eq!(subkeys.metadata.version, 2);
+        assert_eq!(subkeys.subkeys, serde_json::json!({"username": null}));
+
+        let subkeys = Kv2::read_subkeys(&client, &mount.path, "database", Some(1), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(subkeys.metadata.version, 1);
+        assert_eq!(
+            subkeys.subkeys,
+            serde_json::json!({
+                "username": null,
+                "connection": {"host": null, "tls": null}
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_delete_undelete_and_destroy_versions() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        for _ in 0..2 {
+            let _ = Kv2::write(&client, &mount.path, "database", &secret, None)
+                .await
+                .unwrap();
+        }
+
+        let response = Kv2::delete_versions(&client, &mount.path, "database", &[1, 2])
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let metadata = Kv2::read_metadata(&client, &mount.path, "database")
+            .await
+            .unwrap();
+        assert!(metadata
+            .versions
+            .values()
+            .all(|version| !version.deletion_time.is_empty()));
+
+        let response = Kv2::undelete_versions(&client, &mount.path, "database", &[2])
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let latest: VersionedSecret<Database> =
+            Kv2::read(&client, &mount.path, "database").await.unwrap();
+        assert_eq!(latest.data, secret);
+
+        let response = Kv2::destroy_versions(&client, &mount.path, "database", &[1])
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let metadata = Kv2::read_metadata(&client, &mount.path, "database")
+            .await
+            .unwrap();
+        assert!(metadata.versions[&1].destroyed);
+        assert!(!metadata.versions[&2].destroyed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_purge_secrets() {
+        let client = crate::tests::vault_client();
+        let mount = kv2_mount(&client).await;
+
+        let secret = Database {
+            username: "alice".to_string(),
+            password: "hunter2".to_string(),
+        };
+        for key in &["first", "second"] {
+            for _ in 0..3 {
+                let _ = Kv2::write(&client, &mount.path, key, &secret, None)
+                    .await
+                    .unwrap();
+            }
+        }
+        let _ = Kv2::destroy_versions(&client, &mount.path, "first", &[1])
+            .await
+            .unwrap();
+
+        let purge = Kv2::purge(
+            &client,
+            &mount.path,
+            "first",
+            PurgeMode::DestroyVersions,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(purge.versions, vec![2, 3]);
+        assert!(!purge.metadata_deleted);
+        let _ = Kv2::read::<Database>(&client, &mount.path, "first")
+            .await
+            .unwrap();
+
+        let _ = Kv2::purge(
+            &client,
+            &mount.path,
+            "first",
+            PurgeMode::DestroyVersions,
+            false,
+        )
+        .await
+        .unwrap();
+        let metadata = Kv2::read_metadata(&client, &mount.path, "first")
+            .await
+            .unwrap();
+        assert!(metadata.versions.values().all(|state| state.destroyed));
+
+        let purge = Kv2::purge(
+            &client,
+            &mount.path,
+            "second",
+            PurgeMode::DeleteMetadata,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(purge.versions, vec![1, 2, 3]);
+        assert!(purge.metadata_deleted);
+        assert!(Kv2::read_metadata(&client, &mount.path, "second")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! LDAP Secrets Engine
+//!
+//! Manages the passwords of OpenLDAP and Active Directory accounts, either rotating the password
+//! of existing accounts with static roles, or creating accounts on demand with dynamic roles.
+//!
+//! See the [documentation](https://www.vaultproject.io/api-docs/secret/ldap).
+use crate::{Error, Keys, LeasedData, Response, Secret};
+
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Schema of the LDAP server, which determines how passwords are changed
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Schema {
+    /// OpenLDAP
+    OpenLdap,
+    /// Active Directory
+    Ad,
+    /// IBM Resource Access Control Facility
+    Racf,
+}
+
+/// Configuration of the connection to the LDAP server
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct LdapConfig {
+    /// Distinguished name of the account Vault binds with to manage passwords
+    pub binddn: String,
+    /// Password of the account Vault binds with. Never returned by Vault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bindpass: Option<Secret>,
+    /// Comma separated URLs of the LDAP servers, like `ldaps://ldap.example.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Schema of the LDAP server. Defaults to `openldap`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+    /// Name of the password policy used to generate passwords
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<String>,
+    /// Base DN under which accounts are searched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub userdn: Option<String>,
+    /// Attribute identifying accounts, like `cn` or `userPrincipalName`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub userattr: Option<String>,
+    /// Domain of the User Principal Names of Active Directory accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upndomain: Option<String>,
+    /// PEM encoded CA certificate verifying the LDAP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<String>,
+    /// Skip the verification of the certificate of the LDAP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insecure_tls: Option<bool>,
+    /// Upgrade `ldap://` connections with StartTLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starttls: Option<bool>,
+    /// Timeout to connect to the LDAP server, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_timeout: Option<u64>,
+    /// Timeout of requests to the LDAP server, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<u64>,
+}
+
+/// Static role, managing the password of an existing account
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct StaticRole {
+    /// Name of the account
+    pub username: String,
+    /// Distinguished name of the account. Searched with `userattr` if missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dn: Option<String>,
+    /// Interval between rotations of the password, in seconds
+    pub rotation_period: u64,
+}
+
+/// Current credentials of the account of a static role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct StaticCredentials {
+    /// Name of the account
+    pub username: String,
+    /// Distinguished name of the account
+    #[serde(default)]
+    pub dn: String,
+    /// Current password
+    pub password: Secret,
+    /// Password before the last rotation, if any
+    #[serde(default)]
+    pub last_password: Option<Secret>,
+    /// Time of the last rotation by Vault, in RFC 3339 format
+    pub last_vault_rotation: String,
+    /// Interval between rotations of the password, in seconds
+    pub rotation_period: u64,
+    /// Time until the next rotation, in seconds
+    pub ttl: u64,
+}
+
+/// Dynamic role, creating accounts on demand from LDIF templates
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct DynamicRole {
+    /// LDIF template creating the account
+    pub creation_ldif: String,
+    /// LDIF template deleting the account, when its lease expires
+    pub deletion_ldif: String,
+    /// LDIF template undoing a partial creation of the account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback_ldif: Option<String>,
+    /// Template of the names of the accounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_template: Option<String>,
+    /// Default TTL of the accounts, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_ttl: Option<u64>,
+    /// Maximum TTL of the accounts, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ttl: Option<u64>,
+}
+
+/// Credentials of an account created for a dynamic role
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DynamicCredentials {
+    /// Name of the account
+    pub username: String,
+    /// Password of the account
+    pub password: Secret,
+    /// Distinguished names of the entries created
+    #[serde(default)]
+    pub distinguished_names: Vec<String>,
+}
+
+/// LDAP Secrets Engine
+///
+/// See the [documentation](https://www.vaultproject.io/api-docs/secret/ldap).
+#[async_trait]
+pub trait Ldap {
+    /// Configure the connection to the LDAP server
+    async fn configure(&self, path: &str, config: &LdapConfig) -> Result<Response, Error>;
+    /// Read the configuration of the connection to the LDAP server. The password is not returned.
+    async fn read_config(&self, path: &str) -> Result<LdapConfig, Error>;
+    /// Delete the configuration of the connection to the LDAP server
+    async fn delete_config(&self, path: &str) -> Result<Response, Error>;
+    /// Rotate the password of the account Vault binds with. Only Vault knows the new password.
+    async fn rotate_root(&self, path: &str) -> Result<Response, Error>;
+    /// Create a static role. The password of the account is rotated immediately.
+    async fn create_static_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &StaticRole,
+    ) -> Result<Response, Error>;
+    /// Update a static role
+    async fn update_static_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &StaticRole,
+    ) -> Result<Response, Error> {
+        self.create_static_role(path, name, role).await
+    }
+    /// Read a static role
+    async fn read_static_role(&self, path: &str, name: &str) -> Result<StaticRole, Error>;
+    /// List static roles
+    async fn list_static_roles(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete a static role. The account is left untouched.
+    async fn delete_static_role(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Read the current credentials of the account of a static role
+    async fn static_credentials(&self, path: &str, name: &str) -> Result<StaticCredentials, Error>;
+    /// Rotate the password of the account of a static role now, and reset its rotation period
+    async fn rotate_static_role(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Create a dynamic role
+    async fn create_dynamic_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &DynamicRole,
+    ) -> Result<Response, Error>;
+    /// Update a dynamic role
+    async fn update_dynamic_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &DynamicRole,
+    ) -> Result<Response, Error> {
+        self.create_dynamic_role(path, name, role).await
+    }
+    /// Read a dynamic role
+    async fn read_dynamic_role(&self, path: &str, name: &str) -> Result<DynamicRole, Error>;
+    /// List dynamic roles
+    async fn list_dynamic_roles(&self, path: &str) -> Result<Vec<String>, Error>;
+    /// Delete a dynamic role
+    async fn delete_dynamic_role(&self, path: &str, name: &str) -> Result<Response, Error>;
+    /// Create an account for a dynamic role. The account is deleted when the lease expires.
+    async fn dynamic_credentials(
+        &self,
+        path: &str,
+        name: &str,
+    ) -> Result<LeasedData<DynamicCredentials>, Error>;
+}
+
+#[async_trait]
+impl<T> Ldap for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn configure(&self, path: &str, config: &LdapConfig) -> Result<Response, Error> {
+        let path = format!("{}/config", path);
+        self.post(&path, config, false).await
+    }
+
+    async fn read_config(&self, path: &str) -> Result<LdapConfig, Error> {
+        let path = format!("{}/config", path);
+        self.get(&path).await?.data()
+    }
+
+    async fn delete_config(&self, path: &str) -> Result<Response, Error> {
+        let path = format!("{}/config", path);
+        self.delete(&path, false).await
+    }
+
+    async fn rotate_root(&self, path: &str) -> Result<Response, Error> {
+        let path = format!("{}/rotate-root", path);
+        self.read(&path, Method::POST).await
+    }
+
+    async fn create_static_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &StaticRole,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/static-role/{}", path, name);
+        self.post(&path, role, false).await
+    }
+
+    async fn read_static_role(&self, path: &str, name: &str) -> Result<StaticRole, Error> {
+        let path = format!("{}/static-role/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_static_roles(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/static-role", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_static_role(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/static-role/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn static_credentials(&self, path: &str, name: &str) -> Result<StaticCredentials, Error> {
+        let path = format!("{}/static-cred/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn rotate_static_role(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/rotate-role/{}", path, name);
+        self.read(&path, Method::POST).await
+    }
+
+    async fn create_dynamic_role(
+        &self,
+        path: &str,
+        name: &str,
+        role: &DynamicRole,
+    ) -> Result<Response, Error> {
+        let path = format!("{}/role/{}", path, name);
+        self.post(&path, role, false).await
+    }
+
+    async fn read_dynamic_role(&self, path: &str, name: &str) -> Result<DynamicRole, Error> {
+        let path = format!("{}/role/{}", path, name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_dynamic_roles(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = format!("{}/role", path);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_dynamic_role(&self, path: &str, name: &str) -> Result<Response, Error> {
+        let path = format!("{}/role/{}", path, name);
+        self.delete(&path, false).await
+    }
+
+    async fn dynamic_credentials(
+        &self,
+        path: &str,
+        name: &str,
+    ) -> Result<LeasedData<DynamicCredentials>, Error> {
+        let path = format!("{}/creds/{}", path, name);
+        self.get(&path).await?.leased_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::mounts::tests::Mount;
+
+    #[test]
+    fn schema_serializes_lowercase() {
+        let config = LdapConfig {
+            binddn: "cn=vault,ou=users,dc=example,dc=com".to_string(),
+            schema: Some(Schema::OpenLdap),
+            ..Default::default()
+        };
+        let values = serde_json::to_value(&config).unwrap();
+        assert_eq!(values["schema"], "openldap");
+        assert!(values.get("bindpass").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "requires an OpenLDAP server reachable by Vault at ldap://openldap"]
+    async fn can_manage_dynamic_roles() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("ldap");
+        let engine = crate::sys::mounts::SecretEngine {
+            path,
+            r#type: "ldap".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+
+        let config = LdapConfig {
+            binddn: "cn=admin,dc=example,dc=com".to_string(),
+            bindpass: Some(Secret("admin".to_string())),
+            url: Some("ldap://openldap".to_string()),
+            schema: Some(Schema::OpenLdap),
+            ..Default::default()
+        };
+        let _ = Ldap::configure(&client, &mount.path, &config)
+            .await
+            .unwrap();
+        let actual = Ldap::read_config(&client, &mount.path).await.unwrap();
+        assert_eq!(actual.binddn, config.binddn);
+        assert!(actual.bindpass.is_none());
+
+        let role = DynamicRole {
+            creation_ldif: "dn: cn={{.Username}},ou=users,dc=example,dc=com\n\
+                            objectClass: person\n\
+                            userPassword: {{.Password}}\n"
+                .to_string(),
+            deletion_ldif: "dn: cn={{.Username}},ou=users,dc=example,dc=com\n\
+                            changetype: delete\n"
+                .to_string(),
+            default_ttl: Some(3600),
+            ..Default::default()
+        };
+        let _ = Ldap::create_dynamic_role(&client, &mount.path, "app", &role)
+            .await
+            .unwrap();
+        let actual = Ldap::read_dynamic_role(&client, &mount.path, "app")
+            .await
+            .unwrap();
+        assert_eq!(actual.default_ttl, Some(3600));
+        assert_eq!(
+            Ldap::list_dynamic_roles(&client, &mount.path)
+                .await
+                .unwrap(),
+            ["app"]
+        );
+
+        let _ = Ldap::delete_dynamic_role(&client, &mount.path, "app")
+            .await
+            .unwrap();
+    }
+}
@@ -3,9 +3,30 @@
 //! See the [documentation](https://www.vaultproject.io/api/secret/).
 
 pub mod aws;
+pub mod identity;
+pub mod keymgmt;
+pub mod kmip;
+pub mod kv1;
+pub mod kv2;
+pub mod ldap;
+pub mod pki;
 pub mod transit;
 
 #[doc(inline)]
 pub use aws::Aws;
 #[doc(inline)]
+pub use identity::Identity;
+#[doc(inline)]
+pub use keymgmt::KeyManagement;
+#[doc(inline)]
+pub use kmip::Kmip;
+#[doc(inline)]
+pub use kv1::Kv1;
+#[doc(inline)]
+pub use kv2::Kv2;
+#[doc(inline)]
+pub use ldap::Ldap;
+#[doc(inline)]
+pub use pki::Pki;
+#[doc(inline)]
 pub use transit::Transit;
@@ -1,11 +1,15 @@
 //! Transit Secrets Engine
 //!
 //! See the [documentation](https://www.vaultproject.io/api/secret/transit/index.html).
+use crate::crypto::HashAlgorithm;
 use crate::{Error, Response};
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
+use serde::de::{self, DeserializeOwned, Deserializer};
 use serde::{Deserialize, Serialize};
 use serde_json::map::Map;
 use serde_json::Value;
@@ -40,7 +44,7 @@ pub struct CreateKey {
 }
 
 /// Type of Key in the Transit Secrets Engine
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum KeyType {
     /// AES-256 wrapped with GCM using a 96-bit nonce size
     /// AEAD (symmetric, supports derivation and convergent encryption)
@@ -84,12 +88,27 @@ pub struct Key {
     pub r#type: KeyType,
     /// Whether they key can be deleted
     pub deletion_allowed: bool,
-    /// List of key versions
-    pub keys: HashMap<String, Value>,
+    /// Versions of the key kept, by version number
+    pub keys: HashMap<String, KeyVersion>,
+    /// Latest version of the key, as reported by Vault 1.12 and later
+    #[serde(default)]
+    pub latest_version: u64,
+    /// Interval between automatic rotations of the key, in seconds. 0 if disabled.
+    #[serde(default)]
+    pub auto_rotate_period: u64,
+    /// Whether the key was imported, rather than generated by Vault
+    #[serde(default)]
+    pub imported_key: bool,
+    /// Whether an imported key can be rotated by Vault
+    #[serde(default)]
+    pub imported_key_allow_rotation: bool,
     /// Minimum decryption version
     pub min_decryption_version: u64,
     /// Minimum encryption version
     pub min_encryption_version: u64,
+    /// Oldest version of the key kept, once older versions have been trimmed
+    #[serde(default)]
+    pub min_available_version: u64,
     /// Key supports encryption
     pub supports_encryption: bool,
     /// Key supports decryption
@@ -100,6 +119,67 @@ pub struct Key {
     pub supports_signing: bool,
 }
 
+impl Key {
+    /// The latest version of the key, as reported by Vault, or from the versions kept for older
+    /// versions of Vault
+    pub fn latest_version(&self) -> u64 {
+        let kept = self
+            .keys
+            .keys()
+            .filter_map(|version| version.parse().ok())
+            .max()
+            .unwrap_or_default();
+        self.latest_version.max(kept)
+    }
+
+    /// The version of the key, if it is kept
+    pub fn version(&self, version: u64) -> Option<&KeyVersion> {
+        self.keys.get(&version.to_string())
+    }
+}
+
+/// Version of a Transit key
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum KeyVersion {
+    /// Version of a symmetric key, with its creation time as a Unix timestamp
+    Symmetric(i64),
+    /// Version of an asymmetric key
+    Asymmetric {
+        /// Creation time, in RFC 3339 format
+        creation_time: String,
+        /// Name of the curve or key size, like `P-256`
+        #[serde(default)]
+        name: String,
+        /// Public key, base64 encoded for `ed25519` keys, and PEM encoded otherwise
+        #[serde(default)]
+        public_key: String,
+    },
+}
+
+impl KeyVersion {
+    /// Creation time of the version
+    pub fn creation_time(&self) -> Option<SystemTime> {
+        match self {
+            KeyVersion::Symmetric(timestamp) => {
+                let seconds = u64::try_from(*timestamp).ok()?;
+                UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+            }
+            KeyVersion::Asymmetric { creation_time, .. } => {
+                crate::utils::parse_rfc3339(creation_time)
+            }
+        }
+    }
+
+    /// Public key of an asymmetric key version
+    pub fn public_key(&self) -> Option<&str> {
+        match self {
+            KeyVersion::Symmetric(_) => None,
+            KeyVersion::Asymmetric { public_key, .. } => Some(public_key),
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Default)]
 /// Parameters for Key Configuration
 pub struct ConfigureKey {
@@ -147,6 +227,324 @@ pub struct EncryptPayload<'a, 'b, 'c> {
     pub context: Option<&'c [u8]>,
 }
 
+/// Parameters to generate a data key
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct DataKeyRequest<'a, 'b> {
+    /// Context, if any. This is required if key derivation is enabled for this key.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context: Option<&'a [u8]>,
+    /// Nonce, if any. Only for convergent encryption keys generated with Vault 0.6.1.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub nonce: Option<&'b [u8]>,
+    /// Length of the data key in bits: 128, 256 or 512. Defaults to 256.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bits: Option<u32>,
+}
+
+/// Data key generated by Vault, for envelope encryption
+#[derive(Eq, PartialEq, Clone)]
+pub struct DataKey {
+    /// Data key, to encrypt data locally. Only returned for plaintext data keys.
+    pub plaintext: Option<Vec<u8>>,
+    /// Data key encrypted with the Transit key, to store alongside the data
+    pub ciphertext: String,
+    /// Version of the Transit key which encrypted the data key
+    pub key_version: u64,
+}
+
+impl std::fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataKey")
+            .field("plaintext", &self.plaintext.as_ref().map(|_| "<redacted>"))
+            .field("ciphertext", &self.ciphertext)
+            .field("key_version", &self.key_version)
+            .finish()
+    }
+}
+
+/// A single item to be HMAC'd or signed in a batch
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct BatchInput<'a, 'b> {
+    /// Input data
+    #[serde(serialize_with = "crate::utils::serialize_bytes")]
+    pub input: &'a [u8],
+    /// Context, if any. This is required if key derivation is enabled for a signing key.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context: Option<&'b [u8]>,
+}
+
+/// A single item to be verified in a batch, against either a HMAC or a signature
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VerifyInput<'a, 'b> {
+    /// Input data
+    #[serde(serialize_with = "crate::utils::serialize_bytes")]
+    pub input: &'a [u8],
+    /// Context, if any. This is required if key derivation is enabled for a signing key.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context: Option<&'b [u8]>,
+    /// HMAC to verify, like `vault:v1:...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hmac: Option<String>,
+    /// Signature to verify, like `vault:v1:...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Result of a single item of a batch operation.
+///
+/// Vault processes every item of a batch, even if some of them fail.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BatchItemResult<T> {
+    /// The item was processed successfully
+    Ok(T),
+    /// The item could not be processed. Contains the error from Vault.
+    Err(String),
+}
+
+impl<T> BatchItemResult<T> {
+    /// Whether the item was processed successfully
+    pub fn is_ok(&self) -> bool {
+        matches!(self, BatchItemResult::Ok(_))
+    }
+
+    /// Convert the result into a `Result`, with the error from Vault
+    pub fn into_result(self) -> Result<T, Error> {
+        match self {
+            BatchItemResult::Ok(value) => Ok(value),
+            BatchItemResult::Err(error) => Err(Error::VaultError(error)),
+        }
+    }
+
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> BatchItemResult<U> {
+        match self {
+            BatchItemResult::Ok(value) => BatchItemResult::Ok(f(value)),
+            BatchItemResult::Err(error) => BatchItemResult::Err(error),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for BatchItemResult<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match value.get("error").and_then(Value::as_str) {
+            Some(error) if !error.is_empty() => Ok(BatchItemResult::Err(error.to_string())),
+            _ => serde_json::from_value(value)
+                .map(BatchItemResult::Ok)
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct BatchRequest<'a, T> {
+    batch_input: &'a [T],
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+struct BatchResponse<T> {
+    batch_results: Vec<BatchItemResult<T>>,
+}
+
+/// Body of the response to a batch, read whatever its status code. Vault responds with a 400
+/// when every item fails, with the error of each item in `batch_results`.
+#[derive(Deserialize, Debug)]
+#[serde(bound = "T: DeserializeOwned")]
+struct BatchBody<T> {
+    #[serde(default)]
+    data: Option<BatchResponse<T>>,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+impl<T> BatchBody<T> {
+    fn into_results(self) -> Result<Vec<BatchItemResult<T>>, Error> {
+        match self.data {
+            Some(data) => Ok(data.batch_results),
+            None => Err(Error::VaultError(self.errors.join("; "))),
+        }
+    }
+}
+
+async fn post_batch<V, I, T>(
+    client: &V,
+    path: &str,
+    inputs: &[I],
+) -> Result<Vec<BatchItemResult<T>>, Error>
+where
+    V: crate::Vault + Send + Sync,
+    I: Serialize + Send + Sync,
+    T: DeserializeOwned + std::fmt::Debug + Send,
+{
+    let payload = BatchRequest {
+        batch_input: inputs,
+    };
+    let body: BatchBody<T> = client
+        .write_raw(path, &payload, crate::Method::POST, &Default::default())
+        .await?;
+    body.into_results()
+}
+
+#[derive(Deserialize, Debug)]
+struct HmacOutput {
+    hmac: String,
+}
+
+#[derive(Deserialize)]
+struct DataKeyOutput {
+    #[serde(default)]
+    plaintext: Option<String>,
+    ciphertext: String,
+    #[serde(default)]
+    key_version: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct SignatureOutput {
+    signature: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct VerifyOutput {
+    valid: bool,
+}
+
+/// Public keys of the versions of an asymmetric key, to verify signatures without a request to
+/// Vault
+///
+/// Only `ed25519` and `ecdsa-p256` keys are supported. ECDSA signatures must use the default
+/// `sha2-256` hash algorithm and `asn1` marshaling.
+#[cfg(feature = "transit-verify")]
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PublicKeys {
+    r#type: KeyType,
+    versions: HashMap<u64, Vec<u8>>,
+}
+
+#[cfg(feature = "transit-verify")]
+impl PublicKeys {
+    /// Extract the public keys of a key read from Vault. Versions older than the minimum
+    /// decryption version of the key are skipped, as Vault does not verify signatures with them.
+    pub fn from_key(key: &Key) -> Result<Self, Error> {
+        match key.r#type {
+            KeyType::ED25519 | KeyType::EC256 => {}
+            other => {
+                return Err(Error::InvalidRequest(format!(
+                    "signatures of {:?} keys cannot be verified locally",
+                    other
+                )))
+            }
+        }
+
+        let mut versions = HashMap::new();
+        for (version, value) in &key.keys {
+            let version: u64 = version.parse()?;
+            if version < key.min_decryption_version {
+                continue;
+            }
+            let public_key = value
+                .public_key()
+                .filter(|key| !key.is_empty())
+                .ok_or(Error::MalformedResponse)?;
+            let public_key = match key.r#type {
+                KeyType::ED25519 => crate::crypto::base64().decode(public_key)?,
+                _ => p256_point(public_key)?,
+            };
+            let _ = versions.insert(version, public_key);
+        }
+
+        Ok(Self {
+            r#type: key.r#type,
+            versions,
+        })
+    }
+
+    /// Verify a signature like `vault:v1:...` of the input. Returns `Ok(false)` if the signature
+    /// does not match.
+    pub fn verify(&self, input: &[u8], signature: &str) -> Result<bool, Error> {
+        let mut parts = signature.splitn(3, ':');
+        let (version, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some("vault"), Some(version), Some(signature)) if version.starts_with('v') => (
+                version[1..].parse::<u64>()?,
+                crate::crypto::base64().decode(signature)?,
+            ),
+            _ => {
+                return Err(Error::InvalidRequest(
+                    "signature is not in the `vault:v<version>:<signature>` format".to_string(),
+                ))
+            }
+        };
+        let public_key = self.versions.get(&version).ok_or_else(|| {
+            Error::InvalidRequest(format!("no public key for version {} of the key", version))
+        })?;
+
+        let algorithm: &dyn ring::signature::VerificationAlgorithm = match self.r#type {
+            KeyType::ED25519 => &ring::signature::ED25519,
+            _ => &ring::signature::ECDSA_P256_SHA256_ASN1,
+        };
+        Ok(
+            ring::signature::UnparsedPublicKey::new(algorithm, public_key)
+                .verify(input, &signature)
+                .is_ok(),
+        )
+    }
+}
+
+/// Extract the uncompressed point from a P-256 public key in PEM encoded SubjectPublicKeyInfo
+#[cfg(feature = "transit-verify")]
+fn p256_point(pem: &str) -> Result<Vec<u8>, Error> {
+    use crate::utils::der_element;
+
+    const SEQUENCE: u8 = 0x30;
+    const OBJECT_IDENTIFIER: u8 = 0x06;
+    const BIT_STRING: u8 = 0x03;
+    // id-ecPublicKey and prime256v1
+    const EC_PUBLIC_KEY: [u8; 7] = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+    const P256: [u8; 8] = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+    let der: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    let der = crate::crypto::base64().decode(&der)?;
+
+    let point = der_element(&der)
+        .filter(|(tag, _, rest)| *tag == SEQUENCE && rest.is_empty())
+        .and_then(|(_, spki, _)| der_element(spki))
+        .filter(|(tag, _, _)| *tag == SEQUENCE)
+        .and_then(|(_, algorithm, key)| {
+            let (tag, oid, parameters) = der_element(algorithm)?;
+            if tag != OBJECT_IDENTIFIER || oid != EC_PUBLIC_KEY {
+                return None;
+            }
+            let (tag, curve, rest) = der_element(parameters)?;
+            if tag != OBJECT_IDENTIFIER || curve != P256 || !rest.is_empty() {
+                return None;
+            }
+            match der_element(key)? {
+                // A bit string without unused bits, holding an uncompressed point
+                (BIT_STRING, [0, point @ ..], []) if point.len() == 65 && point[0] == 0x04 => {
+                    Some(point.to_vec())
+                }
+                _ => None,
+            }
+        });
+    point.ok_or_else(|| Error::InvalidRequest("public key is not a P-256 key".to_string()))
+}
+
 impl Default for KeyType {
     fn default() -> Self {
         KeyType::AES256GCM96
@@ -173,6 +571,126 @@ pub trait Transit {
         key: &str,
         configuration: &ConfigureKey,
     ) -> Result<Response, Error>;
+    /// Rotate the key to a new version, used for encryption and signing from then on
+    async fn rotate_key(&self, path: &str, key: &str) -> Result<Response, Error>;
+    /// Permanently delete the versions of the key older than `min_available_version`. The
+    /// minimum decryption and encryption versions must not be older than the versions kept.
+    async fn trim_key(
+        &self,
+        path: &str,
+        key: &str,
+        min_available_version: u64,
+    ) -> Result<Response, Error>;
+    /// Rotate the key, then only allow the last `keep_last_n` versions, including the new one,
+    /// to be used for decryption and verification, by raising the minimum decryption version.
+    /// Older versions are kept, and can be allowed again with [`Transit::configure_key`].
+    ///
+    /// The key is read again after the rotation, and the operation stops with an error if the
+    /// rotation did not produce a new version. Returns the key as it is at the end.
+    async fn rotate_and_retire(
+        &self,
+        path: &str,
+        key: &str,
+        keep_last_n: u64,
+    ) -> Result<Key, Error> {
+        if keep_last_n == 0 {
+            return Err(Error::InvalidRequest(
+                "At least one version of the key must be kept".to_string(),
+            ));
+        }
+
+        let before = self.read_key(path, key).await?;
+        let _ = self.rotate_key(path, key).await?;
+        let rotated = self.read_key(path, key).await?;
+        let latest = rotated.latest_version();
+        if latest <= before.latest_version() {
+            return Err(Error::InvalidVaultResponse(format!(
+                "Key {} was not rotated: latest version is still {}",
+                key, latest
+            )));
+        }
+
+        let min_version = latest.saturating_sub(keep_last_n - 1).max(1);
+        if min_version <= rotated.min_decryption_version {
+            return Ok(rotated);
+        }
+        let configuration = ConfigureKey {
+            min_decryption_version: Some(min_version),
+            // 0 always encrypts with the latest version
+            min_encryption_version: match rotated.min_encryption_version {
+                0 => None,
+                version => Some(version.max(min_version)),
+            },
+            ..Default::default()
+        };
+        let _ = self.configure_key(path, key, &configuration).await?;
+        self.read_key(path, key).await
+    }
+    /// Rotate the key, then only allow the last `keep_last_n` versions, including the new one,
+    /// to be used for decryption and verification, and delete older versions.
+    ///
+    /// The key is read again after each step, and the operation stops with an error if the
+    /// rotation did not produce a new version. Returns the key as it is at the end.
+    async fn rotate_and_trim(&self, path: &str, key: &str, keep_last_n: u64) -> Result<Key, Error> {
+        let rotated = self.rotate_and_retire(path, key, keep_last_n).await?;
+        let min_version = rotated.min_decryption_version;
+        if min_version <= rotated.min_available_version {
+            return Ok(rotated);
+        }
+        let _ = self.trim_key(path, key, min_version).await?;
+        self.read_key(path, key).await
+    }
+    /// Generate the HMAC of each input with the key. Results are in the order of the inputs.
+    async fn hmac_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[BatchInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<String>>, Error>;
+    /// Sign each input with the key. Results are in the order of the inputs.
+    async fn sign_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[BatchInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<String>>, Error>;
+    /// Verify the HMAC or signature of each input with the key. Results are in the order of
+    /// the inputs, and are `Ok(false)` for items that do not match.
+    async fn verify_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[VerifyInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<bool>>, Error>;
+    /// Generate a data key, returned both in plaintext and encrypted with the key
+    async fn generate_data_key(
+        &self,
+        path: &str,
+        key: &str,
+        request: &DataKeyRequest<'_, '_>,
+    ) -> Result<DataKey, Error>;
+    /// Generate a data key, only returned encrypted with the key. The data key can be
+    /// decrypted with the key later, when it is needed.
+    async fn generate_wrapped_data_key(
+        &self,
+        path: &str,
+        key: &str,
+        request: &DataKeyRequest<'_, '_>,
+    ) -> Result<DataKey, Error>;
+    /// Sign the digest of the input, computed locally with the digest implementation in use,
+    /// rather than sending the whole input to Vault. Not supported by `ed25519` keys.
+    async fn sign_prehashed(
+        &self,
+        path: &str,
+        key: &str,
+        input: &[u8],
+        algorithm: HashAlgorithm,
+    ) -> Result<String, Error>;
+    /// Read the public keys of an asymmetric key, to verify signatures locally
+    #[cfg(feature = "transit-verify")]
+    async fn public_keys(&self, path: &str, key: &str) -> Result<PublicKeys, Error> {
+        PublicKeys::from_key(&self.read_key(path, key).await?)
+    }
 }
 
 #[async_trait]
@@ -219,6 +737,134 @@ where
         let path = format!("{}/keys/{}/config", path, key);
         self.post(&path, configuration, false).await
     }
+
+    async fn rotate_key(&self, path: &str, key: &str) -> Result<Response, Error> {
+        let path = format!("{}/keys/{}/rotate", path, key);
+        self.post(&path, &crate::Empty, false).await
+    }
+
+    async fn trim_key(
+        &self,
+        path: &str,
+        key: &str,
+        min_available_version: u64,
+    ) -> Result<Response, Error> {
+        #[derive(Serialize)]
+        struct Payload {
+            min_available_version: u64,
+        }
+
+        let path = format!("{}/keys/{}/trim", path, key);
+        self.post(
+            &path,
+            &Payload {
+                min_available_version,
+            },
+            false,
+        )
+        .await
+    }
+
+    async fn hmac_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[BatchInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<String>>, Error> {
+        let path = format!("{}/hmac/{}", path, key);
+        let results: Vec<BatchItemResult<HmacOutput>> = post_batch(self, &path, inputs).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map(|output| output.hmac))
+            .collect())
+    }
+
+    async fn sign_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[BatchInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<String>>, Error> {
+        let path = format!("{}/sign/{}", path, key);
+        let results: Vec<BatchItemResult<SignatureOutput>> =
+            post_batch(self, &path, inputs).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map(|output| output.signature))
+            .collect())
+    }
+
+    async fn generate_data_key(
+        &self,
+        path: &str,
+        key: &str,
+        request: &DataKeyRequest<'_, '_>,
+    ) -> Result<DataKey, Error> {
+        let path = format!("{}/datakey/plaintext/{}", path, key);
+        let output: DataKeyOutput = self.post(&path, request, true).await?.data()?;
+        let plaintext = output
+            .plaintext
+            .ok_or_else(|| Error::InvalidVaultResponse("Missing plaintext data key".to_string()))?;
+        Ok(DataKey {
+            plaintext: Some(crate::crypto::base64().decode(&plaintext)?),
+            ciphertext: output.ciphertext,
+            key_version: output.key_version,
+        })
+    }
+
+    async fn generate_wrapped_data_key(
+        &self,
+        path: &str,
+        key: &str,
+        request: &DataKeyRequest<'_, '_>,
+    ) -> Result<DataKey, Error> {
+        let path = format!("{}/datakey/wrapped/{}", path, key);
+        let output: DataKeyOutput = self.post(&path, request, true).await?.data()?;
+        Ok(DataKey {
+            plaintext: None,
+            ciphertext: output.ciphertext,
+            key_version: output.key_version,
+        })
+    }
+
+    async fn sign_prehashed(
+        &self,
+        path: &str,
+        key: &str,
+        input: &[u8],
+        algorithm: HashAlgorithm,
+    ) -> Result<String, Error> {
+        #[derive(Serialize)]
+        struct Request {
+            #[serde(serialize_with = "crate::utils::serialize_bytes")]
+            input: Vec<u8>,
+            prehashed: bool,
+            hash_algorithm: HashAlgorithm,
+        }
+
+        let path = format!("{}/sign/{}", path, key);
+        let payload = Request {
+            input: crate::crypto::digest()?.digest(algorithm, input)?,
+            prehashed: true,
+            hash_algorithm: algorithm,
+        };
+        let output: SignatureOutput = self.post(&path, &payload, true).await?.data()?;
+        Ok(output.signature)
+    }
+
+    async fn verify_batch(
+        &self,
+        path: &str,
+        key: &str,
+        inputs: &[VerifyInput<'_, '_>],
+    ) -> Result<Vec<BatchItemResult<bool>>, Error> {
+        let path = format!("{}/verify/{}", path, key);
+        let results: Vec<BatchItemResult<VerifyOutput>> = post_batch(self, &path, inputs).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map(|output| output.valid))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -255,4 +901,452 @@ mod tests {
         let keys = Transit::list_keys(&client, &path).await.unwrap();
         assert_eq!(vec!["test"], keys);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_rotate_and_trim_keys() {
+        let client = crate::tests::vault_client();
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("transit"),
+            r#type: "transit".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+        let create_key = CreateKey {
+            name: "test".to_string(),
+            ..Default::default()
+        };
+        let _ = Transit::create_key(&client, &mount.path, &create_key)
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            let _ = Transit::rotate_key(&client, &mount.path, "test")
+                .await
+                .unwrap();
+        }
+        assert!(Transit::rotate_and_trim(&client, &mount.path, "test", 0)
+            .await
+            .is_err());
+
+        let key = Transit::rotate_and_retire(&client, &mount.path, "test", 3)
+            .await
+            .unwrap();
+        assert_eq!(key.latest_version(), 5);
+        assert_eq!(key.min_decryption_version, 3);
+        assert_eq!(key.keys.len(), 5);
+
+        let key = Transit::rotate_and_trim(&client, &mount.path, "test", 2)
+            .await
+            .unwrap();
+        assert_eq!(key.latest_version(), 6);
+        assert_eq!(key.min_decryption_version, 5);
+        assert_eq!(key.min_available_version, 5);
+        assert_eq!(key.keys.len(), 2);
+    }
+
+    #[cfg(feature = "ring")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_sign_prehashed_inputs() {
+        let client = crate::tests::vault_client();
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("transit"),
+            r#type: "transit".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+        let create_key = CreateKey {
+            name: "signing".to_string(),
+            r#type: KeyType::EC256,
+            ..Default::default()
+        };
+        let _ = Transit::create_key(&client, &mount.path, &create_key)
+            .await
+            .unwrap();
+
+        let signature = Transit::sign_prehashed(
+            &client,
+            &mount.path,
+            "signing",
+            b"payload",
+            HashAlgorithm::Sha2_256,
+        )
+        .await
+        .unwrap();
+        let verify = [VerifyInput {
+            input: b"payload",
+            signature: Some(signature),
+            ..Default::default()
+        }];
+        let results = Transit::verify_batch(&client, &mount.path, "signing", &verify)
+            .await
+            .unwrap();
+        assert_eq!(results, [BatchItemResult::Ok(true)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_generate_data_keys() {
+        let client = crate::tests::vault_client();
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("transit"),
+            r#type: "transit".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+        let create_key = CreateKey {
+            name: "envelope".to_string(),
+            ..Default::default()
+        };
+        let _ = Transit::create_key(&client, &mount.path, &create_key)
+            .await
+            .unwrap();
+
+        let request = DataKeyRequest {
+            bits: Some(512),
+            ..Default::default()
+        };
+        let data_key = Transit::generate_data_key(&client, &mount.path, "envelope", &request)
+            .await
+            .unwrap();
+        assert_eq!(data_key.plaintext.as_ref().unwrap().len(), 64);
+        assert!(data_key.ciphertext.starts_with("vault:v1:"));
+        assert!(!format!("{:?}", data_key)
+            .contains(&crate::crypto::base64().encode(&data_key.plaintext.unwrap())));
+
+        let wrapped = Transit::generate_wrapped_data_key(
+            &client,
+            &mount.path,
+            "envelope",
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert!(wrapped.plaintext.is_none());
+        assert_eq!(wrapped.key_version, 1);
+    }
+
+    #[test]
+    fn key_versions_are_typed() {
+        let key: Key = serde_json::from_value(serde_json::json!({
+            "name": "signing",
+            "type": "ecdsa-p256",
+            "derived": false,
+            "exportable": false,
+            "allow_plaintext_backup": false,
+            "deletion_allowed": false,
+            "keys": {
+                "1": {
+                    "creation_time": "2023-09-12T15:19:49.394915-07:00",
+                    "name": "P-256",
+                    "public_key": "-----BEGIN PUBLIC KEY-----"
+                }
+            },
+            "latest_version": 3,
+            "min_available_version": 0,
+            "min_decryption_version": 1,
+            "min_encryption_version": 0,
+            "auto_rotate_period": 2592000,
+            "imported_key": true,
+            "supports_encryption": false,
+            "supports_decryption": false,
+            "supports_derivation": false,
+            "supports_signing": true
+        }))
+        .unwrap();
+        assert_eq!(key.latest_version(), 3);
+        assert_eq!(key.auto_rotate_period, 2_592_000);
+        assert!(key.imported_key);
+        let version = key.version(1).unwrap();
+        assert_eq!(version.public_key(), Some("-----BEGIN PUBLIC KEY-----"));
+        assert_eq!(
+            version.creation_time(),
+            Some(UNIX_EPOCH + Duration::from_micros(1_694_557_189_394_915))
+        );
+
+        let version: KeyVersion = serde_json::from_value(serde_json::json!(1442851412)).unwrap();
+        assert_eq!(
+            version.creation_time(),
+            Some(UNIX_EPOCH + Duration::from_secs(1_442_851_412))
+        );
+        assert!(version.public_key().is_none());
+    }
+
+    #[test]
+    fn batch_results_can_be_partial_failures() {
+        let response: BatchResponse<HmacOutput> = serde_json::from_value(serde_json::json!({
+            "batch_results": [
+                {"hmac": "vault:v1:abc"},
+                {"error": "unable to decode input as base64"},
+                {"hmac": "vault:v1:def", "error": ""},
+            ]
+        }))
+        .unwrap();
+        let results: Vec<BatchItemResult<String>> = response
+            .batch_results
+            .into_iter()
+            .map(|result| result.map(|output| output.hmac))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                BatchItemResult::Ok("vault:v1:abc".to_string()),
+                BatchItemResult::Err("unable to decode input as base64".to_string()),
+                BatchItemResult::Ok("vault:v1:def".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn batches_failing_entirely_are_parsed() {
+        // Body of the 400 response to a batch of which every item failed
+        let body: BatchBody<HmacOutput> = serde_json::from_value(serde_json::json!({
+            "request_id": "",
+            "lease_id": "",
+            "renewable": false,
+            "lease_duration": 0,
+            "data": {
+                "batch_results": [
+                    {"error": "unable to decode input as base64"},
+                    {"error": "unable to decode input as base64"},
+                ]
+            },
+            "wrap_info": null,
+            "warnings": null,
+            "auth": null
+        }))
+        .unwrap();
+        let results = body.into_results().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!results.iter().any(BatchItemResult::is_ok));
+
+        let body: BatchBody<HmacOutput> =
+            serde_json::from_value(serde_json::json!({"errors": ["permission denied"]})).unwrap();
+        assert!(matches!(body.into_results(), Err(Error::VaultError(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_sign_and_verify_batches() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("transit");
+        let engine = crate::sys::mounts::SecretEngine {
+            path,
+            r#type: "transit".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+        let create_key = CreateKey {
+            name: "signing".to_string(),
+            r#type: KeyType::ED25519,
+            ..Default::default()
+        };
+        let _ = Transit::create_key(&client, &mount.path, &create_key)
+            .await
+            .unwrap();
+
+        let inputs = [
+            BatchInput {
+                input: b"first",
+                ..Default::default()
+            },
+            BatchInput {
+                input: b"second",
+                ..Default::default()
+            },
+        ];
+        let signatures = Transit::sign_batch(&client, &mount.path, "signing", &inputs)
+            .await
+            .unwrap();
+        assert!(signatures.iter().all(BatchItemResult::is_ok));
+
+        let signature = signatures[0].clone().into_result().unwrap();
+        let verify = [
+            VerifyInput {
+                input: b"first",
+                signature: Some(signature.clone()),
+                ..Default::default()
+            },
+            VerifyInput {
+                input: b"second",
+                signature: Some(signature),
+                ..Default::default()
+            },
+        ];
+        let results = Transit::verify_batch(&client, &mount.path, "signing", &verify)
+            .await
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![BatchItemResult::Ok(true), BatchItemResult::Ok(false)]
+        );
+
+        let hmacs = Transit::hmac_batch(&client, &mount.path, "signing", &inputs)
+            .await
+            .unwrap();
+        assert_eq!(hmacs.len(), 2);
+        assert!(hmacs.iter().all(BatchItemResult::is_ok));
+
+        // Every item failing is not an error of the whole batch
+        let verify = [VerifyInput {
+            input: b"first",
+            signature: Some("vault:v1:invalid".to_string()),
+            ..Default::default()
+        }];
+        let results = Transit::verify_batch(&client, &mount.path, "signing", &verify)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].is_ok());
+    }
+
+    #[cfg(feature = "transit-verify")]
+    #[test]
+    fn can_verify_ed25519_signatures() {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let mut keys = HashMap::new();
+        let _ = keys.insert(
+            "2".to_string(),
+            KeyVersion::Asymmetric {
+                creation_time: "2023-09-12T15:19:49Z".to_string(),
+                name: "ed25519".to_string(),
+                public_key: crate::crypto::base64().encode(pair.public_key().as_ref()),
+            },
+        );
+        let key = Key {
+            r#type: KeyType::ED25519,
+            keys,
+            ..Default::default()
+        };
+        let public_keys = PublicKeys::from_key(&key).unwrap();
+
+        let signature = format!(
+            "vault:v2:{}",
+            crate::crypto::base64().encode(pair.sign(b"payload").as_ref())
+        );
+        assert!(public_keys.verify(b"payload", &signature).unwrap());
+        assert!(!public_keys.verify(b"tampered", &signature).unwrap());
+        // Unknown version
+        let unknown = signature.replace("vault:v2:", "vault:v1:");
+        assert!(public_keys.verify(b"payload", &unknown).is_err());
+
+        // Version retired by the minimum decryption version
+        let key = Key {
+            min_decryption_version: 3,
+            ..key
+        };
+        let public_keys = PublicKeys::from_key(&key).unwrap();
+        assert!(matches!(
+            public_keys.verify(b"payload", &signature),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[cfg(feature = "transit-verify")]
+    #[test]
+    fn can_verify_p256_signatures() {
+        use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        let pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+            .unwrap();
+        let pem = |curve: &[u8]| {
+            let mut algorithm = vec![0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+            algorithm.extend([0x06, curve.len() as u8]);
+            algorithm.extend(curve);
+            let mut spki = vec![0x30, algorithm.len() as u8];
+            spki.extend(algorithm);
+            spki.extend([0x03, 0x42, 0x00]);
+            spki.extend(pair.public_key().as_ref());
+            let mut der = vec![0x30, spki.len() as u8];
+            der.extend(spki);
+            format!(
+                "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+                crate::crypto::base64().encode(&der)
+            )
+        };
+        let key = |public_key: String| Key {
+            r#type: KeyType::EC256,
+            keys: vec![(
+                "1".to_string(),
+                KeyVersion::Asymmetric {
+                    creation_time: "2023-09-12T15:19:49Z".to_string(),
+                    name: "P-256".to_string(),
+                    public_key,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let p256 = [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+        let public_keys = PublicKeys::from_key(&key(pem(&p256))).unwrap();
+        let signature = format!(
+            "vault:v1:{}",
+            crate::crypto::base64().encode(pair.sign(&rng, b"payload").unwrap().as_ref())
+        );
+        assert!(public_keys.verify(b"payload", &signature).unwrap());
+        assert!(!public_keys.verify(b"tampered", &signature).unwrap());
+
+        // Same point, with the curve of P-384
+        let p384 = [0x2b, 0x81, 0x04, 0x00, 0x22];
+        assert!(PublicKeys::from_key(&key(pem(&p384))).is_err());
+    }
+
+    #[cfg(feature = "transit-verify")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_verify_signatures_locally() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("transit");
+        let engine = crate::sys::mounts::SecretEngine {
+            path,
+            r#type: "transit".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(&client, &engine).await;
+
+        for r#type in &[KeyType::ED25519, KeyType::EC256] {
+            let create_key = CreateKey {
+                name: format!("{:?}", r#type),
+                r#type: *r#type,
+                ..Default::default()
+            };
+            let _ = Transit::create_key(&client, &mount.path, &create_key)
+                .await
+                .unwrap();
+
+            let inputs = [BatchInput {
+                input: b"payload",
+                ..Default::default()
+            }];
+            let signature = Transit::sign_batch(&client, &mount.path, &create_key.name, &inputs)
+                .await
+                .unwrap()
+                .remove(0)
+                .into_result()
+                .unwrap();
+
+            let keys = Transit::public_keys(&client, &mount.path, &create_key.name)
+                .await
+                .unwrap();
+            assert!(keys.verify(b"payload", &signature).unwrap());
+            assert!(!keys.verify(b"tampered", &signature).unwrap());
+
+            // Retired versions are rejected, like Vault does
+            let _ = Transit::rotate_and_retire(&client, &mount.path, &create_key.name, 1)
+                .await
+                .unwrap();
+            let keys = Transit::public_keys(&client, &mount.path, &create_key.name)
+                .await
+                .unwrap();
+            assert!(matches!(
+                keys.verify(b"payload", &signature),
+                Err(Error::InvalidRequest(_))
+            ));
+        }
+    }
 }
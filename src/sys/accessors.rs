@@ -0,0 +1,168 @@
+//! Translation between the accessors of mounts, as seen in entity aliases and audit logs, and
+//! the paths of the mounts
+use crate::sys::{AuthMethods, Mounts};
+use crate::Error;
+
+use std::sync::RwLock;
+
+/// Whether a mount is an auth method or a secrets engine
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MountKind {
+    /// Auth method, mounted under `auth/`
+    Auth,
+    /// Secrets engine
+    Secret,
+}
+
+/// A mount, identified by its accessor
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MountAccessor {
+    /// Accessor of the mount, like `auth_userpass_1793464a`
+    pub accessor: String,
+    /// Path of the mount, without the `auth/` prefix for auth methods
+    pub path: String,
+    /// Type of the auth method or secrets engine
+    pub r#type: String,
+    /// Whether the mount is an auth method or a secrets engine
+    pub kind: MountKind,
+}
+
+/// List the accessors of every auth method and secrets engine
+pub async fn mount_accessors<T>(client: &T) -> Result<Vec<MountAccessor>, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let methods = AuthMethods::list_auth(client)
+        .await?
+        .into_iter()
+        .map(|(path, method)| MountAccessor {
+            accessor: method.accessor,
+            path,
+            r#type: method.r#type,
+            kind: MountKind::Auth,
+        });
+    let engines = Mounts::list(client)
+        .await?
+        .into_iter()
+        .map(|(path, engine)| MountAccessor {
+            accessor: engine.accessor,
+            path,
+            r#type: engine.r#type,
+            kind: MountKind::Secret,
+        });
+    Ok(methods.chain(engines).collect())
+}
+
+/// Resolves accessors to mounts and back, caching the mounts.
+///
+/// The mounts are listed again when an accessor or path is not found, so mounts enabled after
+/// the first lookup are found. Mounts disabled since are only forgotten after
+/// [`AccessorResolver::invalidate`].
+#[derive(Debug, Default)]
+pub struct AccessorResolver {
+    mounts: RwLock<Option<Vec<MountAccessor>>>,
+}
+
+impl AccessorResolver {
+    /// Create a resolver with an empty cache
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Find the mount with the accessor
+    pub async fn resolve<T>(
+        &self,
+        client: &T,
+        accessor: &str,
+    ) -> Result<Option<MountAccessor>, Error>
+    where
+        T: crate::Vault + Send + Sync,
+    {
+        self.find(client, |mount| mount.accessor == accessor).await
+    }
+
+    /// Find the accessor of the mount at `path`
+    pub async fn accessor_of<T>(
+        &self,
+        client: &T,
+        kind: MountKind,
+        path: &str,
+    ) -> Result<Option<String>, Error>
+    where
+        T: crate::Vault + Send + Sync,
+    {
+        let path = path.trim_matches('/');
+        let mount = self
+            .find(client, |mount| mount.kind == kind && mount.path == path)
+            .await?;
+        Ok(mount.map(|mount| mount.accessor))
+    }
+
+    /// Forget the mounts cached
+    pub fn invalidate(&self) {
+        *self.mounts.write().expect("Lock not to be poisoned") = None;
+    }
+
+    async fn find<T, P>(&self, client: &T, predicate: P) -> Result<Option<MountAccessor>, Error>
+    where
+        T: crate::Vault + Send + Sync,
+        P: Fn(&MountAccessor) -> bool,
+    {
+        if let Some(mount) = self.cached(&predicate) {
+            return Ok(Some(mount));
+        }
+        let mounts = mount_accessors(client).await?;
+        let mount = mounts.iter().find(|mount| predicate(mount)).cloned();
+        *self.mounts.write().expect("Lock not to be poisoned") = Some(mounts);
+        Ok(mount)
+    }
+
+    fn cached<P>(&self, predicate: P) -> Option<MountAccessor>
+    where
+        P: Fn(&MountAccessor) -> bool,
+    {
+        let mounts = self.mounts.read().expect("Lock not to be poisoned");
+        mounts
+            .as_ref()?
+            .iter()
+            .find(|mount| predicate(mount))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_resolve_accessors() {
+        let client = crate::tests::vault_client();
+        let resolver = AccessorResolver::new();
+
+        let token = resolver
+            .accessor_of(&client, MountKind::Auth, "token")
+            .await
+            .unwrap()
+            .unwrap();
+        let mount = resolver.resolve(&client, &token).await.unwrap().unwrap();
+        assert_eq!(mount.path, "token");
+        assert_eq!(mount.kind, MountKind::Auth);
+
+        // Mounts enabled after the first lookup are found
+        let path = crate::tests::uuid_prefix("userpass");
+        let _mount = AuthMount::new(&client, &path, "userpass").await;
+        let accessor = resolver
+            .accessor_of(&client, MountKind::Auth, &path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(accessor.starts_with("auth_userpass_"));
+
+        assert!(resolver
+            .resolve(&client, "auth_missing_0000")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,465 @@
+//! Implements the
+//! [`/sys/internal/counters/activity`](https://developer.hashicorp.com/vault/api-docs/system/internal-counters#client-count)
+//! endpoints, which count the clients active in the cluster
+use crate::version::ServerVersion;
+use crate::{Client, Error, Method, Response};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Number of clients active, by type
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Copy)]
+pub struct ClientCounts {
+    /// Number of clients, of every type
+    #[serde(default)]
+    pub clients: u64,
+    /// Number of identity entities
+    #[serde(default)]
+    pub entity_clients: u64,
+    /// Number of tokens without an entity
+    #[serde(default)]
+    pub non_entity_clients: u64,
+    /// Number of secrets synced to external destinations
+    #[serde(default)]
+    pub secret_syncs: u64,
+    /// Number of ACME clients of PKI secrets engines
+    #[serde(default)]
+    pub acme_clients: u64,
+}
+
+/// Clients active in a mount
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MountActivity {
+    /// Path of the mount
+    pub mount_path: String,
+    /// Number of clients active
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub counts: ClientCounts,
+}
+
+/// Clients active in a namespace
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct NamespaceActivity {
+    /// ID of the namespace
+    pub namespace_id: String,
+    /// Path of the namespace. Empty for the root namespace.
+    #[serde(default)]
+    pub namespace_path: String,
+    /// Number of clients active
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub counts: ClientCounts,
+    /// Clients active, by mount
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub mounts: Vec<MountActivity>,
+}
+
+/// Clients first active in a month
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct NewClients {
+    /// Number of new clients
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub counts: ClientCounts,
+    /// New clients, by namespace
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub namespaces: Vec<NamespaceActivity>,
+}
+
+/// Clients active in a month
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MonthActivity {
+    /// Start of the month, in RFC 3339 format
+    pub timestamp: String,
+    /// Number of clients active
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub counts: ClientCounts,
+    /// Clients active, by namespace
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub namespaces: Vec<NamespaceActivity>,
+    /// Clients first active in the month
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub new_clients: NewClients,
+}
+
+/// Clients active during a period
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct ActivityReport {
+    /// Start of the period, in RFC 3339 format
+    #[serde(default)]
+    pub start_time: String,
+    /// End of the period, in RFC 3339 format
+    #[serde(default)]
+    pub end_time: String,
+    /// Number of clients active
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub total: ClientCounts,
+    /// Clients active, by namespace
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub by_namespace: Vec<NamespaceActivity>,
+    /// Clients active, by month
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub months: Vec<MonthActivity>,
+}
+
+/// Clients active in the current month
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MonthlyActivity {
+    /// Number of clients active
+    #[serde(flatten)]
+    pub counts: ClientCounts,
+    /// Clients active, by namespace
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub by_namespace: Vec<NamespaceActivity>,
+    /// Clients active, by month. Only the current month is returned.
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub months: Vec<MonthActivity>,
+}
+
+/// Whether client activity is tracked
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum ActivityTracking {
+    /// Tracking is enabled
+    Enable,
+    /// Tracking is disabled
+    Disable,
+    /// Tracking is enabled, because it was never configured. Cannot be written.
+    DefaultEnabled,
+    /// Tracking is disabled, because it was never configured. Cannot be written.
+    DefaultDisabled,
+    /// Restore the default of Vault. Only written, and read back as one of the defaults.
+    Default,
+}
+
+/// Configuration of the tracking of client activity
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct ActivityConfig {
+    /// Whether client activity is tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<ActivityTracking>,
+    /// Number of months of activity to retain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_months: Option<u64>,
+    /// Whether activity is reported automatically to HashiCorp. Read only.
+    #[serde(default, skip_serializing)]
+    pub reporting_enabled: Option<bool>,
+    /// Whether activity can be queried. Read only.
+    #[serde(default, skip_serializing)]
+    pub queries_available: Option<bool>,
+    /// Start of the current billing period, in RFC 3339 format. Read only.
+    #[serde(default, skip_serializing)]
+    pub billing_start_timestamp: Option<String>,
+    /// Minimum number of months of activity which must be retained. Read only.
+    #[serde(default, skip_serializing)]
+    pub minimum_retention_months: Option<u64>,
+}
+
+/// Implements the
+/// [`/sys/internal/counters/activity`](https://developer.hashicorp.com/vault/api-docs/system/internal-counters#client-count)
+/// endpoints
+#[async_trait]
+pub trait Activity {
+    /// Count the clients active during the period. Without a period, the current billing period
+    /// is counted.
+    async fn activity(&self, period: &ActivityExport) -> Result<ActivityReport, Error>;
+
+    /// Count the clients active in the current month
+    async fn monthly_activity(&self) -> Result<MonthlyActivity, Error>;
+
+    /// Read the configuration of the tracking of client activity
+    async fn activity_config(&self) -> Result<ActivityConfig, Error>;
+
+    /// Configure the tracking of client activity. Read only fields are ignored.
+    async fn update_activity_config(&self, config: &ActivityConfig) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Activity for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn activity(&self, period: &ActivityExport) -> Result<ActivityReport, Error> {
+        self.get_with_query("sys/internal/counters/activity", period)
+            .await?
+            .data()
+    }
+
+    async fn monthly_activity(&self) -> Result<MonthlyActivity, Error> {
+        self.get("sys/internal/counters/activity/monthly")
+            .await?
+            .data()
+    }
+
+    async fn activity_config(&self) -> Result<ActivityConfig, Error> {
+        self.get("sys/internal/counters/config").await?.data()
+    }
+
+    async fn update_activity_config(&self, config: &ActivityConfig) -> Result<Response, Error> {
+        self.post("sys/internal/counters/config", config, false)
+            .await
+    }
+}
+
+/// A client that was active during the exported period
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct ActivityRecord {
+    /// ID of the client
+    pub client_id: String,
+    /// Type of the client, like `entity`, `non-entity-token` or `acme`
+    #[serde(default)]
+    pub client_type: String,
+    /// Namespace the client was first active in
+    #[serde(default)]
+    pub namespace_id: String,
+    /// Path of the namespace the client was first active in
+    #[serde(default)]
+    pub namespace_path: String,
+    /// Accessor of the mount the client authenticated with
+    #[serde(default)]
+    pub mount_accessor: String,
+    /// Path of the mount the client authenticated with
+    #[serde(default)]
+    pub mount_path: String,
+    /// Time the client was first active in the period, in RFC 3339 format
+    #[serde(default)]
+    pub timestamp: String,
+    /// Whether the client is a token without an entity
+    #[serde(default)]
+    pub non_entity: bool,
+    /// Name of the entity
+    #[serde(default)]
+    pub entity_name: String,
+    /// Name of the entity alias
+    #[serde(default)]
+    pub entity_alias_name: String,
+    /// Metadata of the entity
+    #[serde(default)]
+    pub entity_metadata: HashMap<String, String>,
+    /// Policies of the entity
+    #[serde(default)]
+    pub policies: Vec<String>,
+}
+
+/// Period to count or export the activity of
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct ActivityExport {
+    /// Start of the period, in RFC 3339 format. Defaults to the start of the billing period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    /// End of the period, in RFC 3339 format. Defaults to the end of the last month.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct Query<'a> {
+    #[serde(flatten)]
+    export: &'a ActivityExport,
+    format: &'static str,
+}
+
+/// Splits a body received in chunks into JSON lines
+#[derive(Debug, Default)]
+struct JsonLines {
+    buffer: Vec<u8>,
+}
+
+impl JsonLines {
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Returns the next complete, non empty, line
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let _ = line.pop();
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                return Some(line);
+            }
+        }
+        None
+    }
+
+    /// Returns the last line, which is not terminated by a newline
+    fn finish(&mut self) -> Option<Vec<u8>> {
+        let line = std::mem::take(&mut self.buffer);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            None
+        } else {
+            Some(line)
+        }
+    }
+}
+
+fn parse(line: &[u8]) -> Result<ActivityRecord, Error> {
+    Ok(serde_json::from_slice(line)?)
+}
+
+/// Export of the clients active during a period, streamed from Vault
+pub type ActivityRecords = BoxStream<'static, Result<ActivityRecord, Error>>;
+
+/// Implements the
+/// [`/sys/internal/counters/activity/export`](https://developer.hashicorp.com/vault/api-docs/system/internal-counters#activity-export)
+/// endpoint
+///
+/// The export is streamed from the HTTP client rather than read through [`crate::Vault`], so
+/// this trait is only implemented by [`Client`].
+#[async_trait]
+pub trait ExportActivity {
+    /// Export the clients active during the period, as they are received from Vault.
+    ///
+    /// Records are parsed as the response body is streamed, so that the whole export does not
+    /// need to be held in memory. A record which cannot be parsed is returned as an error, and
+    /// is followed by the next records. The stream ends after an error reading the response, or
+    /// once the response exceeds the size limit of the client, with
+    /// [`Error::ResponseTooLarge`].
+    ///
+    /// This endpoint is available from Vault 1.14.
+    async fn export_activity(&self, export: &ActivityExport) -> Result<ActivityRecords, Error>;
+}
+
+#[async_trait]
+impl ExportActivity for Client {
+    async fn export_activity(&self, export: &ActivityExport) -> Result<ActivityRecords, Error> {
+        self.require_version("Activity export", &ServerVersion::new(1, 14, 0))
+            .await?;
+        let query = Query {
+            export,
+            format: "json",
+        };
+        let request = self
+            .build_request("sys/internal/counters/activity/export", Method::GET)?
+            .query(&query)
+            .build()?;
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            let body = self.read_text(response).await?;
+            return match serde_json::from_str::<Response>(&body) {
+                Ok(Response::Error { errors }) => Err(Error::VaultError(errors.join("; "))),
+                _ => Err(Error::UnexpectedResponse(body)),
+            };
+        }
+        let limit = self.limits.max_response_bytes;
+        if let Some(limit) = limit {
+            if response
+                .content_length()
+                .is_some_and(|length| length > limit as u64)
+            {
+                return Err(Error::ResponseTooLarge { limit });
+            }
+        }
+
+        let state = Some((response, JsonLines::default(), 0));
+        Ok(stream::unfold(state, move |state| async move {
+            let (mut response, mut lines, mut read) = state?;
+            loop {
+                if let Some(line) = lines.next_line() {
+                    return Some((parse(&line), Some((response, lines, read))));
+                }
+                match response.chunk().await {
+                    Ok(Some(chunk)) => {
+                        read += chunk.len();
+                        if let Some(limit) = limit.filter(|limit| read > *limit) {
+                            return Some((Err(Error::ResponseTooLarge { limit }), None));
+                        }
+                        lines.push(&chunk)
+                    }
+                    Ok(None) => return lines.finish().map(|line| (parse(&line), None)),
+                    Err(e) => return Some((Err(e.into()), None)),
+                }
+            }
+        })
+        .boxed())
+    }
+}
+
+#[async_trait]
+impl<T> ExportActivity for &T
+where
+    T: ExportActivity + Send + Sync,
+{
+    async fn export_activity(&self, export: &ActivityExport) -> Result<ActivityRecords, Error> {
+        T::export_activity(self, export).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_chunks_into_lines() {
+        let mut lines = JsonLines::default();
+        lines.push(b"{\"client_id\":\"a\"}\n{\"client_");
+        assert_eq!(lines.next_line().unwrap(), b"{\"client_id\":\"a\"}");
+        assert_eq!(lines.next_line(), None);
+
+        lines.push(b"id\":\"b\"}\n\n{\"client_id\":\"c\"}");
+        let record = parse(&lines.next_line().unwrap()).unwrap();
+        assert_eq!(record.client_id, "b");
+        assert_eq!(lines.next_line(), None);
+        let record = parse(&lines.finish().unwrap()).unwrap();
+        assert_eq!(record.client_id, "c");
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn activity_reports_tolerate_missing_months() {
+        let report: ActivityReport = serde_json::from_value(serde_json::json!({
+            "start_time": "2024-01-01T00:00:00Z",
+            "end_time": "2024-01-31T23:59:59Z",
+            "total": {"clients": 3, "entity_clients": 2, "non_entity_clients": 1},
+            "by_namespace": [{
+                "namespace_id": "root",
+                "namespace_path": "",
+                "counts": {"clients": 3, "entity_clients": 2, "non_entity_clients": 1},
+                "mounts": [{"mount_path": "auth/approle/", "counts": {"clients": 2}}]
+            }],
+            "months": null
+        }))
+        .unwrap();
+        assert_eq!(report.total.clients, 3);
+        assert_eq!(report.by_namespace[0].mounts[0].counts.clients, 2);
+        assert!(report.months.is_empty());
+    }
+
+    #[test]
+    fn read_only_config_is_not_written() {
+        let config = ActivityConfig {
+            enabled: Some(ActivityTracking::Enable),
+            queries_available: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({"enabled": "enable"})
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_count_activity() {
+        let client = crate::tests::vault_client();
+
+        let config = Activity::activity_config(&client).await.unwrap();
+        assert!(config.enabled.is_some());
+        let _ = Activity::monthly_activity(&client).await.unwrap();
+        let _ = Activity::activity(&client, &Default::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_export_activity() {
+        let client = crate::tests::vault_client();
+        let records: Vec<_> = ExportActivity::export_activity(&client, &Default::default())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(records.iter().all(Result::is_ok));
+    }
+}
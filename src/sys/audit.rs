@@ -0,0 +1,232 @@
+//! Implements the [`/sys/audit`](https://www.vaultproject.io/api/system/audit.html) endpoint
+use std::collections::HashMap;
+
+use crate::{Error, Response};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Audit Device
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct AuditDevice {
+    /// Path of the audit device
+    pub path: String,
+    /// Type of the audit device, like `file`, `syslog` or `socket`
+    pub r#type: String,
+    /// Specifies the human-friendly description of the audit device
+    pub description: Option<String>,
+    /// Only enable the audit device on the local cluster, and do not replicate it
+    pub local: bool,
+    /// Options specific to the type of the audit device, like `file_path` for `file` devices
+    pub options: HashMap<String, String>,
+    /// Only receive the entries that no other device with a filter has received.
+    ///
+    /// Available from Vault Enterprise 1.15.
+    pub fallback: Option<bool>,
+    /// Only receive the entries matching the filter expression, like `mount_type == kv`.
+    ///
+    /// Available from Vault Enterprise 1.15.
+    pub filter: Option<String>,
+    /// Do not write a test message to the device when enabling it
+    pub skip_test: Option<bool>,
+}
+
+const FALLBACK: &str = "fallback";
+const FILTER: &str = "filter";
+const SKIP_TEST: &str = "skip_test";
+
+// Vault receives and returns the common options with the device specific ones
+#[derive(Serialize, Deserialize, Debug)]
+struct AuditDeviceData {
+    #[serde(default, skip_serializing)]
+    path: String,
+    r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default)]
+    local: bool,
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    options: HashMap<String, String>,
+}
+
+impl From<&AuditDevice> for AuditDeviceData {
+    fn from(device: &AuditDevice) -> Self {
+        let mut options = device.options.clone();
+        if let Some(fallback) = device.fallback {
+            let _ = options.insert(FALLBACK.to_string(), fallback.to_string());
+        }
+        if let Some(filter) = &device.filter {
+            let _ = options.insert(FILTER.to_string(), filter.clone());
+        }
+        if let Some(skip_test) = device.skip_test {
+            let _ = options.insert(SKIP_TEST.to_string(), skip_test.to_string());
+        }
+        Self {
+            path: device.path.clone(),
+            r#type: device.r#type.clone(),
+            description: device.description.clone(),
+            local: device.local,
+            options,
+        }
+    }
+}
+
+impl From<AuditDeviceData> for AuditDevice {
+    fn from(mut data: AuditDeviceData) -> Self {
+        let fallback = data.options.remove(FALLBACK).map(|v| v == "true");
+        let filter = data.options.remove(FILTER);
+        let skip_test = data.options.remove(SKIP_TEST).map(|v| v == "true");
+        Self {
+            path: data.path,
+            r#type: data.r#type,
+            description: data.description.filter(|d| !d.is_empty()),
+            local: data.local,
+            options: data.options,
+            fallback,
+            filter,
+            skip_test,
+        }
+    }
+}
+
+/// Implements the [`/sys/audit`](https://www.vaultproject.io/api/system/audit.html) endpoint
+#[async_trait]
+pub trait AuditDevices {
+    /// List all the enabled audit devices
+    async fn list_audit(&self) -> Result<HashMap<String, AuditDevice>, Error>;
+
+    /// Enable an audit device.
+    ///
+    /// Returns [`Error::AuditTestFailed`] if Vault could not write its test message to the
+    /// device.
+    async fn enable_audit(&self, device: &AuditDevice) -> Result<Response, Error>;
+
+    /// Disable an audit device
+    async fn disable_audit(&self, path: &str) -> Result<Response, Error>;
+
+    /// Hash the input with the salt of the audit device at `path`, to find the value in its
+    /// logs. The hash is prefixed with its algorithm, like `hmac-sha256:`, as in the logs.
+    async fn audit_hash(&self, path: &str, input: &str) -> Result<String, Error>;
+}
+
+#[async_trait]
+impl<T> AuditDevices for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn list_audit(&self) -> Result<HashMap<String, AuditDevice>, Error> {
+        let values: HashMap<String, AuditDeviceData> = self.get("sys/audit").await?.data()?;
+
+        Ok(values
+            .into_iter()
+            .map(|(path, mut data)| {
+                data.path = path.trim_end_matches('/').to_string();
+                (data.path.clone(), data.into())
+            })
+            .collect())
+    }
+
+    async fn enable_audit(&self, device: &AuditDevice) -> Result<Response, Error> {
+        let path = format!("sys/audit/{}", device.path);
+        let data = AuditDeviceData::from(device);
+        match self.put(&path, &data, false).await {
+            Err(Error::VaultError(message)) if is_test_failure(&message) => {
+                Err(Error::AuditTestFailed(message))
+            }
+            result => result,
+        }
+    }
+
+    async fn disable_audit(&self, path: &str) -> Result<Response, Error> {
+        let path = format!("sys/audit/{}", path);
+        self.delete(&path, false).await
+    }
+
+    async fn audit_hash(&self, path: &str, input: &str) -> Result<String, Error> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            input: &'a str,
+        }
+
+        #[derive(Deserialize)]
+        struct Hash {
+            hash: String,
+        }
+
+        let path = format!("sys/audit-hash/{}", path);
+        let hash: Hash = self.post(&path, &Request { input }, true).await?.data()?;
+        Ok(hash.hash)
+    }
+}
+
+fn is_test_failure(message: &str) -> bool {
+    message.contains("failed test message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_options_are_sent_with_device_options() {
+        let device = AuditDevice {
+            path: "file".to_string(),
+            r#type: "file".to_string(),
+            options: vec![("file_path".to_string(), "stdout".to_string())]
+                .into_iter()
+                .collect(),
+            filter: Some("mount_type == kv".to_string()),
+            skip_test: Some(true),
+            ..Default::default()
+        };
+        let data = AuditDeviceData::from(&device);
+        assert_eq!(data.options["file_path"], "stdout");
+        assert_eq!(data.options["filter"], "mount_type == kv");
+        assert_eq!(data.options["skip_test"], "true");
+        assert!(!data.options.contains_key("fallback"));
+
+        assert_eq!(AuditDevice::from(data), device);
+    }
+
+    #[test]
+    fn test_write_failures_are_recognised() {
+        assert!(is_test_failure(
+            "audit backend failed test message: open /var/log/audit.log: permission denied"
+        ));
+        assert!(!is_test_failure("path already in use at file/"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_enable_audit_devices() {
+        let client = crate::tests::vault_client();
+        let path = crate::tests::uuid_prefix("file");
+
+        let device = AuditDevice {
+            path: path.clone(),
+            r#type: "file".to_string(),
+            description: Some("Audit to stdout".to_string()),
+            options: vec![("file_path".to_string(), "stdout".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let _ = AuditDevices::enable_audit(&client, &device).await.unwrap();
+
+        let devices = AuditDevices::list_audit(&client).await.unwrap();
+        assert_eq!(devices[&path].r#type, "file");
+        assert_eq!(devices[&path].options["file_path"], "stdout");
+
+        let hash = AuditDevices::audit_hash(&client, &path, "secret")
+            .await
+            .unwrap();
+        assert!(hash.starts_with("hmac-sha256:"));
+        assert_eq!(
+            AuditDevices::audit_hash(&client, &path, "secret")
+                .await
+                .unwrap(),
+            hash
+        );
+
+        let _ = AuditDevices::disable_audit(&client, &path).await.unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+//! Implements the [`/sys/auth`](https://www.vaultproject.io/api/system/auth.html) endpoint
+use std::collections::HashMap;
+
+use crate::sys::mounts::{DeprecationStatus, SecretsEngineConfig, SecretsEngineTune};
+use crate::{Error, MountPath, Response};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::map::Map;
+use serde_json::Value;
+
+/// Auth Method Mount
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default)]
+pub struct AuthMethod {
+    /// Path to the auth method, without the `auth/` prefix
+    pub path: String,
+    /// Type of auth method
+    pub r#type: String,
+    /// Specifies the human-friendly description of the mount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Configuration options for the mount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<SecretsEngineConfig>,
+    /// Accessor of the mount, used to identify the auth method in entity aliases
+    #[serde(default, skip_serializing)]
+    pub accessor: String,
+    /// Deprecation status of the builtin plugin backing the mount, as listed by Vault 1.12 and
+    /// later. External plugins do not have a deprecation status.
+    #[serde(default, skip_serializing)]
+    pub deprecation_status: Option<DeprecationStatus>,
+}
+
+/// Implements the [`/sys/auth`](https://www.vaultproject.io/api/system/auth.html) endpoint
+///
+/// Paths of auth methods are validated and normalized as a [`MountPath`] before being sent.
+#[async_trait]
+pub trait AuthMethods {
+    /// List all the enabled auth methods
+    async fn list_auth(&self) -> Result<HashMap<String, AuthMethod>, Error>;
+
+    /// Enable an auth method
+    async fn enable_auth(&self, method: &AuthMethod) -> Result<Response, Error>;
+
+    /// Disable an auth method
+    async fn disable_auth(&self, path: &str) -> Result<Response, Error>;
+
+    /// Tune the configuration of an auth method
+    async fn tune_auth(&self, path: &str, config: &SecretsEngineTune) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> AuthMethods for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn list_auth(&self) -> Result<HashMap<String, AuthMethod>, Error> {
+        let values: HashMap<String, Map<String, Value>> = self.get("sys/auth").await?.data()?;
+
+        values
+            .into_iter()
+            .map(|(path, mut map)| {
+                let path = String::from(MountPath::new(&path)?);
+                let _ = map.insert("path".to_string(), Value::String(path.clone()));
+                let method = serde_json::from_value(Value::Object(map))?;
+                Ok((path, method))
+            })
+            .collect()
+    }
+
+    async fn enable_auth(&self, method: &AuthMethod) -> Result<Response, Error> {
+        let mut value = serde_json::to_value(method)?;
+        let _ = value["path"].take();
+        let path = format!("sys/auth/{}", MountPath::new(&method.path)?);
+        self.post(&path, &value, false).await
+    }
+
+    async fn disable_auth(&self, path: &str) -> Result<Response, Error> {
+        let path = format!("sys/auth/{}", MountPath::new(path)?);
+        self.delete(&path, false).await
+    }
+
+    async fn tune_auth(&self, path: &str, config: &SecretsEngineTune) -> Result<Response, Error> {
+        let path = format!("sys/auth/{}/tune", MountPath::new(path)?);
+        self.post(&path, config, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::AuthMount;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_list_auth_methods() {
+        let client = crate::tests::vault_client();
+
+        let path = crate::tests::uuid_prefix("userpass");
+        let _mount = AuthMount::new(&client, &path, "userpass").await;
+
+        let methods = AuthMethods::list_auth(&client).await.unwrap();
+        let method = &methods["token"];
+        assert_eq!(method.r#type, "token");
+        assert!(!method.accessor.is_empty());
+        assert_eq!(methods[&path].r#type, "userpass");
+    }
+}
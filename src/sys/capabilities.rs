@@ -0,0 +1,269 @@
+//! Implements the
+//! [`/sys/capabilities-self`](https://www.vaultproject.io/api/system/capabilities-self.html)
+//! endpoint, and compares the token in use against the policies and capabilities a service
+//! requires
+use crate::auth::Token;
+use crate::Error;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Implements the
+/// [`/sys/capabilities-self`](https://www.vaultproject.io/api/system/capabilities-self.html)
+/// endpoint
+#[async_trait]
+pub trait Capabilities {
+    /// Returns the capabilities of the token making the request on each of the paths, like
+    /// `read` or `update`
+    async fn capabilities_self(
+        &self,
+        paths: &[&str],
+    ) -> Result<HashMap<String, Vec<String>>, Error>;
+}
+
+#[async_trait]
+impl<T> Capabilities for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn capabilities_self(
+        &self,
+        paths: &[&str],
+    ) -> Result<HashMap<String, Vec<String>>, Error> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            paths: &'a [&'a str],
+        }
+
+        let mut data: HashMap<String, serde_json::Value> = self
+            .post("sys/capabilities-self", &Request { paths }, true)
+            .await?
+            .data()?;
+        paths
+            .iter()
+            .map(|path| {
+                let capabilities = data
+                    .remove(*path)
+                    .ok_or_else(|| {
+                        Error::InvalidVaultResponse(format!("Missing capabilities of {}", path))
+                    })
+                    .and_then(|capabilities| Ok(serde_json::from_value(capabilities)?))?;
+                Ok((path.to_string(), capabilities))
+            })
+            .collect()
+    }
+}
+
+/// Policies and capabilities a service requires from its token
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct TokenRequirements {
+    /// Policies the token must have, directly or through its entity
+    pub policies: Vec<String>,
+    /// Capabilities the token must have, by path
+    pub capabilities: Vec<(String, Vec<String>)>,
+}
+
+impl TokenRequirements {
+    /// Require the policy
+    pub fn policy<S: Into<String>>(mut self, policy: S) -> Self {
+        self.policies.push(policy.into());
+        self
+    }
+
+    /// Require the capabilities, like `read` or `update`, on the path
+    pub fn capabilities<S, I, C>(mut self, path: S, capabilities: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = C>,
+        C: Into<String>,
+    {
+        let capabilities = capabilities.into_iter().map(Into::into).collect();
+        self.capabilities.push((path.into(), capabilities));
+        self
+    }
+}
+
+/// Capabilities missing on a path
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MissingCapabilities {
+    /// Path
+    pub path: String,
+    /// Capabilities required but not granted
+    pub missing: Vec<String>,
+    /// Capabilities granted on the path
+    pub granted: Vec<String>,
+}
+
+/// Policies and capabilities required but missing from a token
+#[derive(Debug, Eq, PartialEq, Default, Clone)]
+pub struct TokenDiff {
+    /// Policies required but not attached to the token
+    pub missing_policies: Vec<String>,
+    /// Capabilities required but not granted, by path
+    pub missing_capabilities: Vec<MissingCapabilities>,
+}
+
+impl TokenDiff {
+    /// Returns whether the token has all the policies and capabilities required
+    pub fn is_satisfied(&self) -> bool {
+        self.missing_policies.is_empty() && self.missing_capabilities.is_empty()
+    }
+}
+
+impl fmt::Display for TokenDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_satisfied() {
+            return write!(f, "token has all the required policies and capabilities");
+        }
+        let mut problems = self
+            .missing_policies
+            .iter()
+            .map(|policy| format!("token lacks policy {}", policy))
+            .chain(self.missing_capabilities.iter().map(|missing| {
+                format!(
+                    "token lacks {} on {} (granted: {})",
+                    missing.missing.join(", "),
+                    missing.path,
+                    if missing.granted.is_empty() {
+                        "none".to_string()
+                    } else {
+                        missing.granted.join(", ")
+                    }
+                )
+            }));
+        if let Some(first) = problems.next() {
+            write!(f, "{}", first)?;
+        }
+        for problem in problems {
+            write!(f, "; {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the policies and capabilities of the token in use against those required, with
+/// `auth/token/lookup-self` and `sys/capabilities-self`
+pub async fn token_diff<T>(client: &T, required: &TokenRequirements) -> Result<TokenDiff, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let token = Token::lookup_self(client).await?;
+    let policies: Vec<&String> = token
+        .policies
+        .iter()
+        .chain(token.identity_policies.iter().flatten())
+        .collect();
+    let missing_policies = required
+        .policies
+        .iter()
+        .filter(|policy| !policies.contains(policy))
+        .cloned()
+        .collect();
+
+    let paths: Vec<&str> = required
+        .capabilities
+        .iter()
+        .map(|(path, _)| path.as_str())
+        .collect();
+    let granted = if paths.is_empty() {
+        HashMap::new()
+    } else {
+        Capabilities::capabilities_self(client, &paths).await?
+    };
+    let missing_capabilities = required
+        .capabilities
+        .iter()
+        .filter_map(|(path, capabilities)| {
+            let granted = granted.get(path).cloned().unwrap_or_default();
+            missing(capabilities, &granted).map(|missing| MissingCapabilities {
+                path: path.clone(),
+                missing,
+                granted,
+            })
+        })
+        .collect();
+
+    Ok(TokenDiff {
+        missing_policies,
+        missing_capabilities,
+    })
+}
+
+/// Capabilities required but not granted, if any. `root` grants every capability, and `deny`
+/// revokes them all.
+fn missing(required: &[String], granted: &[String]) -> Option<Vec<String>> {
+    let has = |capability: &str| granted.iter().any(|granted| granted == capability);
+    let missing: Vec<String> = if has("deny") {
+        required.to_vec()
+    } else if has("root") {
+        vec![]
+    } else {
+        required
+            .iter()
+            .filter(|capability| !has(capability))
+            .cloned()
+            .collect()
+    };
+    if missing.is_empty() {
+        None
+    } else {
+        Some(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn missing_capabilities_account_for_root_and_deny() {
+        let required = strings(&["read", "update"]);
+        assert_eq!(
+            missing(&required, &strings(&["read"])),
+            Some(strings(&["update"]))
+        );
+        assert_eq!(missing(&required, &strings(&["root"])), None);
+        assert_eq!(
+            missing(&required, &strings(&["deny", "read"])),
+            Some(required.clone())
+        );
+        assert_eq!(missing(&required, &strings(&["read", "update"])), None);
+    }
+
+    #[test]
+    fn diff_is_actionable() {
+        let diff = TokenDiff {
+            missing_policies: strings(&["app"]),
+            missing_capabilities: vec![MissingCapabilities {
+                path: "secret/data/app".to_string(),
+                missing: strings(&["update"]),
+                granted: strings(&["read"]),
+            }],
+        };
+        assert_eq!(
+            diff.to_string(),
+            "token lacks policy app; token lacks update on secret/data/app (granted: read)"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn root_token_has_every_capability() {
+        let client = crate::tests::vault_client();
+        let required = TokenRequirements::default()
+            .policy("root")
+            .policy("app")
+            .capabilities("secret/data/app", ["read", "update"])
+            .capabilities("sys/mounts", ["sudo"]);
+
+        let diff = token_diff(&client, &required).await.unwrap();
+        assert_eq!(diff.missing_policies, ["app"]);
+        assert!(diff.missing_capabilities.is_empty());
+    }
+}
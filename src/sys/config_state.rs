@@ -0,0 +1,143 @@
+//! Implements the
+//! [`/sys/config/state/sanitized`](https://developer.hashicorp.com/vault/api-docs/system/config-state)
+//! endpoint, which returns the configuration Vault was started with, without secrets
+use crate::Error;
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Configuration of a Vault server, with secrets removed
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct SanitizedConfig {
+    /// Address advertised to clients
+    #[serde(default)]
+    pub api_addr: String,
+    /// Address advertised to the other nodes of the cluster
+    #[serde(default)]
+    pub cluster_addr: String,
+    /// Name of the cluster
+    #[serde(default)]
+    pub cluster_name: String,
+    /// Default lease duration, in seconds
+    #[serde(default)]
+    pub default_lease_ttl: u64,
+    /// Maximum lease duration, in seconds
+    #[serde(default)]
+    pub max_lease_ttl: u64,
+    /// Whether the read cache is disabled
+    #[serde(default)]
+    pub disable_cache: bool,
+    /// Whether clustering is disabled
+    #[serde(default)]
+    pub disable_clustering: bool,
+    /// Whether memory locking is disabled
+    #[serde(default)]
+    pub disable_mlock: bool,
+    /// Whether the UI is served
+    #[serde(default)]
+    pub enable_ui: bool,
+    /// Whether the `sys/raw` endpoints are enabled
+    #[serde(default)]
+    pub raw_storage_endpoint: bool,
+    /// Level of the logs, like `info`
+    #[serde(default)]
+    pub log_level: String,
+    /// Format of the logs, like `standard` or `json`
+    #[serde(default)]
+    pub log_format: String,
+    /// Directory of the external plugins
+    #[serde(default)]
+    pub plugin_directory: String,
+    /// Listeners
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub listeners: Vec<ConfigBlock>,
+    /// Seals
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub seals: Vec<ConfigBlock>,
+    /// Storage backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<ConfigBlock>,
+    /// Storage backend for high availability, if it differs from the storage backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ha_storage: Option<ConfigBlock>,
+    /// Configuration of the telemetry
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub telemetry: HashMap<String, Value>,
+    /// Other settings, which depend on the version of Vault
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Block of the configuration, like a listener, a seal or a storage backend
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct ConfigBlock {
+    /// Type of the block, like `tcp` or `raft`
+    #[serde(default)]
+    pub r#type: String,
+    /// Settings of the block
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub config: HashMap<String, Value>,
+    /// Other settings, which depend on the type of block
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Implements the
+/// [`/sys/config/state/sanitized`](https://developer.hashicorp.com/vault/api-docs/system/config-state)
+/// endpoint
+#[async_trait]
+pub trait ConfigState {
+    /// Read the configuration of the server handling the request, with secrets removed. The
+    /// token needs `sudo` on the path.
+    async fn sanitized_config(&self) -> Result<SanitizedConfig, Error>;
+}
+
+#[async_trait]
+impl<T> ConfigState for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn sanitized_config(&self) -> Result<SanitizedConfig, Error> {
+        self.get("sys/config/state/sanitized").await?.data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_settings_are_kept() {
+        let config: SanitizedConfig = serde_json::from_value(serde_json::json!({
+            "api_addr": "https://vault.example.com:8200",
+            "default_lease_ttl": 2764800,
+            "disable_mlock": true,
+            "listeners": [{
+                "type": "tcp",
+                "config": {"address": "0.0.0.0:8200", "tls_disable": false}
+            }],
+            "seals": [{"type": "shamir", "disabled": false}],
+            "storage": {"type": "raft", "cluster_addr": "", "redirect_addr": ""},
+            "ha_storage": null,
+            "telemetry": null,
+            "enable_response_header_hostname": false
+        }))
+        .unwrap();
+        assert_eq!(config.default_lease_ttl, 2764800);
+        assert_eq!(config.listeners[0].config["address"], "0.0.0.0:8200");
+        assert_eq!(config.seals[0].other["disabled"], false);
+        assert_eq!(config.storage.unwrap().r#type, "raft");
+        assert!(config.ha_storage.is_none());
+        assert_eq!(config.other["enable_response_header_hostname"], false);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_sanitized_config() {
+        let client = crate::tests::vault_client();
+        let config = ConfigState::sanitized_config(&client).await.unwrap();
+        assert!(!config.listeners.is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! Implements the [`/sys/internal/counters`](https://www.vaultproject.io/api-docs/system/internal-counters)
+//! endpoints
+use crate::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Number of requests handled in a month
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct RequestCount {
+    /// Start of the month, in RFC 3339 format
+    pub start_time: String,
+    /// Number of requests handled in the month
+    pub total: u64,
+}
+
+/// A total count
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Copy)]
+pub struct Total {
+    /// The count
+    pub total: u64,
+}
+
+/// Number of tokens
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Copy)]
+pub struct TokenCounters {
+    /// Number of service tokens
+    pub service_tokens: Total,
+}
+
+/// Number of identity entities
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Copy)]
+pub struct EntityCounters {
+    /// Number of entities
+    pub entities: Total,
+}
+
+#[derive(Deserialize, Debug)]
+struct Counters<T> {
+    counters: T,
+}
+
+/// Implements the [`/sys/internal/counters`](https://www.vaultproject.io/api-docs/system/internal-counters)
+/// endpoints
+#[async_trait]
+pub trait InternalCounters {
+    /// Number of requests handled by the cluster each month, for the months Vault has data for.
+    ///
+    /// This endpoint was removed in Vault 1.9.
+    async fn request_counters(&self) -> Result<Vec<RequestCount>, Error>;
+
+    /// Number of service tokens in the cluster. Batch tokens are not counted.
+    async fn token_counters(&self) -> Result<TokenCounters, Error>;
+
+    /// Number of identity entities in the cluster
+    async fn entity_counters(&self) -> Result<EntityCounters, Error>;
+}
+
+#[async_trait]
+impl<T> InternalCounters for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn request_counters(&self) -> Result<Vec<RequestCount>, Error> {
+        let counters: Counters<Option<Vec<RequestCount>>> =
+            self.get("sys/internal/counters/requests").await?.data()?;
+        Ok(counters.counters.unwrap_or_default())
+    }
+
+    async fn token_counters(&self) -> Result<TokenCounters, Error> {
+        let counters: Counters<TokenCounters> =
+            self.get("sys/internal/counters/tokens").await?.data()?;
+        Ok(counters.counters)
+    }
+
+    async fn entity_counters(&self) -> Result<EntityCounters, Error> {
+        let counters: Counters<EntityCounters> =
+            self.get("sys/internal/counters/entities").await?.data()?;
+        Ok(counters.counters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_counters() {
+        let client = crate::tests::vault_client();
+
+        let _ = InternalCounters::request_counters(&client).await.unwrap();
+        let tokens = InternalCounters::token_counters(&client).await.unwrap();
+        // At least the root token exists
+        assert!(tokens.service_tokens.total >= 1);
+        let _ = InternalCounters::entity_counters(&client).await.unwrap();
+    }
+}
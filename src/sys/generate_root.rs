@@ -0,0 +1,343 @@
+//! Implements the [`/sys/generate-root`](https://www.vaultproject.io/api/system/generate-root.html)
+//! endpoints
+use crate::{Client, Error, Response, Secret};
+
+use std::future::Future;
+
+use async_trait::async_trait;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Progress of a root token generation
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct GenerateRootStatus {
+    /// Whether a root token generation is in progress
+    pub started: bool,
+    /// Nonce of the generation in progress, which must be provided with each key share
+    #[serde(default)]
+    pub nonce: String,
+    /// Number of key shares provided so far
+    pub progress: u64,
+    /// Number of key shares required to generate the root token
+    pub required: u64,
+    /// Whether the root token has been generated
+    pub complete: bool,
+    /// The root token, encoded with the one time password. Set once the generation is complete.
+    #[serde(default)]
+    pub encoded_token: String,
+    /// The root token, encrypted with the PGP key, if one was provided
+    #[serde(default)]
+    pub encoded_root_token: String,
+    /// Fingerprint of the PGP key, if one was provided
+    #[serde(default)]
+    pub pgp_fingerprint: String,
+    /// One time password used to encode the root token. Only returned when starting a
+    /// generation.
+    #[serde(default)]
+    pub otp: Secret,
+    /// Length of the one time password
+    #[serde(default)]
+    pub otp_length: u64,
+}
+
+impl GenerateRootStatus {
+    /// Decode the generated root token with the one time password returned when the generation
+    /// was started
+    pub fn decode_token(&self, otp: &str) -> Result<String, Error> {
+        if !self.complete {
+            return Err(Error::InvalidRequest(
+                "root token generation is not complete".to_string(),
+            ));
+        }
+        let encoded = if self.encoded_token.is_empty() {
+            &self.encoded_root_token
+        } else {
+            &self.encoded_token
+        };
+        decode_root_token(encoded, otp)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct KeyShare<'a> {
+    key: &'a str,
+    nonce: &'a str,
+}
+
+/// Implements the [`/sys/generate-root`](https://www.vaultproject.io/api/system/generate-root.html)
+/// endpoints
+#[async_trait]
+pub trait GenerateRoot {
+    /// Read the progress of the root token generation in progress, if any
+    async fn generate_root_status(&self) -> Result<GenerateRootStatus, Error>;
+
+    /// Start a root token generation. The returned status contains the one time password
+    /// needed to decode the generated token.
+    async fn start_generate_root(&self) -> Result<GenerateRootStatus, Error>;
+
+    /// Start a root token generation, encrypting the generated token with the PGP public key,
+    /// base64 encoded, instead of a one time password
+    async fn start_generate_root_with_pgp_key(
+        &self,
+        pgp_key: &str,
+    ) -> Result<GenerateRootStatus, Error>;
+
+    /// Cancel the root token generation in progress. Key shares provided so far are discarded.
+    async fn cancel_generate_root(&self) -> Result<Response, Error>;
+
+    /// Provide a single unseal key share for the generation with the `nonce`
+    async fn provide_generate_root_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<GenerateRootStatus, Error>;
+}
+
+#[async_trait]
+impl<T> GenerateRoot for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn generate_root_status(&self) -> Result<GenerateRootStatus, Error> {
+        // This endpoint returns the status as-is, without the usual response wrapping
+        self.read_with_options(
+            "sys/generate-root/attempt",
+            crate::Method::GET,
+            &Default::default(),
+        )
+        .await
+    }
+
+    async fn start_generate_root(&self) -> Result<GenerateRootStatus, Error> {
+        self.write_raw(
+            "sys/generate-root/attempt",
+            &crate::Empty,
+            crate::Method::PUT,
+            &Default::default(),
+        )
+        .await
+    }
+
+    async fn start_generate_root_with_pgp_key(
+        &self,
+        pgp_key: &str,
+    ) -> Result<GenerateRootStatus, Error> {
+        #[derive(Serialize)]
+        struct Init<'a> {
+            pgp_key: &'a str,
+        }
+
+        self.write_raw(
+            "sys/generate-root/attempt",
+            &Init { pgp_key },
+            crate::Method::PUT,
+            &Default::default(),
+        )
+        .await
+    }
+
+    async fn cancel_generate_root(&self) -> Result<Response, Error> {
+        self.delete("sys/generate-root/attempt", false).await
+    }
+
+    async fn provide_generate_root_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<GenerateRootStatus, Error> {
+        self.write_raw(
+            "sys/generate-root/update",
+            &KeyShare { key, nonce },
+            crate::Method::PUT,
+            &Default::default(),
+        )
+        .await
+    }
+}
+
+/// Decode a root token encoded with a one time password.
+///
+/// Vault 1.10 and later generate one time passwords as long as the token. One time passwords
+/// from older versions are base64 encoded, and decode tokens which are UUIDs.
+pub fn decode_root_token(encoded_token: &str, otp: &str) -> Result<String, Error> {
+    let encoded = crate::crypto::decode_unpadded(encoded_token)?;
+    if encoded.len() == otp.len() {
+        return Ok(String::from_utf8(xor(&encoded, otp.as_bytes()))?);
+    }
+
+    let legacy_otp = crate::crypto::decode_unpadded(otp)
+        .ok()
+        .filter(|legacy| legacy.len() == encoded.len() && legacy.len() == 16);
+    match legacy_otp {
+        Some(legacy) => Ok(format_uuid(&xor(&encoded, &legacy))),
+        None => Err(Error::InvalidRequest(
+            "one time password does not match the length of the encoded token".to_string(),
+        )),
+    }
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(a, b)| a ^ b).collect()
+}
+
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+impl Client {
+    /// Generate a temporary root token with the unseal key shares, run `f` with a client
+    /// authenticated with the root token, and revoke the token.
+    ///
+    /// Fails without cancelling anything if another root token generation is in progress. If
+    /// the generation started by this call fails, it is cancelled. The token is revoked even if
+    /// `f` returns an error. An error revoking the token is returned instead of the result of
+    /// `f`, because the root token is still valid.
+    ///
+    /// # Panics and cancellation
+    ///
+    /// If `f` panics, or the returned future is dropped before it completes, the token is
+    /// revoked when the client holding it is dropped, like clients created with
+    /// `revoke_self_on_drop`. That revocation blocks the current thread until Vault responds,
+    /// and its failure is only logged, so the root token may remain valid.
+    pub async fn with_temporary_root_token<S, F, Fut, R>(
+        &self,
+        unseal_keys: &[S],
+        f: F,
+    ) -> Result<R, Error>
+    where
+        S: AsRef<str>,
+        F: FnOnce(Client) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        let token = self.generate_root_token(unseal_keys).await?;
+
+        // Revokes the token on drop if `f` panics or the future is dropped
+        let mut guard = self.clone();
+        guard.token = Secret(token);
+        guard.revoke_self_on_drop = true;
+        let mut root = guard.clone();
+        root.revoke_self_on_drop = false;
+
+        info!("Running with a temporary root token");
+        let result = f(root).await;
+
+        guard.revoke_self_on_drop = false;
+        let _ = crate::auth::Token::revoke_self(&guard).await?;
+        info!("Temporary root token revoked");
+        Ok(result)
+    }
+
+    async fn generate_root_token<S: AsRef<str>>(&self, unseal_keys: &[S]) -> Result<String, Error> {
+        // Fails if another generation is in progress, which must not be cancelled
+        let status = GenerateRoot::start_generate_root(self).await?;
+        let nonce = status.nonce.clone();
+        match self.provide_root_keys(status, unseal_keys).await {
+            Ok(token) => Ok(token),
+            Err(e) => {
+                self.cancel_own_generation(&nonce).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Cancel the root token generation with the nonce, unless another one replaced it
+    async fn cancel_own_generation(&self, nonce: &str) {
+        match GenerateRoot::generate_root_status(self).await {
+            Ok(status) if status.started && status.nonce == nonce => {
+                if let Err(e) = GenerateRoot::cancel_generate_root(self).await {
+                    warn!("Error cancelling root token generation: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Error reading root token generation status: {}", e),
+        }
+    }
+
+    async fn provide_root_keys<S: AsRef<str>>(
+        &self,
+        status: GenerateRootStatus,
+        unseal_keys: &[S],
+    ) -> Result<String, Error> {
+        let otp = status.otp.clone();
+
+        let mut status = status;
+        for key in unseal_keys {
+            if status.complete {
+                break;
+            }
+            status =
+                GenerateRoot::provide_generate_root_key(self, key.as_ref(), &status.nonce).await?;
+        }
+        if !status.complete {
+            return Err(Error::InvalidRequest(format!(
+                "{} key shares are required to generate a root token, but {} were provided",
+                status.required,
+                unseal_keys.len()
+            )));
+        }
+
+        status.decode_token(&otp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_root_token() {
+        let token = "s.hZv9mVYC0Kv2ZBcTU4gKK9CP";
+        let otp = "6xWQ0nqv7Mr9rlbVkQZMiQbdd8";
+        let encoded: Vec<u8> = token.bytes().zip(otp.bytes()).map(|(a, b)| a ^ b).collect();
+        let encoded = crate::crypto::base64().encode(&encoded);
+        let encoded = encoded.trim_end_matches('=').to_string();
+
+        assert_eq!(decode_root_token(&encoded, otp).unwrap(), token);
+        assert!(decode_root_token(&encoded, "short").is_err());
+
+        let status = GenerateRootStatus {
+            complete: true,
+            encoded_token: encoded,
+            ..Default::default()
+        };
+        assert_eq!(status.decode_token(otp).unwrap(), token);
+    }
+
+    #[test]
+    fn can_decode_legacy_root_token() {
+        let token = [
+            0x7c, 0x2b, 0x0b, 0xa3, 0x5b, 0x5e, 0x4f, 0x3c, 0x90, 0x1c, 0x4b, 0x7e, 0x8e, 0x1e,
+            0x20, 0x46,
+        ];
+        let otp = [7u8; 16];
+        let encoded = crate::crypto::base64().encode(&xor(&token, &otp));
+        let otp = crate::crypto::base64().encode(&otp);
+
+        assert_eq!(
+            decode_root_token(&encoded, &otp).unwrap(),
+            "7c2b0ba3-5b5e-4f3c-901c-4b7e8e1e2046"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_start_and_cancel_root_generation() {
+        let client = crate::tests::vault_client();
+
+        let status = GenerateRoot::start_generate_root(&client).await.unwrap();
+        assert!(status.started);
+        assert!(!status.otp.is_empty());
+
+        let response = GenerateRoot::cancel_generate_root(&client).await.unwrap();
+        assert!(response.ok().unwrap().is_none());
+        let status = GenerateRoot::generate_root_status(&client).await.unwrap();
+        assert!(!status.started);
+    }
+}
@@ -0,0 +1,173 @@
+//! Implements the [`/sys/health`](https://www.vaultproject.io/api/system/health.html) endpoint
+//!
+//! Vault reports its health with the status code of the response as much as with its body:
+//! standbys, uninitialized and sealed nodes respond with a non-200 status code, so that load
+//! balancers can check them without parsing the body. [`Client::health`] reads both.
+use crate::{Client, Error, Method, RequestOptions, Response};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+/// Health of a node, according to the status code of `sys/health`
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum HealthStatus {
+    /// Initialized, unsealed and active (200)
+    Active,
+    /// Unsealed standby (429)
+    Standby,
+    /// (Vault Enterprise) Disaster recovery secondary, active (472)
+    DrSecondary,
+    /// (Vault Enterprise) Performance standby (473)
+    PerformanceStandby,
+    /// Not initialized (501)
+    NotInitialized,
+    /// Sealed (503)
+    Sealed,
+    /// Status code not documented by Vault
+    Other(u16),
+}
+
+impl HealthStatus {
+    /// Status of the status code of a `sys/health` response
+    pub fn from_status_code(code: u16) -> Self {
+        match code {
+            200 => HealthStatus::Active,
+            429 => HealthStatus::Standby,
+            472 => HealthStatus::DrSecondary,
+            473 => HealthStatus::PerformanceStandby,
+            501 => HealthStatus::NotInitialized,
+            503 => HealthStatus::Sealed,
+            code => HealthStatus::Other(code),
+        }
+    }
+
+    /// Whether the node is unsealed and can serve requests, possibly by forwarding them.
+    /// Disaster recovery secondaries are unsealed, but reject requests other than those
+    /// managing replication.
+    pub fn is_serving(self) -> bool {
+        matches!(
+            self,
+            HealthStatus::Active | HealthStatus::Standby | HealthStatus::PerformanceStandby
+        )
+    }
+}
+
+/// Health of a node
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Health {
+    /// Health according to the status code of the response
+    #[serde(skip, default = "unknown_status")]
+    pub status: HealthStatus,
+    /// Whether Vault has been initialized
+    pub initialized: bool,
+    /// Whether Vault is sealed
+    pub sealed: bool,
+    /// Whether the node is a standby
+    pub standby: bool,
+    /// (Vault Enterprise) Whether the node is a performance standby
+    #[serde(default)]
+    pub performance_standby: bool,
+    /// (Vault Enterprise) Performance replication mode, like `disabled`, `primary` or
+    /// `secondary`
+    #[serde(default)]
+    pub replication_performance_mode: Option<String>,
+    /// (Vault Enterprise) Disaster recovery replication mode, like `disabled`, `primary` or
+    /// `secondary`
+    #[serde(default)]
+    pub replication_dr_mode: Option<String>,
+    /// Time of the server, in seconds since the UNIX epoch
+    pub server_time_utc: u64,
+    /// Version of Vault
+    pub version: String,
+    /// Whether Vault is Vault Enterprise
+    #[serde(default)]
+    pub enterprise: bool,
+    /// Name of the cluster, once unsealed
+    #[serde(default)]
+    pub cluster_name: Option<String>,
+    /// ID of the cluster, once unsealed
+    #[serde(default)]
+    pub cluster_id: Option<String>,
+}
+
+fn unknown_status() -> HealthStatus {
+    HealthStatus::Other(0)
+}
+
+impl Client {
+    /// Read the health of the node at the address of the client. This endpoint is
+    /// unauthenticated.
+    ///
+    /// Responses with the non-200 status codes documented by Vault are not errors: their status
+    /// is returned in [`Health::status`].
+    pub async fn health(&self) -> Result<Health, Error> {
+        let options = RequestOptions {
+            unauthenticated: true,
+            ..Default::default()
+        };
+        let request = self
+            .build_request_with_options("sys/health", Method::GET, &options)?
+            .build()?;
+        let response = self.send(request).await?;
+        let status = HealthStatus::from_status_code(response.status().as_u16());
+        let body = self.read_text(response).await?;
+        debug!("Health of Vault ({:?}): {}", status, body);
+        parse_health(status, &body)
+    }
+}
+
+fn parse_health(status: HealthStatus, body: &str) -> Result<Health, Error> {
+    match serde_json::from_str::<Health>(body) {
+        Ok(health) => Ok(Health { status, ..health }),
+        Err(e) => match serde_json::from_str::<Response>(body) {
+            Ok(Response::Error { errors }) if !errors.is_empty() => {
+                Err(Error::VaultError(errors.join("; ")))
+            }
+            _ if status == HealthStatus::Active => Err(e.into()),
+            _ => Err(Error::UnexpectedResponse(body.to_string())),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_200_statuses_are_parsed() {
+        let body = r#"{
+            "initialized": true,
+            "sealed": true,
+            "standby": true,
+            "performance_standby": false,
+            "replication_performance_mode": "disabled",
+            "replication_dr_mode": "disabled",
+            "server_time_utc": 1700000000,
+            "version": "1.15.0"
+        }"#;
+        let health = parse_health(HealthStatus::from_status_code(503), body).unwrap();
+        assert_eq!(health.status, HealthStatus::Sealed);
+        assert!(!health.status.is_serving());
+        assert!(health.sealed);
+        assert!(HealthStatus::from_status_code(473).is_serving());
+        assert!(!HealthStatus::from_status_code(472).is_serving());
+        assert_eq!(health.replication_dr_mode.as_deref(), Some("disabled"));
+
+        assert!(matches!(
+            parse_health(
+                HealthStatus::Other(500),
+                r#"{"errors": ["internal error"]}"#
+            ),
+            Err(Error::VaultError(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_health() {
+        let client = crate::tests::vault_client();
+        let health = client.health().await.unwrap();
+        assert_eq!(health.status, HealthStatus::Active);
+        assert!(health.initialized);
+        assert!(!health.sealed);
+    }
+}
@@ -0,0 +1,88 @@
+//! Implements the [`/sys/in-flight-req`](https://www.vaultproject.io/api-docs/system/in-flight-req)
+//! endpoint
+use std::collections::HashMap;
+
+use crate::{Error, Method};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// A request currently being executed by the Vault node
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct InFlightRequest {
+    /// Time the request started, in RFC 3339 format
+    pub start_time: String,
+    /// Remote address of the client making the request
+    #[serde(default)]
+    pub client_remote_address: String,
+    /// Path of the request
+    pub request_path: String,
+    /// HTTP method of the request
+    pub request_method: String,
+    /// ID of the client making the request, if known
+    #[serde(default)]
+    pub client_id: String,
+}
+
+/// Implements the [`/sys/in-flight-req`](https://www.vaultproject.io/api-docs/system/in-flight-req)
+/// endpoint
+#[async_trait]
+pub trait InFlightRequests {
+    /// Returns the requests currently being executed on the node serving the request, keyed by
+    /// request ID
+    async fn in_flight_requests(&self) -> Result<HashMap<String, InFlightRequest>, Error>;
+}
+
+#[async_trait]
+impl<T> InFlightRequests for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn in_flight_requests(&self) -> Result<HashMap<String, InFlightRequest>, Error> {
+        // The requests are returned as-is, without the usual response wrapping
+        self.read_with_options("sys/in-flight-req", Method::GET, &Default::default())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_in_flight_requests() {
+        let requests: HashMap<String, InFlightRequest> =
+            serde_json::from_value(serde_json::json!({
+                "9049326b-f6c4-4a63-9a7e-57a1c8c4e6f2": {
+                    "start_time": "2021-11-19T09:13:01.34157-08:00",
+                    "client_remote_address": "127.0.0.3:49816",
+                    "request_path": "/v1/sys/in-flight-req",
+                    "request_method": "GET",
+                    "client_id": ""
+                },
+                "0e6aa4bd-0c4d-4e5b-8d1f-5d3c1b7e8a90": {
+                    "start_time": "2021-11-19T09:13:01.2-08:00",
+                    "request_path": "/v1/secret/data/foo",
+                    "request_method": "POST"
+                }
+            }))
+            .unwrap();
+        assert_eq!(requests.len(), 2);
+        let request = &requests["9049326b-f6c4-4a63-9a7e-57a1c8c4e6f2"];
+        assert_eq!(request.request_path, "/v1/sys/in-flight-req");
+        assert_eq!(request.client_remote_address, "127.0.0.3:49816");
+        let request = &requests["0e6aa4bd-0c4d-4e5b-8d1f-5d3c1b7e8a90"];
+        assert_eq!(request.request_method, "POST");
+        assert!(request.client_remote_address.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_list_in_flight_requests() {
+        let client = crate::tests::vault_client();
+        let requests = InFlightRequests::in_flight_requests(&client).await.unwrap();
+        // The request listing the requests is itself in flight
+        assert!(requests
+            .values()
+            .any(|request| request.request_path == "/v1/sys/in-flight-req"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Implements the [`/sys/init`](https://www.vaultproject.io/api/system/init.html) endpoint
+use crate::{Error, Method, RequestOptions, Secret};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for initializing Vault
+///
+/// Vaults sealed with Shamir's secret sharing split their unseal key into `secret_shares`.
+/// Vaults unsealed automatically, with a cloud KMS or an HSM, split their recovery key into
+/// `recovery_shares` instead.
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct InitRequest {
+    /// Number of shares to split the unseal key into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_shares: Option<u64>,
+    /// Number of shares required to reconstruct the unseal key. Must be less than or equal to
+    /// `secret_shares`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_threshold: Option<u64>,
+    /// Base64 encoded PGP public keys, or `keybase:` user names, encrypting each unseal key
+    /// share. There must be as many keys as shares.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pgp_keys: Option<Vec<String>>,
+    /// Base64 encoded PGP public key, or `keybase:` user name, encrypting the root token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_token_pgp_key: Option<String>,
+    /// Number of shares of the unseal key stored in the seal. Must be equal to
+    /// `secret_shares` for Vaults unsealed automatically.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored_shares: Option<u64>,
+    /// Number of shares to split the recovery key into, for Vaults unsealed automatically
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_shares: Option<u64>,
+    /// Number of shares required to reconstruct the recovery key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_threshold: Option<u64>,
+    /// Base64 encoded PGP public keys, or `keybase:` user names, encrypting each recovery key
+    /// share
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_pgp_keys: Option<Vec<String>>,
+}
+
+impl InitRequest {
+    /// Split the unseal key of a Vault sealed with Shamir's secret sharing
+    pub fn shamir(shares: u64, threshold: u64) -> Self {
+        Self {
+            secret_shares: Some(shares),
+            secret_threshold: Some(threshold),
+            ..Default::default()
+        }
+    }
+
+    /// Split the recovery key of a Vault unsealed automatically
+    pub fn recovery(shares: u64, threshold: u64) -> Self {
+        Self {
+            recovery_shares: Some(shares),
+            recovery_threshold: Some(threshold),
+            ..Default::default()
+        }
+    }
+}
+
+/// Keys and root token of a newly initialized Vault
+///
+/// Key shares and the root token are encrypted, and base64 encoded, when PGP keys were given.
+#[derive(Deserialize, Debug, Clone)]
+pub struct InitResponse {
+    /// Shares of the unseal key, hex encoded. Empty for Vaults unsealed automatically.
+    #[serde(default)]
+    pub keys: Vec<Secret>,
+    /// Shares of the unseal key, base64 encoded
+    #[serde(default)]
+    pub keys_base64: Vec<Secret>,
+    /// Shares of the recovery key, hex encoded, for Vaults unsealed automatically
+    #[serde(default)]
+    pub recovery_keys: Vec<Secret>,
+    /// Shares of the recovery key, base64 encoded
+    #[serde(default)]
+    pub recovery_keys_base64: Vec<Secret>,
+    /// Initial root token
+    pub root_token: Secret,
+}
+
+#[derive(Deserialize, Debug)]
+struct InitStatus {
+    initialized: bool,
+}
+
+/// Implements the [`/sys/init`](https://www.vaultproject.io/api/system/init.html) endpoint
+#[async_trait]
+pub trait Init {
+    /// Whether Vault has been initialized. This endpoint is unauthenticated.
+    async fn is_initialized(&self) -> Result<bool, Error>;
+
+    /// Initialize Vault, returning the shares of its unseal key and its initial root token.
+    /// This endpoint is unauthenticated.
+    async fn init(&self, request: &InitRequest) -> Result<InitResponse, Error>;
+}
+
+#[async_trait]
+impl<T> Init for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn is_initialized(&self) -> Result<bool, Error> {
+        let options = RequestOptions {
+            unauthenticated: true,
+            ..Default::default()
+        };
+        let status: InitStatus = self
+            .read_with_options("sys/init", Method::GET, &options)
+            .await?;
+        Ok(status.initialized)
+    }
+
+    async fn init(&self, request: &InitRequest) -> Result<InitResponse, Error> {
+        let options = RequestOptions {
+            unauthenticated: true,
+            ..Default::default()
+        };
+        self.write_raw("sys/init", request, Method::PUT, &options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_requests_are_serialized() {
+        let request = InitRequest {
+            pgp_keys: Some(vec!["keybase:alice".to_string(), "keybase:bob".to_string()]),
+            ..InitRequest::shamir(2, 1)
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "secret_shares": 2,
+                "secret_threshold": 1,
+                "pgp_keys": ["keybase:alice", "keybase:bob"]
+            })
+        );
+
+        let response: InitResponse = serde_json::from_value(serde_json::json!({
+            "keys": [],
+            "keys_base64": [],
+            "recovery_keys": ["abcd"],
+            "recovery_keys_base64": ["q80="],
+            "root_token": "hvs.root"
+        }))
+        .unwrap();
+        assert_eq!(response.recovery_keys[0].as_str(), "abcd");
+        assert_eq!(format!("{:?}", response.root_token), "***");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dev_server_is_initialized() {
+        let client = crate::tests::vault_client();
+        assert!(Init::is_initialized(&client).await.unwrap());
+    }
+}
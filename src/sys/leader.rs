@@ -0,0 +1,154 @@
+//! Implements the [`/sys/leader`](https://www.vaultproject.io/api/system/leader.html) and
+//! [`/sys/ha-status`](https://developer.hashicorp.com/vault/api-docs/system/ha-status) endpoints
+use crate::{Error, Method, RequestOptions};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// High Availability status and the current leader of the cluster
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct LeaderStatus {
+    /// Whether High Availability is enabled
+    pub ha_enabled: bool,
+    /// Whether the node serving the request is the leader
+    pub is_self: bool,
+    /// API address of the leader
+    #[serde(default)]
+    pub leader_address: String,
+    /// Cluster address of the leader
+    #[serde(default)]
+    pub leader_cluster_address: String,
+    /// (Vault Enterprise) Whether the node serving the request is a performance standby
+    #[serde(default)]
+    pub performance_standby: bool,
+    /// (Vault Enterprise) Index of the last remote WAL entry seen by a performance standby
+    #[serde(default)]
+    pub performance_standby_last_remote_wal: u64,
+}
+
+/// Node of a High Availability cluster
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct HaNode {
+    /// Host name of the node
+    #[serde(default)]
+    pub hostname: String,
+    /// API address of the node
+    #[serde(default)]
+    pub api_address: String,
+    /// Cluster address of the node
+    #[serde(default)]
+    pub cluster_address: String,
+    /// Whether the node is the active node. Other nodes are standbys.
+    #[serde(default)]
+    pub active_node: bool,
+    /// Time at which the standby last sent an echo to the active node, in RFC 3339 format.
+    /// Not set for the active node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_echo: Option<String>,
+    /// Version of Vault running on the node
+    #[serde(default)]
+    pub version: String,
+    /// Version of Vault the node is upgrading to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_version: Option<String>,
+    /// (Vault Enterprise) Redundancy zone of the node, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redundancy_zone: Option<String>,
+}
+
+impl HaNode {
+    /// Whether the node is a standby
+    pub fn is_standby(&self) -> bool {
+        !self.active_node
+    }
+}
+
+/// Implements the [`/sys/leader`](https://www.vaultproject.io/api/system/leader.html) and
+/// [`/sys/ha-status`](https://developer.hashicorp.com/vault/api-docs/system/ha-status) endpoints
+#[async_trait]
+pub trait Leader {
+    /// Returns the High Availability status and the current leader of the cluster. This
+    /// endpoint is unauthenticated.
+    async fn leader(&self) -> Result<LeaderStatus, Error>;
+
+    /// Returns the nodes of the High Availability cluster, with the active node first
+    async fn ha_status(&self) -> Result<Vec<HaNode>, Error>;
+}
+
+#[async_trait]
+impl<T> Leader for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn leader(&self) -> Result<LeaderStatus, Error> {
+        let options = RequestOptions {
+            unauthenticated: true,
+            ..Default::default()
+        };
+        self.read_with_options("sys/leader", Method::GET, &options)
+            .await
+    }
+
+    async fn ha_status(&self) -> Result<Vec<HaNode>, Error> {
+        #[derive(Deserialize)]
+        struct Nodes {
+            #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+            nodes: Vec<HaNode>,
+        }
+
+        let nodes: Nodes = self.get("sys/ha-status").await?.data()?;
+        let mut nodes = nodes.nodes;
+        nodes.sort_by_key(|node| !node.active_node);
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_leader() {
+        let client = crate::tests::vault_client();
+        let status = Leader::leader(&client).await.unwrap();
+        assert!(!status.ha_enabled);
+    }
+
+    #[test]
+    fn can_parse_ha_nodes() {
+        let node: HaNode = serde_json::from_value(serde_json::json!({
+            "hostname": "vault-1",
+            "api_address": "https://10.0.0.2:8200",
+            "cluster_address": "https://10.0.0.2:8201",
+            "active_node": false,
+            "last_echo": "2024-01-01T10:00:00.123Z",
+            "version": "1.15.2",
+            "upgrade_version": "1.15.2",
+            "redundancy_zone": ""
+        }))
+        .unwrap();
+        assert!(node.is_standby());
+        assert_eq!(node.last_echo.as_deref(), Some("2024-01-01T10:00:00.123Z"));
+
+        let node: HaNode = serde_json::from_value(serde_json::json!({
+            "hostname": "vault-0",
+            "api_address": "https://10.0.0.1:8200",
+            "cluster_address": "https://10.0.0.1:8201",
+            "active_node": true,
+            "last_echo": null,
+            "version": "1.15.2"
+        }))
+        .unwrap();
+        assert!(!node.is_standby());
+        assert!(node.last_echo.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_ha_status() {
+        // The dev server does not run in High Availability mode
+        let client = crate::tests::vault_client();
+        if let Ok(nodes) = Leader::ha_status(&client).await {
+            assert!(nodes.iter().filter(|node| node.active_node).count() <= 1);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Implements the [`/sys/leases`](https://www.vaultproject.io/api/system/leases.html) endpoints
+use crate::{Error, Keys, LeasedData, Response};
+
+use async_trait::async_trait;
+use futures::future::FutureExt;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Information about a lease
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct LeaseInfo {
+    /// ID of the lease
+    pub id: String,
+    /// Time at which the lease was issued, in RFC 3339 format
+    pub issue_time: String,
+    /// Time at which the lease expires, in RFC 3339 format
+    #[serde(default)]
+    pub expire_time: Option<String>,
+    /// Time at which the lease was last renewed, in RFC 3339 format
+    #[serde(default)]
+    pub last_renewal: Option<String>,
+    /// Whether the lease can be renewed
+    pub renewable: bool,
+    /// Remaining time to live of the lease, in seconds
+    pub ttl: u64,
+}
+
+/// Lease, after its renewal
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct RenewedLease {
+    /// ID of the lease
+    pub lease_id: String,
+    /// Whether the lease can be renewed again
+    pub renewable: bool,
+    /// Duration of the lease from its renewal, in seconds. Vault may grant less than the
+    /// increment requested.
+    pub lease_duration: u64,
+}
+
+#[derive(Serialize)]
+struct LeaseId<'a> {
+    lease_id: &'a str,
+}
+
+#[derive(Serialize)]
+struct Renew<'a> {
+    lease_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    increment: Option<u64>,
+}
+
+/// Lease management
+#[async_trait]
+pub trait Leases {
+    /// Look up a lease
+    async fn lookup_lease(&self, lease_id: &str) -> Result<LeaseInfo, Error>;
+    /// List the IDs of the leases under `prefix`, like `aws/creds/app/`, relative to the
+    /// prefix. Subdirectories end with `/`.
+    ///
+    /// Requires `sudo` capability on the path.
+    async fn list_leases(&self, prefix: &str) -> Result<Vec<String>, Error>;
+    /// Renew the lease, optionally requesting an `increment`, in seconds, for its new duration
+    async fn renew_lease(
+        &self,
+        lease_id: &str,
+        increment: Option<u64>,
+    ) -> Result<RenewedLease, Error>;
+    /// Renew the lease of the secret, optionally requesting an `increment`, in seconds, and
+    /// update its lease duration
+    async fn renew_leased_data<D: Send>(
+        &self,
+        leased: &mut LeasedData<D>,
+        increment: Option<u64>,
+    ) -> Result<(), Error> {
+        let renewed = self.renew_lease(&leased.lease_id, increment).await?;
+        leased.renewable = renewed.renewable;
+        leased.lease_duration = renewed.lease_duration;
+        Ok(())
+    }
+    /// Revoke the lease, invalidating the secret it leases
+    async fn revoke_lease(&self, lease_id: &str) -> Result<Response, Error>;
+    /// Revoke all the leases under `prefix`, like `aws/creds/` or `database/creds/app/`.
+    ///
+    /// Requires `sudo` capability on the path.
+    async fn revoke_leases(&self, prefix: &str) -> Result<Response, Error>;
+    /// Revoke all the leases under `prefix`, ignoring errors from the secrets engines, like
+    /// credentials which cannot be deleted. The secrets may still be valid.
+    ///
+    /// Meant to recover from a secrets engine whose backend is gone. Requires `sudo` capability
+    /// on the path.
+    async fn revoke_leases_force(&self, prefix: &str) -> Result<Response, Error>;
+    /// Clean up the leases of the storage of Vault, removing invalid entries. The tidy runs in
+    /// the background.
+    async fn tidy_leases(&self) -> Result<Response, Error>;
+    /// Revoke the leases, with at most `concurrency` revocations in flight at a time.
+    ///
+    /// Every lease is attempted, even if some revocations fail. The failures are reported together
+    /// in [`Error::LeaseRevocationFailed`].
+    async fn revoke_many(&self, lease_ids: &[String], concurrency: usize) -> Result<(), Error> {
+        let revocations: Vec<_> = lease_ids
+            .iter()
+            .map(|lease_id| {
+                self.revoke_lease(lease_id)
+                    .map(move |result| (lease_id, result))
+            })
+            .collect();
+        let results: Vec<_> = stream::iter(revocations)
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+        let failures: Vec<(String, Error)> = results
+            .into_iter()
+            .filter_map(|(lease_id, result)| result.err().map(|e| (lease_id.clone(), e)))
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::LeaseRevocationFailed(failures))
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Leases for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn lookup_lease(&self, lease_id: &str) -> Result<LeaseInfo, Error> {
+        self.put("sys/leases/lookup", &LeaseId { lease_id }, true)
+            .await?
+            .data()
+    }
+
+    async fn list_leases(&self, prefix: &str) -> Result<Vec<String>, Error> {
+        let path = format!("sys/leases/lookup/{}", prefix);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn renew_lease(
+        &self,
+        lease_id: &str,
+        increment: Option<u64>,
+    ) -> Result<RenewedLease, Error> {
+        let response = self
+            .put(
+                "sys/leases/renew",
+                &Renew {
+                    lease_id,
+                    increment,
+                },
+                true,
+            )
+            .await?;
+        match response.ok()? {
+            Some(data) => Ok(RenewedLease {
+                lease_id: data.lease_id,
+                renewable: data.renewable,
+                lease_duration: data.lease_duration,
+            }),
+            None => Err(Error::MissingData(Box::new(Response::Empty))),
+        }
+    }
+
+    async fn revoke_lease(&self, lease_id: &str) -> Result<Response, Error> {
+        self.put("sys/leases/revoke", &LeaseId { lease_id }, false)
+            .await
+    }
+
+    async fn revoke_leases(&self, prefix: &str) -> Result<Response, Error> {
+        let path = format!("sys/leases/revoke-prefix/{}", prefix);
+        self.put(&path, &crate::Empty, false).await
+    }
+
+    async fn revoke_leases_force(&self, prefix: &str) -> Result<Response, Error> {
+        let path = format!("sys/leases/revoke-force/{}", prefix);
+        self.put(&path, &crate::Empty, false).await
+    }
+
+    async fn tidy_leases(&self) -> Result<Response, Error> {
+        self.put("sys/leases/tidy", &crate::Empty, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::aws::{self, Aws, CredentialType, Credentials, RootCredentials};
+    use crate::sys::mounts::tests::Mount;
+    use crate::Client;
+
+    /// Mount an AWS secrets engine backed by the IAM mock, and generate IAM user credentials,
+    /// which are leased
+    async fn aws_lease(client: &Client) -> (Mount<Client>, LeasedData<Credentials>) {
+        let engine = crate::sys::mounts::SecretEngine {
+            path: crate::tests::uuid_prefix("aws"),
+            r#type: "aws".to_string(),
+            ..Default::default()
+        };
+        let mount = Mount::new(client, &engine).await;
+        let config = RootCredentials {
+            max_retries: -1,
+            access_key: "aaa".to_string(),
+            secret_key: "aaa".to_string(),
+            region: None,
+            iam_endpoint: Some("http://aws_iam:5000".to_string()),
+            sts_endpoint: Some("http://aws_sts:8000".to_string()),
+        };
+        let _ = Aws::configure_root(client, &mount.path, &config)
+            .await
+            .unwrap();
+        let role = aws::Role {
+            credential_type: Some(CredentialType::IamUser),
+            policy_arns: Some(vec!["arn:aws:iam::aws:policy/ReadOnlyAccess".to_string()]),
+            ..Default::default()
+        };
+        let _ = Aws::create_role(client, &mount.path, "app", &role)
+            .await
+            .unwrap();
+        let credentials =
+            Aws::generate_credentials(client, &mount.path, "app", &Default::default())
+                .await
+                .unwrap();
+        (mount, credentials)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_look_up_renew_and_revoke_leases() {
+        let client = crate::tests::vault_client();
+        let (mount, mut credentials) = aws_lease(&client).await;
+        let lease_id = credentials.lease_id.clone();
+        assert!(lease_id.starts_with(&format!("{}/creds/app/", mount.path)));
+
+        let lease = Leases::lookup_lease(&client, &lease_id).await.unwrap();
+        assert_eq!(lease.id, lease_id);
+        assert!(lease.renewable);
+        let prefix = format!("{}/creds/app/", mount.path);
+        let leases = Leases::list_leases(&client, &prefix).await.unwrap();
+        assert_eq!(leases, [lease_id.trim_start_matches(&prefix)]);
+
+        let renewed = Leases::renew_lease(&client, &lease_id, Some(600))
+            .await
+            .unwrap();
+        assert_eq!(renewed.lease_id, lease_id);
+        assert!(renewed.lease_duration <= 600);
+        Leases::renew_leased_data(&client, &mut credentials, Some(300))
+            .await
+            .unwrap();
+        assert!(credentials.lease_duration <= 300);
+
+        let _ = Leases::revoke_lease(&client, &lease_id).await.unwrap();
+        assert!(Leases::lookup_lease(&client, &lease_id).await.is_err());
+        assert!(Leases::renew_lease(&client, &lease_id, None).await.is_err());
+        let _ = Leases::tidy_leases(&client).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_revoke_leases_by_prefix() {
+        let client = crate::tests::vault_client();
+        let (mount, credentials) = aws_lease(&client).await;
+
+        let prefix = format!("{}/creds/", mount.path);
+        let _ = Leases::revoke_leases(&client, &prefix).await.unwrap();
+        assert!(Leases::lookup_lease(&client, &credentials.lease_id)
+            .await
+            .is_err());
+        let _ = Leases::revoke_leases_force(&client, &prefix).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn revoke_many_reports_every_failure() {
+        let client = crate::tests::vault_client();
+        let (_mount, credentials) = aws_lease(&client).await;
+
+        // Vault rejects revocations without a lease ID
+        let lease_ids = vec![credentials.lease_id.clone(), String::new()];
+        match Leases::revoke_many(&client, &lease_ids, 4).await {
+            Err(Error::LeaseRevocationFailed(failures)) => {
+                let failed: Vec<_> = failures.into_iter().map(|(id, _)| id).collect();
+                assert_eq!(failed, [""]);
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+        // The other leases are still revoked
+        assert!(Leases::lookup_lease(&client, &credentials.lease_id)
+            .await
+            .is_err());
+
+        assert!(Leases::revoke_many(&client, &[], 4).await.is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+//! Implements the
+//! [`/sys/license/status`](https://developer.hashicorp.com/vault/api-docs/system/license)
+//! endpoint of Vault Enterprise
+use crate::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// License of Vault Enterprise
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct LicenseInfo {
+    /// ID of the license
+    #[serde(default)]
+    pub license_id: String,
+    /// ID of the customer the license was issued to
+    #[serde(default)]
+    pub customer_id: String,
+    /// ID of the installation the license was issued for
+    #[serde(default)]
+    pub installation_id: String,
+    /// Time at which the license was issued, in RFC 3339 format
+    #[serde(default)]
+    pub issue_time: String,
+    /// Time from which the license is valid, in RFC 3339 format
+    #[serde(default)]
+    pub start_time: String,
+    /// Time at which the license expires, in RFC 3339 format. Vault keeps running, but cannot
+    /// be restarted, once it expires.
+    #[serde(default)]
+    pub expiration_time: String,
+    /// Time at which Vault stops running with the license, in RFC 3339 format
+    #[serde(default)]
+    pub termination_time: String,
+    /// Features enabled by the license, like `Performance Replication`
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub features: Vec<String>,
+    /// Number of performance standby nodes allowed
+    #[serde(default)]
+    pub performance_standby_count: u64,
+}
+
+impl LicenseInfo {
+    /// Whether the license enables the feature
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
+/// Status of the license of Vault Enterprise
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct LicenseStatus {
+    /// License loaded from the environment or the configuration of Vault, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autoloaded: Option<LicenseInfo>,
+}
+
+/// Implements the
+/// [`/sys/license/status`](https://developer.hashicorp.com/vault/api-docs/system/license)
+/// endpoint
+#[async_trait]
+pub trait License {
+    /// Read the status of the license. Fails on Vault Community Edition, which has no license.
+    async fn license_status(&self) -> Result<LicenseStatus, Error>;
+}
+
+#[async_trait]
+impl<T> License for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn license_status(&self) -> Result<LicenseStatus, Error> {
+        self.get("sys/license/status").await?.data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_license_status() {
+        let status: LicenseStatus = serde_json::from_value(serde_json::json!({
+            "autoloaded": {
+                "license_id": "7d0e0d16-bd87-4e1c-b6e4-d5b2e6b5e7a8",
+                "customer_id": "example",
+                "installation_id": "*",
+                "issue_time": "2024-01-01T00:00:00Z",
+                "start_time": "2024-01-01T00:00:00Z",
+                "expiration_time": "2025-01-01T00:00:00Z",
+                "termination_time": "2025-01-11T00:00:00Z",
+                "features": ["HSMs", "Performance Replication"],
+                "performance_standby_count": 9999
+            }
+        }))
+        .unwrap();
+        let license = status.autoloaded.unwrap();
+        assert_eq!(license.expiration_time, "2025-01-01T00:00:00Z");
+        assert!(license.has_feature("HSMs"));
+        assert!(!license.has_feature("Sentinel"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn community_edition_has_no_license() {
+        let client = crate::tests::vault_client();
+        assert!(License::license_status(&client).await.is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Implements the [`/sys/metrics`](https://developer.hashicorp.com/vault/api-docs/system/metrics)
+//! endpoint
+//!
+//! [`Metrics::metrics`] reads the metrics of Vault as typed gauges, counters and samples, and
+//! [`Client::prometheus_metrics`] passes them through in the Prometheus format.
+//!
+//! With the `prometheus-handler` feature, [`prometheus_response`] proxies the metrics of Vault in
+//! the Prometheus format, so that applications can expose them on their own metrics port without
+//! giving their Vault token to Prometheus.
+use crate::{Client, Error, Method};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Metrics of Vault, aggregated over the current interval of its in-memory sink
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct MetricsSnapshot {
+    /// Start of the interval, like `2024-01-01 10:00:00 +0000 UTC`
+    #[serde(default)]
+    pub timestamp: String,
+    /// Gauges, with their last value
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub gauges: Vec<Gauge>,
+    /// Raw values of the points recorded
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub points: Vec<Points>,
+    /// Counters, summarized over the interval
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub counters: Vec<Summary>,
+    /// Samples, like request durations, summarized over the interval
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub samples: Vec<Summary>,
+}
+
+impl MetricsSnapshot {
+    /// Find the gauge with the name, like `vault.core.unsealed`
+    pub fn gauge(&self, name: &str) -> Option<&Gauge> {
+        self.gauges.iter().find(|gauge| gauge.name == name)
+    }
+
+    /// Find the counter with the name, like `vault.core.handle_request`
+    pub fn counter(&self, name: &str) -> Option<&Summary> {
+        self.counters.iter().find(|counter| counter.name == name)
+    }
+
+    /// Find the sample with the name, like `vault.core.handle_request`
+    pub fn sample(&self, name: &str) -> Option<&Summary> {
+        self.samples.iter().find(|sample| sample.name == name)
+    }
+}
+
+/// Value of a gauge
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Gauge {
+    /// Name of the gauge
+    pub name: String,
+    /// Last value of the gauge
+    pub value: f64,
+    /// Labels of the gauge
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub labels: HashMap<String, String>,
+}
+
+/// Points recorded for a key
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Points {
+    /// Name of the key
+    pub name: String,
+    /// Values recorded
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub points: Vec<f64>,
+}
+
+/// Summary of the values of a counter or sample
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Summary {
+    /// Name of the counter or sample
+    pub name: String,
+    /// Number of values
+    pub count: u64,
+    /// Values per second
+    pub rate: f64,
+    /// Sum of the values
+    pub sum: f64,
+    /// Minimum value
+    pub min: f64,
+    /// Maximum value
+    pub max: f64,
+    /// Mean of the values
+    pub mean: f64,
+    /// Standard deviation of the values
+    pub stddev: f64,
+    /// Labels of the counter or sample
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub labels: HashMap<String, String>,
+}
+
+/// Implements the [`/sys/metrics`](https://developer.hashicorp.com/vault/api-docs/system/metrics)
+/// endpoint
+#[async_trait]
+pub trait Metrics {
+    /// Read the metrics of Vault. The token needs `read` on `sys/metrics`, unless
+    /// unauthenticated access to metrics is allowed by the listener.
+    async fn metrics(&self) -> Result<MetricsSnapshot, Error>;
+}
+
+#[async_trait]
+impl<T> Metrics for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn metrics(&self) -> Result<MetricsSnapshot, Error> {
+        // This endpoint returns the metrics as-is, without the usual response wrapping
+        self.read_with_options("sys/metrics", Method::GET, &Default::default())
+            .await
+    }
+}
+
+impl Client {
+    /// Read the metrics of Vault in the Prometheus text exposition format.
+    ///
+    /// Vault only serves metrics in this format when its telemetry is configured with a
+    /// `prometheus_retention_time`. The token needs `read` on `sys/metrics`, unless
+    /// unauthenticated access to metrics is allowed by the listener.
+    pub async fn prometheus_metrics(&self) -> Result<String, Error> {
+        let request = self
+            .build_request("sys/metrics", Method::GET)?
+            .query(&[("format", "prometheus")])
+            .build()?;
+        Ok(String::from_utf8(
+            self.execute_request_bytes(request).await?,
+        )?)
+    }
+}
+
+/// Read the metrics of Vault in the Prometheus format with the client, as an HTTP response for
+/// a scrape by Prometheus.
+///
+/// The response has status `502 Bad Gateway` if the metrics cannot be read. The handler can be
+/// used with `hyper::service::service_fn`, or as an `axum` handler:
+///
+/// ```ignore
+/// let app = axum::Router::new().route(
+///     "/metrics/vault",
+///     axum::routing::get(move || async move { prometheus_response(&client).await }),
+/// );
+/// ```
+#[cfg(feature = "prometheus-handler")]
+pub async fn prometheus_response(client: &Client) -> hyper::Response<hyper::Body> {
+    let (status, content_type, body) = match client.prometheus_metrics().await {
+        Ok(metrics) => (hyper::StatusCode::OK, PROMETHEUS_CONTENT_TYPE, metrics),
+        Err(e) => {
+            log::warn!("Failed to read the metrics of Vault: {}", e);
+            (
+                hyper::StatusCode::BAD_GATEWAY,
+                "text/plain",
+                format!("Failed to read the metrics of Vault: {}\n", e),
+            )
+        }
+    };
+    let mut response = hyper::Response::new(hyper::Body::from(body));
+    *response.status_mut() = status;
+    let _ = response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static(content_type),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_metrics() {
+        let metrics: MetricsSnapshot = serde_json::from_value(serde_json::json!({
+            "Timestamp": "2024-01-01 10:00:00 +0000 UTC",
+            "Gauges": [
+                {"Name": "vault.core.unsealed", "Value": 1, "Labels": {"cluster": "vault"}}
+            ],
+            "Points": [],
+            "Counters": [{
+                "Name": "vault.core.handle_request",
+                "Count": 4,
+                "Rate": 0.4,
+                "Sum": 4,
+                "Min": 1,
+                "Max": 1,
+                "Mean": 1,
+                "Stddev": 0,
+                "Labels": {}
+            }],
+            "Samples": null
+        }))
+        .unwrap();
+        assert_eq!(metrics.gauge("vault.core.unsealed").unwrap().value, 1.0);
+        assert_eq!(
+            metrics.counter("vault.core.handle_request").unwrap().count,
+            4
+        );
+        assert!(metrics.sample("vault.core.handle_request").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_metrics() {
+        let client = crate::tests::vault_client();
+        let metrics = Metrics::metrics(&client).await.unwrap();
+        assert!(!metrics.gauges.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_prometheus_metrics() {
+        let client = crate::tests::vault_client();
+        let metrics = client.prometheus_metrics().await.unwrap();
+        assert!(metrics.contains("# TYPE"));
+    }
+
+    #[cfg(feature = "prometheus-handler")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreadable_metrics_are_bad_gateway() {
+        let client = Client::without_token(Some("http://127.0.0.1:1"), None::<&str>).unwrap();
+        let response = prometheus_response(&client).await;
+        assert_eq!(response.status(), hyper::StatusCode::BAD_GATEWAY);
+    }
+}
@@ -1,6 +1,114 @@
 //! Implements API wrappers for the
 //! [System Backend](https://www.vaultproject.io/api/system/index.html) endpoints
 
+pub mod accessors;
+pub mod activity;
+pub mod audit;
+pub mod auth;
+pub mod capabilities;
+pub mod config_state;
+pub mod counters;
+pub mod generate_root;
+pub mod health;
+pub mod in_flight_requests;
+pub mod init;
+pub mod leader;
+pub mod leases;
+pub mod license;
+pub mod metrics;
 pub mod mounts;
+pub mod policies;
+pub mod quotas;
+pub mod raw;
+pub mod reconcile;
+pub mod rekey;
+pub mod seal;
+pub mod tools;
+pub mod ui_mounts;
 
+pub use activity::Activity;
+pub use audit::AuditDevices;
+pub use auth::AuthMethods;
+pub use capabilities::Capabilities;
+pub use config_state::ConfigState;
+pub use counters::InternalCounters;
+pub use generate_root::GenerateRoot;
+pub use in_flight_requests::InFlightRequests;
+pub use init::Init;
+pub use leader::Leader;
+pub use leases::Leases;
+pub use license::License;
+pub use metrics::Metrics;
 pub use mounts::Mounts;
+pub use policies::Policies;
+pub use quotas::Quotas;
+pub use raw::RawStorage;
+pub use rekey::{RecoveryRekey, Rekey};
+pub use seal::Seal;
+pub use tools::Tools;
+pub use ui_mounts::UiMounts;
+
+use crate::sys::mounts::DeprecationStatus;
+use crate::Error;
+
+/// A secrets engine or auth method mount backed by a deprecated builtin plugin
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DeprecatedMount {
+    /// Path of the mount. Auth methods are prefixed with `auth/`.
+    pub path: String,
+    /// Type of the plugin
+    pub r#type: String,
+    /// Deprecation status of the plugin
+    pub status: DeprecationStatus,
+}
+
+/// List the secrets engines and auth methods backed by deprecated builtin plugins, which need to
+/// be migrated before upgrading Vault.
+///
+/// Vault only reports the deprecation status of mounts from version 1.12. Older versions, and
+/// external plugins, are never flagged.
+pub async fn deprecated_mounts<T>(client: &T) -> Result<Vec<DeprecatedMount>, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let engines = Mounts::list(client)
+        .await?
+        .into_iter()
+        .map(|(path, engine)| (path, engine.r#type, engine.deprecation_status));
+    let methods = AuthMethods::list_auth(client)
+        .await?
+        .into_iter()
+        .map(|(path, method)| {
+            (
+                format!("auth/{}", path),
+                method.r#type,
+                method.deprecation_status,
+            )
+        });
+
+    let mut deprecated: Vec<DeprecatedMount> = engines
+        .chain(methods)
+        .filter_map(|(path, r#type, status)| match status {
+            Some(status) if status.is_deprecated() => Some(DeprecatedMount {
+                path,
+                r#type,
+                status,
+            }),
+            _ => None,
+        })
+        .collect();
+    deprecated.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(deprecated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn builtin_mounts_are_not_deprecated() {
+        let client = crate::tests::vault_client();
+        let deprecated = deprecated_mounts(&client).await.unwrap();
+        assert!(deprecated.is_empty());
+    }
+}
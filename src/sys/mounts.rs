@@ -1,7 +1,7 @@
 //! Implements the [`/sys/mounts`](https://www.vaultproject.io/api/system/mounts.html) endpoint
 use std::collections::{HashMap, HashSet};
 
-use crate::Error;
+use crate::{Error, MountPath};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -21,6 +21,13 @@ pub struct SecretEngine {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Configuration options for the mounts
     pub config: Option<SecretsEngineConfig>,
+    /// Accessor of the mount, used to identify the secrets engine in audit logs
+    #[serde(default, skip_serializing)]
+    pub accessor: String,
+    /// Deprecation status of the builtin plugin backing the mount, as listed by Vault 1.12 and
+    /// later. External plugins do not have a deprecation status.
+    #[serde(default, skip_serializing)]
+    pub deprecation_status: Option<DeprecationStatus>,
 }
 
 /// Configuration options for secrets engines
@@ -89,6 +96,9 @@ pub struct SecretsEngineTune {
     /// List of headers to whitelist, allowing a plugin to include them in the response.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub allowed_response_headers: Option<HashSet<String>>,
+    /// Specifies mount type specific options that are passed to the backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
 }
 
 /// Specifies whether to show this mount in the UI-specific listing endpoint.
@@ -101,7 +111,73 @@ pub enum ListingVisibility {
     Hidden,
 }
 
+/// Deprecation status of a builtin plugin
+///
+/// See the [deprecation notices](https://developer.hashicorp.com/vault/docs/deprecation).
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeprecationStatus {
+    /// The plugin is supported
+    Supported,
+    /// The plugin still works, but will be removed in a future release
+    Deprecated,
+    /// Mounting the plugin fails, unless Vault is started with
+    /// `VAULT_ALLOW_PENDING_REMOVAL_MOUNTS` set. Existing mounts prevent Vault from unsealing.
+    PendingRemoval,
+    /// The plugin has been removed from Vault
+    Removed,
+}
+
+impl DeprecationStatus {
+    /// Whether mounts of the plugin need to be migrated before upgrading Vault
+    pub fn is_deprecated(self) -> bool {
+        self != DeprecationStatus::Supported
+    }
+}
+
+/// Migration of a mount moved with [`Mounts::remount`]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct RemountMigration {
+    /// ID of the migration
+    pub migration_id: String,
+    /// Details of the migration
+    pub migration_info: RemountMigrationInfo,
+}
+
+/// Details of the migration of a mount
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct RemountMigrationInfo {
+    /// Path the mount is moved from
+    pub source_mount: String,
+    /// Path the mount is moved to
+    pub target_mount: String,
+    /// Status of the migration
+    pub status: RemountStatus,
+}
+
+/// Status of the migration of a mount
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemountStatus {
+    /// The mount is being moved
+    InProgress,
+    /// The mount was moved
+    Success,
+    /// The mount could not be moved, and remains at its source path
+    Failure,
+}
+
+impl RemountStatus {
+    /// Whether the migration has ended, successfully or not
+    pub fn is_finished(self) -> bool {
+        self != RemountStatus::InProgress
+    }
+}
+
 /// Implements the [`/sys/mounts`](https://www.vaultproject.io/api/system/mounts.html) endpoint
+///
+/// Paths of mounts are validated and normalized as a [`MountPath`] before being sent, so a
+/// [`MountPath`] or any string can be given.
 #[async_trait]
 pub trait Mounts {
     /// List all the mounted secrets engine
@@ -118,6 +194,17 @@ pub trait Mounts {
 
     /// Tune the configuration for a mount
     async fn tune(&self, path: &str, config: &SecretsEngineTune) -> Result<crate::Response, Error>;
+
+    /// Move a secrets engine, or an auth method prefixed with `auth/`, from one path to another.
+    /// Leases of the mount are revoked.
+    ///
+    /// From Vault 1.10, the mount is moved in the background. Returns the ID of the migration,
+    /// to follow with [`Mounts::remount_status`]. Earlier versions move the mount before
+    /// responding, without a migration ID.
+    async fn remount(&self, from: &str, to: &str) -> Result<Option<String>, Error>;
+
+    /// Read the status of the migration of a mount
+    async fn remount_status(&self, migration_id: &str) -> Result<RemountMigration, Error>;
 }
 
 #[async_trait]
@@ -131,8 +218,7 @@ where
         let values: Result<HashMap<String, SecretEngine>, Error> = values
             .into_iter()
             .map(|(path, mut map)| {
-                // Let's trim the trailing slash
-                let path = path.trim_end_matches('/').to_string();
+                let path = String::from(MountPath::new(&path)?);
 
                 let _ = map.insert("path".to_string(), serde_json::Value::String(path.clone()));
 
@@ -148,25 +234,54 @@ where
 
     async fn enable(&self, engine: &SecretEngine) -> Result<crate::Response, Error> {
         let mut value = serde_json::to_value(engine)?;
-        let path = value["path"].take();
-        let path = format!("sys/mounts/{}", path.as_str().expect("To be a string"));
+        let _ = value["path"].take();
+        let path = format!("sys/mounts/{}", MountPath::new(&engine.path)?);
         self.post(&path, &value, false).await
     }
 
     async fn disable(&self, path: &str) -> Result<crate::Response, Error> {
-        let path = format!("sys/mounts/{}", path);
+        let path = format!("sys/mounts/{}", MountPath::new(path)?);
         self.delete(&path, false).await
     }
 
     async fn get(&self, path: &str) -> Result<SecretsEngineConfig, Error> {
-        let path = format!("sys/mounts/{}/tune", path);
+        let path = format!("sys/mounts/{}/tune", MountPath::new(path)?);
         self.get(&path).await?.data()
     }
 
     async fn tune(&self, path: &str, config: &SecretsEngineTune) -> Result<crate::Response, Error> {
-        let path = format!("sys/mounts/{}/tune", path);
+        let path = format!("sys/mounts/{}/tune", MountPath::new(path)?);
         self.post(&path, config, false).await
     }
+
+    async fn remount(&self, from: &str, to: &str) -> Result<Option<String>, Error> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            from: &'a str,
+            to: &'a str,
+        }
+
+        #[derive(Deserialize)]
+        struct Migration {
+            migration_id: String,
+        }
+
+        let from = MountPath::new(from)?;
+        let to = MountPath::new(to)?;
+        let request = Request {
+            from: &from,
+            to: &to,
+        };
+        match self.post("sys/remount", &request, true).await? {
+            crate::Response::Empty => Ok(None),
+            response => Ok(Some(response.data::<Migration>()?.migration_id)),
+        }
+    }
+
+    async fn remount_status(&self, migration_id: &str) -> Result<RemountMigration, Error> {
+        let path = format!("sys/remount/status/{}", migration_id);
+        self.get(&path).await?.data()
+    }
 }
 
 #[cfg(test)]
@@ -208,6 +323,34 @@ pub(crate) mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn invalid_mount_paths_are_rejected() {
+        // Nothing listens on the port, so only validation can fail the requests quickly
+        let client = crate::Client::from_environment(
+            Some("http://127.0.0.1:9"),
+            Some("token"),
+            None::<&str>,
+        )
+        .unwrap();
+        assert!(matches!(
+            Mounts::disable(&client, "secret/../sys").await,
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            Mounts::remount(&client, "secret", "").await,
+            Err(Error::InvalidRequest(_))
+        ));
+        let engine = SecretEngine {
+            path: "kv//nested".to_string(),
+            r#type: "kv".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Mounts::enable(&client, &engine).await,
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn can_list_mounts() {
         let client = crate::tests::vault_client();
@@ -228,7 +371,7 @@ pub(crate) mod tests {
         assert!(response.ok().unwrap().is_none());
 
         let mounts = Mounts::list(&client).await.unwrap();
-        assert!(mounts.get(&path).is_some());
+        assert!(mounts.contains_key(&path));
 
         // Config can be read back
         let _ = Mounts::get(&client, &path).await.unwrap();
@@ -249,6 +392,42 @@ pub(crate) mod tests {
         assert!(response.ok().unwrap().is_none());
 
         let mounts = Mounts::list(&client).await.unwrap();
-        assert!(mounts.get(&path).is_none());
+        assert!(!mounts.contains_key(&path));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_remount_kv() {
+        let client = crate::tests::vault_client();
+
+        let from = crate::tests::uuid();
+        let to = crate::tests::uuid();
+        let engine = SecretEngine {
+            path: from.clone(),
+            r#type: "kv".to_string(),
+            ..Default::default()
+        };
+        let mut mount = Mount::new(&client, &engine).await;
+
+        let migration_id = Mounts::remount(&client, &from, &to).await.unwrap();
+        mount.path = to.clone();
+        if let Some(migration_id) = migration_id {
+            let mut attempts = 0;
+            let migration = loop {
+                let migration = Mounts::remount_status(&client, &migration_id)
+                    .await
+                    .unwrap();
+                if migration.migration_info.status.is_finished() {
+                    break migration;
+                }
+                attempts += 1;
+                assert!(attempts < 50, "Remount did not finish");
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            };
+            assert_eq!(migration.migration_info.status, RemountStatus::Success);
+        }
+
+        let mounts = Mounts::list(&client).await.unwrap();
+        assert!(!mounts.contains_key(&from));
+        assert!(mounts.contains_key(&to));
     }
 }
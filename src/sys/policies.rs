@@ -0,0 +1,217 @@
+//! Implements the [ACL Policies](https://www.vaultproject.io/api-docs/system/policies) endpoints
+//!
+//! Policies are written and read as documents in HCL or JSON. [`AclPolicy`] is a typed
+//! representation of a policy, which is written as JSON, and can be read back from policies
+//! written in JSON.
+use crate::{Error, Keys, Response};
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// ACL policy, as stored by Vault
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Policy {
+    /// Name of the policy
+    pub name: String,
+    /// Policy document, in HCL or JSON
+    pub policy: String,
+}
+
+impl Policy {
+    /// Parse the policy document into an [`AclPolicy`]. Only documents in JSON can be parsed.
+    pub fn parse(&self) -> Result<AclPolicy, Error> {
+        AclPolicy::from_json(&self.policy)
+    }
+}
+
+/// Typed ACL policy, granting capabilities on paths
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct AclPolicy {
+    /// Rules of the policy, keyed by path. Paths may end with `*`, or contain `+` to match a
+    /// single segment.
+    #[serde(default)]
+    pub path: BTreeMap<String, PathRules>,
+}
+
+impl AclPolicy {
+    /// Grant the capabilities on the path, replacing its existing rules
+    pub fn grant<S, I>(mut self, path: S, capabilities: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = Capability>,
+    {
+        let rules = PathRules {
+            capabilities: capabilities.into_iter().collect(),
+            ..Default::default()
+        };
+        let _ = self.path.insert(path.into(), rules);
+        self
+    }
+
+    /// Parse a policy document in JSON
+    pub fn from_json(document: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(document)?)
+    }
+
+    /// Policy document in JSON, which Vault accepts in place of HCL
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Rules of a path in an ACL policy
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct PathRules {
+    /// Capabilities granted on the path
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// Parameters which must be present in requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_parameters: Option<Vec<String>>,
+    /// Parameters allowed in requests, with their allowed values. An empty list allows any value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_parameters: Option<BTreeMap<String, Vec<serde_json::Value>>>,
+    /// Parameters denied in requests, with their denied values. An empty list denies any value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denied_parameters: Option<BTreeMap<String, Vec<serde_json::Value>>>,
+    /// Minimum TTL of the response wrapping requested by clients, like "1m"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_wrapping_ttl: Option<String>,
+    /// Maximum TTL of the response wrapping requested by clients, like "1h"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wrapping_ttl: Option<String>,
+}
+
+/// Capability granted on a path
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Capability {
+    /// Write data to paths which do not exist, with `POST` or `PUT`
+    Create,
+    /// Read data, with `GET`
+    Read,
+    /// Change data at paths which exist, with `POST` or `PUT`
+    Update,
+    /// Partially update data, with `PATCH`
+    Patch,
+    /// Delete data, with `DELETE`
+    Delete,
+    /// List keys, with `LIST`
+    List,
+    /// Access root-protected paths
+    Sudo,
+    /// Deny access, overriding every other capability
+    Deny,
+}
+
+/// Implements the [ACL Policies](https://www.vaultproject.io/api-docs/system/policies) endpoints
+#[async_trait]
+pub trait Policies {
+    /// List the names of the ACL policies
+    async fn list_policies(&self) -> Result<Vec<String>, Error>;
+    /// Read the ACL policy
+    async fn read_policy(&self, name: &str) -> Result<Policy, Error>;
+    /// Create an ACL policy from a document in HCL or JSON, or replace the existing policy
+    async fn create_policy(&self, name: &str, policy: &str) -> Result<Response, Error>;
+    /// Update the ACL policy, replacing its document
+    async fn update_policy(&self, name: &str, policy: &str) -> Result<Response, Error> {
+        self.create_policy(name, policy).await
+    }
+    /// Create or replace the ACL policy from its typed representation
+    async fn create_acl_policy(&self, name: &str, policy: &AclPolicy) -> Result<Response, Error> {
+        let document = policy.to_json()?;
+        self.create_policy(name, &document).await
+    }
+    /// Delete the ACL policy. Policies still attached to tokens are not revoked from them.
+    async fn delete_policy(&self, name: &str) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Policies for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn list_policies(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("sys/policies/acl").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn read_policy(&self, name: &str) -> Result<Policy, Error> {
+        let path = format!("sys/policies/acl/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn create_policy(&self, name: &str, policy: &str) -> Result<Response, Error> {
+        #[derive(Serialize)]
+        struct Payload<'a> {
+            policy: &'a str,
+        }
+
+        let path = format!("sys/policies/acl/{}", name);
+        self.post(&path, &Payload { policy }, false).await
+    }
+
+    async fn delete_policy(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("sys/policies/acl/{}", name);
+        self.delete(&path, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{uuid_prefix, vault_client};
+
+    #[test]
+    fn acl_policies_round_trip_through_json() {
+        let policy = AclPolicy::default()
+            .grant(
+                "secret/data/app/*",
+                vec![Capability::Read, Capability::List],
+            )
+            .grant("secret/data/admin", vec![Capability::Deny]);
+        let document = policy.to_json().unwrap();
+        assert_eq!(AclPolicy::from_json(&document).unwrap(), policy);
+
+        let parsed =
+            AclPolicy::from_json(r#"{"path": {"sys/*": {"capabilities": ["sudo", "read"]}}}"#)
+                .unwrap();
+        assert_eq!(
+            parsed.path["sys/*"].capabilities,
+            [Capability::Sudo, Capability::Read]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn policies_crud() {
+        let client = vault_client();
+        let name = uuid_prefix("policy");
+        let policy = AclPolicy::default().grant("secret/*", vec![Capability::Read]);
+
+        let _ = Policies::create_acl_policy(&client, &name, &policy)
+            .await
+            .unwrap();
+        assert!(Policies::list_policies(&client)
+            .await
+            .unwrap()
+            .contains(&name));
+        let read = Policies::read_policy(&client, &name).await.unwrap();
+        assert_eq!(read.name, name);
+        assert_eq!(read.parse().unwrap(), policy);
+
+        let _ = Policies::update_policy(
+            &client,
+            &name,
+            r#"path "secret/*" { capabilities = ["list"] }"#,
+        )
+        .await
+        .unwrap();
+        let read = Policies::read_policy(&client, &name).await.unwrap();
+        assert!(read.parse().is_err());
+
+        let _ = Policies::delete_policy(&client, &name).await.unwrap();
+        assert!(Policies::read_policy(&client, &name).await.is_err());
+    }
+}
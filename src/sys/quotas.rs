@@ -0,0 +1,150 @@
+//! Implements the [`/sys/quotas`](https://www.vaultproject.io/api-docs/system/rate-limit-quotas)
+//! endpoints
+use crate::{Error, Keys, Response};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Global configuration for quotas
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct QuotasConfig {
+    /// Specifies the list of exempt paths from all rate limit resource quotas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_exempt_paths: Option<Vec<String>>,
+    /// If set, starts audit logging of requests that get rejected due to rate limit quota rule
+    /// violations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_rate_limit_audit_logging: Option<bool>,
+    /// If set, additional rate limit quota HTTP headers will be added to responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_rate_limit_response_headers: Option<bool>,
+}
+
+/// Rate limit quota, limiting the rate of requests to a path
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct RateLimitQuota {
+    /// Name of the quota. Only returned by Vault.
+    #[serde(default, skip_serializing)]
+    pub name: String,
+    /// Path the quota applies to, like a mount (`secret/`), a path within a mount, or a
+    /// namespace. Empty for a global quota.
+    #[serde(default)]
+    pub path: String,
+    /// Maximum number of requests per `interval`
+    pub rate: f64,
+    /// Duration over which the rate is enforced, in seconds. Defaults to 1 second.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+    /// Duration for which clients exceeding the rate are blocked, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_interval: Option<u64>,
+    /// Login role of the auth method at `path` the quota applies to. Requires Vault 1.12 or
+    /// later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// Implements the [`/sys/quotas`](https://www.vaultproject.io/api-docs/system/rate-limit-quotas)
+/// endpoints
+#[async_trait]
+pub trait Quotas {
+    /// Read the global configuration for quotas
+    async fn read_config(&self) -> Result<QuotasConfig, Error>;
+
+    /// Update the global configuration for quotas. Options that are not set are left unchanged.
+    async fn configure(&self, config: &QuotasConfig) -> Result<Response, Error>;
+
+    /// Create or update the rate limit quota `name`
+    async fn write_rate_limit_quota(
+        &self,
+        name: &str,
+        quota: &RateLimitQuota,
+    ) -> Result<Response, Error>;
+
+    /// Read the rate limit quota `name`
+    async fn read_rate_limit_quota(&self, name: &str) -> Result<RateLimitQuota, Error>;
+
+    /// List the names of the rate limit quotas
+    async fn list_rate_limit_quotas(&self) -> Result<Vec<String>, Error>;
+
+    /// Delete the rate limit quota `name`
+    async fn delete_rate_limit_quota(&self, name: &str) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Quotas for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn read_config(&self) -> Result<QuotasConfig, Error> {
+        self.get("sys/quotas/config").await?.data()
+    }
+
+    async fn configure(&self, config: &QuotasConfig) -> Result<Response, Error> {
+        self.post("sys/quotas/config", config, false).await
+    }
+
+    async fn write_rate_limit_quota(
+        &self,
+        name: &str,
+        quota: &RateLimitQuota,
+    ) -> Result<Response, Error> {
+        let path = format!("sys/quotas/rate-limit/{}", name);
+        self.post(&path, quota, false).await
+    }
+
+    async fn read_rate_limit_quota(&self, name: &str) -> Result<RateLimitQuota, Error> {
+        let path = format!("sys/quotas/rate-limit/{}", name);
+        self.get(&path).await?.data()
+    }
+
+    async fn list_rate_limit_quotas(&self) -> Result<Vec<String>, Error> {
+        let keys: Keys = self.list("sys/quotas/rate-limit").await?.data()?;
+        Ok(keys.keys)
+    }
+
+    async fn delete_rate_limit_quota(&self, name: &str) -> Result<Response, Error> {
+        let path = format!("sys/quotas/rate-limit/{}", name);
+        self.delete(&path, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_manage_rate_limit_quotas() {
+        let client = crate::tests::vault_client();
+        let name = crate::tests::uuid_prefix("quota");
+
+        let quota = RateLimitQuota {
+            path: "cubbyhole/".to_string(),
+            rate: 100.0,
+            interval: Some(60),
+            block_interval: Some(120),
+            ..Default::default()
+        };
+        let response = Quotas::write_rate_limit_quota(&client, &name, &quota)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+
+        let actual = Quotas::read_rate_limit_quota(&client, &name).await.unwrap();
+        assert_eq!(actual.name, name);
+        assert_eq!(actual.path, quota.path);
+        assert_eq!(actual.rate, 100.0);
+        assert_eq!(actual.interval, Some(60));
+        assert_eq!(actual.block_interval, Some(120));
+        assert!(Quotas::list_rate_limit_quotas(&client)
+            .await
+            .unwrap()
+            .contains(&name));
+
+        let response = Quotas::delete_rate_limit_quota(&client, &name)
+            .await
+            .unwrap();
+        assert!(response.ok().unwrap().is_none());
+        assert!(Quotas::read_rate_limit_quota(&client, &name).await.is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Implements the [`/sys/raw`](https://www.vaultproject.io/api/system/raw.html) endpoints
+//!
+//! **These endpoints bypass the barrier of Vault, and read and write its storage directly.**
+//! Entries written here are not validated, and can leave Vault unable to unseal. They are
+//! meant for recovery tooling, and are only available when Vault is started with
+//! `raw_storage_endpoint` enabled, to root tokens.
+//!
+//! Values are exchanged base64 encoded, so that binary entries are preserved. This requires
+//! Vault 1.15 or later.
+use crate::{Error, Keys, Response};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct Encoding {
+    encoding: &'static str,
+}
+
+const BASE64: Encoding = Encoding { encoding: "base64" };
+
+/// Implements the [`/sys/raw`](https://www.vaultproject.io/api/system/raw.html) endpoints
+#[async_trait]
+pub trait RawStorage {
+    /// Read the entry of the storage at `path`, decompressed
+    async fn read_raw_storage(&self, path: &str) -> Result<Vec<u8>, Error>;
+    /// Write the entry of the storage at `path`, replacing any existing entry
+    async fn write_raw_storage(&self, path: &str, value: &[u8]) -> Result<Response, Error>;
+    /// Delete the entry of the storage at `path`
+    async fn delete_raw_storage(&self, path: &str) -> Result<Response, Error>;
+    /// List the keys of the storage under `prefix`. Subdirectories end with `/`.
+    async fn list_raw_storage(&self, prefix: &str) -> Result<Vec<String>, Error>;
+}
+
+#[async_trait]
+impl<T> RawStorage for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn read_raw_storage(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[derive(Deserialize)]
+        struct Entry {
+            value: String,
+        }
+
+        let path = format!("sys/raw/{}", path);
+        let entry: Entry = self.get_with_query(&path, &BASE64).await?.data()?;
+        crate::crypto::base64().decode(&entry.value)
+    }
+
+    async fn write_raw_storage(&self, path: &str, value: &[u8]) -> Result<Response, Error> {
+        #[derive(Serialize)]
+        struct Entry {
+            value: String,
+            encoding: &'static str,
+        }
+
+        let path = format!("sys/raw/{}", path);
+        let entry = Entry {
+            value: crate::crypto::base64().encode(value),
+            encoding: BASE64.encoding,
+        };
+        self.put(&path, &entry, false).await
+    }
+
+    async fn delete_raw_storage(&self, path: &str) -> Result<Response, Error> {
+        let path = format!("sys/raw/{}", path);
+        self.delete(&path, false).await
+    }
+
+    async fn list_raw_storage(&self, prefix: &str) -> Result<Vec<String>, Error> {
+        let path = format!("sys/raw/{}", prefix);
+        let keys: Keys = self.list(&path).await?.data()?;
+        Ok(keys.keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn raw_storage_is_disabled_by_default() {
+        // The dev server does not enable the raw storage endpoint
+        let client = crate::tests::vault_client();
+        assert!(RawStorage::read_raw_storage(&client, "core/mounts")
+            .await
+            .is_err());
+        assert!(RawStorage::list_raw_storage(&client, "core/")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,553 @@
+//! Declarative configuration of mounts, auth methods and ACL policies
+//!
+//! [`export`] reads the secrets engines, auth methods and ACL policies of Vault into a [`Spec`].
+//! [`diff`] compares two specs, and returns the [`Change`]s turning one into the other, which
+//! [`apply`] makes. [`reconcile`] does all three, to converge Vault on a desired spec.
+//!
+//! Mounts created by Vault itself, like `sys/`, `cubbyhole/`, `identity/` and the `token` auth
+//! method, and the `root` policy, are not part of specs, and are never changed. The `default`
+//! policy is exported, and can be updated, but is never deleted.
+use crate::sys::auth::{AuthMethod, AuthMethods};
+use crate::sys::mounts::{Mounts, SecretEngine, SecretsEngineConfig, SecretsEngineTune};
+use crate::sys::policies::Policies;
+use crate::{Error, MountPath};
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Secrets engines which are mounted by Vault, and cannot be changed
+const SYSTEM_MOUNTS: &[&str] = &["sys", "cubbyhole", "identity"];
+/// Auth methods which are mounted by Vault, and cannot be changed
+const SYSTEM_AUTH_METHODS: &[&str] = &["token"];
+/// Policy which cannot be changed
+const ROOT_POLICY: &str = "root";
+/// Policy which cannot be deleted
+const DEFAULT_POLICY: &str = "default";
+
+/// Desired configuration of a secrets engine or auth method
+///
+/// Tuning parameters left unset are not compared, and keep their current value.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct MountSpec {
+    /// Type of the secrets engine or auth method, like `kv` or `approle`
+    pub r#type: String,
+    /// Human-friendly description of the mount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Default lease duration, in seconds. 0 uses the default of Vault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_lease_ttl: Option<u64>,
+    /// Maximum lease duration, in seconds. 0 uses the default of Vault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lease_ttl: Option<u64>,
+    /// Mount type specific options, like `version` for the `kv` secrets engine. Options which
+    /// are not listed are not compared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+}
+
+impl MountSpec {
+    /// Mount of the type, like `kv` or `approle`, with the default configuration
+    pub fn of_type<S: Into<String>>(r#type: S) -> Self {
+        Self {
+            r#type: r#type.into(),
+            ..Default::default()
+        }
+    }
+
+    fn new(
+        r#type: String,
+        description: Option<String>,
+        config: Option<SecretsEngineConfig>,
+    ) -> Self {
+        let config = config.unwrap_or_default();
+        Self {
+            r#type,
+            description: description.filter(|description| !description.is_empty()),
+            default_lease_ttl: config.default_lease_ttl,
+            max_lease_ttl: config.max_lease_ttl,
+            options: config.options.filter(|options| !options.is_empty()),
+        }
+    }
+
+    /// Whether the tuning parameters set in `self` differ from `current`
+    fn needs_tuning(&self, current: &MountSpec) -> bool {
+        fn differs<T: PartialEq>(desired: &Option<T>, current: &Option<T>) -> bool {
+            desired.is_some() && desired != current
+        }
+
+        let options = self.options.as_ref().is_some_and(|options| {
+            let current = current.options.clone().unwrap_or_default();
+            options
+                .iter()
+                .any(|(key, value)| current.get(key) != Some(value))
+        });
+        differs(&self.description, &current.description)
+            || differs(&self.default_lease_ttl, &current.default_lease_ttl)
+            || differs(&self.max_lease_ttl, &current.max_lease_ttl)
+            || options
+    }
+
+    fn tune(&self) -> SecretsEngineTune {
+        SecretsEngineTune {
+            description: self.description.clone(),
+            default_lease_ttl: self.default_lease_ttl,
+            max_lease_ttl: self.max_lease_ttl,
+            options: self.options.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn config(&self) -> SecretsEngineConfig {
+        SecretsEngineConfig {
+            default_lease_ttl: self.default_lease_ttl,
+            max_lease_ttl: self.max_lease_ttl,
+            options: self.options.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Secrets engines, auth methods and ACL policies of Vault
+///
+/// Mounts are keyed by their path, without leading or trailing slashes, and auth methods by
+/// their path without the `auth/` prefix. Policies are keyed by their name.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct Spec {
+    /// Secrets engines
+    #[serde(default)]
+    pub mounts: BTreeMap<String, MountSpec>,
+    /// Auth methods
+    #[serde(default)]
+    pub auth: BTreeMap<String, MountSpec>,
+    /// ACL policies, in HCL or JSON
+    #[serde(default)]
+    pub policies: BTreeMap<String, String>,
+}
+
+/// Change to the configuration of Vault
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Change {
+    /// Enable a secrets engine
+    EnableMount {
+        /// Path of the mount
+        path: String,
+        /// Configuration of the mount
+        spec: MountSpec,
+    },
+    /// Tune a secrets engine
+    TuneMount {
+        /// Path of the mount
+        path: String,
+        /// Configuration of the mount
+        spec: MountSpec,
+    },
+    /// Disable a secrets engine, deleting its data
+    DisableMount {
+        /// Path of the mount
+        path: String,
+    },
+    /// Enable an auth method
+    EnableAuth {
+        /// Path of the auth method
+        path: String,
+        /// Configuration of the auth method
+        spec: MountSpec,
+    },
+    /// Tune an auth method
+    TuneAuth {
+        /// Path of the auth method
+        path: String,
+        /// Configuration of the auth method
+        spec: MountSpec,
+    },
+    /// Disable an auth method, deleting its configuration and revoking its tokens
+    DisableAuth {
+        /// Path of the auth method
+        path: String,
+    },
+    /// Create or update an ACL policy
+    WritePolicy {
+        /// Name of the policy
+        name: String,
+        /// Rules of the policy
+        policy: String,
+    },
+    /// Delete an ACL policy
+    DeletePolicy {
+        /// Name of the policy
+        name: String,
+    },
+}
+
+impl Change {
+    /// Whether the change deletes data or configuration
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Change::DisableMount { .. } | Change::DisableAuth { .. } | Change::DeletePolicy { .. }
+        )
+    }
+}
+
+/// Read the secrets engines, auth methods and ACL policies of Vault
+pub async fn export<T>(client: &T) -> Result<Spec, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let mounts = Mounts::list(client)
+        .await?
+        .into_iter()
+        .filter(|(path, _)| !SYSTEM_MOUNTS.contains(&path.as_str()))
+        .map(|(path, engine)| {
+            let spec = MountSpec::new(engine.r#type, engine.description, engine.config);
+            (path, spec)
+        })
+        .collect();
+    let auth = AuthMethods::list_auth(client)
+        .await?
+        .into_iter()
+        .filter(|(path, _)| !SYSTEM_AUTH_METHODS.contains(&path.as_str()))
+        .map(|(path, method)| {
+            let spec = MountSpec::new(method.r#type, method.description, method.config);
+            (path, spec)
+        })
+        .collect();
+
+    let mut policies = BTreeMap::new();
+    for name in Policies::list_policies(client).await? {
+        if name == ROOT_POLICY {
+            continue;
+        }
+        let policy = Policies::read_policy(client, &name).await?;
+        let _ = policies.insert(name, policy.policy);
+    }
+
+    Ok(Spec {
+        mounts,
+        auth,
+        policies,
+    })
+}
+
+/// Changes turning `current` into `desired`
+///
+/// Mounts and auth methods are enabled and tuned first, then policies are written, and finally
+/// policies, auth methods and mounts missing from `desired` are deleted, so that policies never
+/// refer to mounts which do not exist yet. Changing the type of a mount disables it, deleting its
+/// data, before enabling it again.
+pub fn diff(current: &Spec, desired: &Spec) -> Result<Vec<Change>, Error> {
+    let current_mounts = normalize(&current.mounts, SYSTEM_MOUNTS)?;
+    let desired_mounts = normalize(&desired.mounts, SYSTEM_MOUNTS)?;
+    let current_auth = normalize(&current.auth, SYSTEM_AUTH_METHODS)?;
+    let desired_auth = normalize(&desired.auth, SYSTEM_AUTH_METHODS)?;
+
+    let mut changes = vec![];
+    let mut deletions = vec![];
+
+    let (mounts, unmounts) = diff_mounts(&current_mounts, &desired_mounts);
+    changes.extend(mounts.into_iter().map(|(path, spec, enable)| {
+        if enable {
+            Change::EnableMount { path, spec }
+        } else {
+            Change::TuneMount { path, spec }
+        }
+    }));
+    let (methods, disabled) = diff_mounts(&current_auth, &desired_auth);
+    changes.extend(methods.into_iter().map(|(path, spec, enable)| {
+        if enable {
+            Change::EnableAuth { path, spec }
+        } else {
+            Change::TuneAuth { path, spec }
+        }
+    }));
+
+    for (name, policy) in desired.policies.iter() {
+        if name == ROOT_POLICY {
+            return Err(Error::InvalidRequest(
+                "the root policy cannot be changed".to_string(),
+            ));
+        }
+        if current.policies.get(name) != Some(policy) {
+            changes.push(Change::WritePolicy {
+                name: name.clone(),
+                policy: policy.clone(),
+            });
+        }
+    }
+    deletions.extend(
+        current
+            .policies
+            .keys()
+            .filter(|name| !desired.policies.contains_key(*name))
+            .filter(|name| *name != ROOT_POLICY && *name != DEFAULT_POLICY)
+            .map(|name| Change::DeletePolicy { name: name.clone() }),
+    );
+    deletions.extend(
+        disabled
+            .into_iter()
+            .map(|path| Change::DisableAuth { path }),
+    );
+    deletions.extend(
+        unmounts
+            .into_iter()
+            .map(|path| Change::DisableMount { path }),
+    );
+
+    // Mounts replaced by a mount of another type are disabled before they are enabled again
+    let (replaced, deletions): (Vec<Change>, Vec<Change>) =
+        deletions.into_iter().partition(|deletion| {
+            changes.iter().any(|change| match (deletion, change) {
+                (Change::DisableMount { path }, Change::EnableMount { path: enabled, .. })
+                | (Change::DisableAuth { path }, Change::EnableAuth { path: enabled, .. }) => {
+                    path == enabled
+                }
+                _ => false,
+            })
+        });
+    Ok(replaced
+        .into_iter()
+        .chain(changes)
+        .chain(deletions)
+        .collect())
+}
+
+/// Make the changes, in order, stopping at the first failure
+pub async fn apply<T>(client: &T, changes: &[Change]) -> Result<(), Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    for change in changes {
+        let response = match change {
+            Change::EnableMount { path, spec } => {
+                let engine = SecretEngine {
+                    path: path.clone(),
+                    r#type: spec.r#type.clone(),
+                    description: spec.description.clone(),
+                    config: Some(spec.config()),
+                    ..Default::default()
+                };
+                Mounts::enable(client, &engine).await?
+            }
+            Change::TuneMount { path, spec } => Mounts::tune(client, path, &spec.tune()).await?,
+            Change::DisableMount { path } => Mounts::disable(client, path).await?,
+            Change::EnableAuth { path, spec } => {
+                let method = AuthMethod {
+                    path: path.clone(),
+                    r#type: spec.r#type.clone(),
+                    description: spec.description.clone(),
+                    config: Some(spec.config()),
+                    ..Default::default()
+                };
+                AuthMethods::enable_auth(client, &method).await?
+            }
+            Change::TuneAuth { path, spec } => {
+                AuthMethods::tune_auth(client, path, &spec.tune()).await?
+            }
+            Change::DisableAuth { path } => AuthMethods::disable_auth(client, path).await?,
+            Change::WritePolicy { name, policy } => {
+                Policies::create_policy(client, name, policy).await?
+            }
+            Change::DeletePolicy { name } => Policies::delete_policy(client, name).await?,
+        };
+        let _ = response.ok()?;
+    }
+    Ok(())
+}
+
+/// Converge Vault on the desired spec, returning the changes made
+pub async fn reconcile<T>(client: &T, desired: &Spec) -> Result<Vec<Change>, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let current = export(client).await?;
+    let changes = diff(&current, desired)?;
+    apply(client, &changes).await?;
+    Ok(changes)
+}
+
+/// Normalize the paths of mounts, rejecting system mounts
+fn normalize<'a>(
+    mounts: &'a BTreeMap<String, MountSpec>,
+    system: &[&str],
+) -> Result<BTreeMap<String, &'a MountSpec>, Error> {
+    mounts
+        .iter()
+        .map(|(path, spec)| {
+            let path = String::from(MountPath::new(path)?);
+            if system.contains(&path.as_str()) {
+                return Err(Error::InvalidRequest(format!(
+                    "{} is mounted by Vault, and cannot be changed",
+                    path
+                )));
+            }
+            Ok((path, spec))
+        })
+        .collect()
+}
+
+/// Mounts to enable or tune, with whether they are enabled, and paths of the mounts to disable
+#[allow(clippy::type_complexity)]
+fn diff_mounts(
+    current: &BTreeMap<String, &MountSpec>,
+    desired: &BTreeMap<String, &MountSpec>,
+) -> (Vec<(String, MountSpec, bool)>, Vec<String>) {
+    let mut changes = vec![];
+    let mut disabled = vec![];
+    for (path, spec) in desired.iter() {
+        match current.get(path) {
+            Some(current) if current.r#type == spec.r#type => {
+                if spec.needs_tuning(current) {
+                    changes.push((path.clone(), (*spec).clone(), false));
+                }
+            }
+            Some(_) => {
+                disabled.push(path.clone());
+                changes.push((path.clone(), (*spec).clone(), true));
+            }
+            None => changes.push((path.clone(), (*spec).clone(), true)),
+        }
+    }
+    disabled.extend(
+        current
+            .keys()
+            .filter(|path| !desired.contains_key(*path))
+            .cloned(),
+    );
+    (changes, disabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv(version: &str) -> MountSpec {
+        MountSpec {
+            r#type: "kv".to_string(),
+            options: Some(
+                vec![("version".to_string(), version.to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn diff_orders_changes() {
+        let current = Spec {
+            mounts: vec![
+                ("secret".to_string(), kv("2")),
+                ("old".to_string(), kv("1")),
+                ("pki".to_string(), kv("1")),
+            ]
+            .into_iter()
+            .collect(),
+            policies: vec![
+                ("default".to_string(), "".to_string()),
+                ("old".to_string(), "".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let pki = MountSpec {
+            r#type: "pki".to_string(),
+            max_lease_ttl: Some(3600),
+            ..Default::default()
+        };
+        let desired = Spec {
+            mounts: vec![
+                ("/secret/".to_string(), kv("2")),
+                ("new".to_string(), kv("1")),
+                ("pki".to_string(), pki.clone()),
+            ]
+            .into_iter()
+            .collect(),
+            policies: vec![("app".to_string(), "path \"*\" {}".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let changes = diff(&current, &desired).unwrap();
+        assert_eq!(
+            changes,
+            [
+                Change::DisableMount {
+                    path: "pki".to_string()
+                },
+                Change::EnableMount {
+                    path: "new".to_string(),
+                    spec: kv("1"),
+                },
+                Change::EnableMount {
+                    path: "pki".to_string(),
+                    spec: pki,
+                },
+                Change::WritePolicy {
+                    name: "app".to_string(),
+                    policy: "path \"*\" {}".to_string(),
+                },
+                Change::DeletePolicy {
+                    name: "old".to_string()
+                },
+                Change::DisableMount {
+                    path: "old".to_string()
+                },
+            ]
+        );
+        assert!(diff(&desired, &desired).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unset_parameters_are_not_tuned() {
+        let current = MountSpec {
+            description: Some("Secrets".to_string()),
+            default_lease_ttl: Some(60),
+            ..kv("2")
+        };
+        assert!(!kv("2").needs_tuning(&current));
+        assert!(kv("1").needs_tuning(&current));
+        assert!(MountSpec {
+            default_lease_ttl: Some(120),
+            ..Default::default()
+        }
+        .needs_tuning(&current));
+    }
+
+    #[test]
+    fn system_mounts_cannot_be_changed() {
+        let desired = Spec {
+            mounts: vec![("sys/".to_string(), kv("1"))].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(diff(&Spec::default(), &desired).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_reconcile_specs() {
+        let client = crate::tests::vault_client();
+        let mut desired = export(&client).await.unwrap();
+        assert!(diff(&desired, &desired).unwrap().is_empty());
+
+        let path = crate::tests::uuid();
+        let policy = crate::tests::uuid_prefix("reconcile");
+        let _ = desired.mounts.insert(path.clone(), kv("2"));
+        let _ = desired.policies.insert(
+            policy.clone(),
+            format!("path \"{}/*\" {{ capabilities = [\"read\"] }}", path),
+        );
+        let changes = reconcile(&client, &desired).await.unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(reconcile(&client, &desired).await.unwrap().is_empty());
+
+        let _ = desired.mounts.remove(&path);
+        let _ = desired.policies.remove(&policy);
+        let changes = reconcile(&client, &desired).await.unwrap();
+        assert!(changes.iter().all(Change::is_destructive));
+        assert_eq!(changes.len(), 2);
+    }
+}
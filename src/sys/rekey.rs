@@ -0,0 +1,518 @@
+//! Implements the [`/sys/rekey`](https://www.vaultproject.io/api/system/rekey.html) endpoints,
+//! which replace the unseal key, and the
+//! [`/sys/rekey-recovery-key`](https://www.vaultproject.io/api/system/rekey-recovery-key.html)
+//! endpoints, which replace the recovery keys of clusters using auto-unseal
+//!
+//! A rekey takes several calls, possibly from different key holders: it is started, each holder
+//! provides their current key share with the nonce of the rekey, and once enough shares are
+//! provided, the new key shares are returned. [`RekeyStatus::stage`] tells which step a rekey
+//! is at.
+use crate::{Error, Method, Response, Secret};
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Parameters to start a rekey
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct RekeyInit {
+    /// Number of key shares to split the new key into
+    pub secret_shares: u64,
+    /// Number of key shares required to reconstruct the new key
+    pub secret_threshold: u64,
+    /// PGP public keys, base64 encoded, to encrypt each new key share with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pgp_keys: Option<Vec<String>>,
+    /// Back up the PGP encrypted key shares in Vault
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<bool>,
+    /// Require the new key shares to be provided back to Vault before the rekey is applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_verification: Option<bool>,
+}
+
+/// Progress of a rekey, and the new key shares once it completes
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct RekeyStatus {
+    /// Whether a rekey is in progress
+    #[serde(default)]
+    pub started: bool,
+    /// Nonce of the rekey in progress, which must be provided with each key share
+    #[serde(default)]
+    pub nonce: String,
+    /// Number of key shares required to reconstruct the new key
+    #[serde(default)]
+    pub t: u64,
+    /// Number of key shares the new key is split into
+    #[serde(default)]
+    pub n: u64,
+    /// Number of current key shares provided so far
+    #[serde(default)]
+    pub progress: u64,
+    /// Number of current key shares required to rekey
+    #[serde(default)]
+    pub required: u64,
+    /// Whether the rekey is complete
+    #[serde(default)]
+    pub complete: bool,
+    /// New key shares, hex encoded. Set once the rekey is complete.
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub keys: Vec<Secret>,
+    /// New key shares, base64 encoded. Set once the rekey is complete.
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub keys_base64: Vec<Secret>,
+    /// Fingerprints of the PGP keys encrypting the new key shares, if any
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub pgp_fingerprints: Vec<String>,
+    /// Whether the PGP encrypted key shares are backed up in Vault
+    #[serde(default)]
+    pub backup: bool,
+    /// Whether the new key shares must be verified before the rekey is applied
+    #[serde(default)]
+    pub verification_required: bool,
+    /// Nonce of the verification, once the rekey is complete and verification is required
+    #[serde(default)]
+    pub verification_nonce: String,
+}
+
+impl RekeyStatus {
+    /// Step of the rekey
+    pub fn stage(&self) -> RekeyStage {
+        if self.complete {
+            if self.verification_required {
+                RekeyStage::AwaitingVerification {
+                    nonce: self.verification_nonce.clone(),
+                }
+            } else {
+                RekeyStage::Complete
+            }
+        } else if self.started {
+            RekeyStage::CollectingKeys {
+                nonce: self.nonce.clone(),
+                progress: self.progress,
+                required: self.required,
+            }
+        } else {
+            RekeyStage::NotStarted
+        }
+    }
+}
+
+/// Step of a rekey
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RekeyStage {
+    /// No rekey is in progress
+    NotStarted,
+    /// Current key shares are being provided
+    CollectingKeys {
+        /// Nonce of the rekey, to provide with each key share
+        nonce: String,
+        /// Number of current key shares provided so far
+        progress: u64,
+        /// Number of current key shares required
+        required: u64,
+    },
+    /// The new key shares were returned, and must be provided back to Vault before the rekey is
+    /// applied
+    AwaitingVerification {
+        /// Nonce of the verification, to provide with each new key share
+        nonce: String,
+    },
+    /// The rekey is applied
+    Complete,
+}
+
+/// PGP encrypted key shares backed up by a rekey
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct RekeyBackup {
+    /// Nonce of the rekey which backed up the key shares
+    pub nonce: String,
+    /// Encrypted key shares, keyed by the fingerprint of the PGP key encrypting them
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub keys: HashMap<String, Vec<String>>,
+}
+
+/// Progress of the verification of the new key shares of a rekey
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct RekeyVerificationStatus {
+    /// Whether a verification is in progress
+    #[serde(default)]
+    pub started: bool,
+    /// Nonce of the verification, which must be provided with each new key share
+    #[serde(default)]
+    pub nonce: String,
+    /// Number of new key shares required
+    #[serde(default)]
+    pub t: u64,
+    /// Number of new key shares
+    #[serde(default)]
+    pub n: u64,
+    /// Number of new key shares provided so far
+    #[serde(default)]
+    pub progress: u64,
+    /// Whether the verification is complete, and the rekey applied
+    #[serde(default)]
+    pub complete: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct KeyShare<'a> {
+    key: &'a str,
+    nonce: &'a str,
+}
+
+/// Implements the [`/sys/rekey`](https://www.vaultproject.io/api/system/rekey.html) endpoints
+#[async_trait]
+pub trait Rekey {
+    /// Read the progress of the unseal key rekey in progress, if any
+    async fn rekey_status(&self) -> Result<RekeyStatus, Error>;
+    /// Start an unseal key rekey
+    async fn start_rekey(&self, init: &RekeyInit) -> Result<RekeyStatus, Error>;
+    /// Cancel the unseal key rekey in progress. Key shares provided so far are discarded.
+    async fn cancel_rekey(&self) -> Result<Response, Error>;
+    /// Provide a single current unseal key share for the rekey with the `nonce`. The returned
+    /// status contains the new key shares once enough shares are provided.
+    async fn provide_rekey_key(&self, key: &str, nonce: &str) -> Result<RekeyStatus, Error>;
+    /// Provide key shares until the rekey with the `nonce` completes, returning the final
+    /// status with the new key shares. Fails if the rekey is still in progress once every key
+    /// share has been provided.
+    async fn provide_rekey_keys<S: AsRef<str> + Sync>(
+        &self,
+        keys: &[S],
+        nonce: &str,
+    ) -> Result<RekeyStatus, Error> {
+        let mut status = self.rekey_status().await?;
+        for key in keys {
+            if status.complete {
+                break;
+            }
+            status = self.provide_rekey_key(key.as_ref(), nonce).await?;
+        }
+        if !status.complete {
+            return Err(Error::InvalidRequest(format!(
+                "Rekey is still in progress: {} of {} key shares provided",
+                status.progress, status.required
+            )));
+        }
+        Ok(status)
+    }
+    /// Read the progress of the verification of the new unseal key shares
+    async fn rekey_verification_status(&self) -> Result<RekeyVerificationStatus, Error>;
+    /// Restart the verification of the new unseal key shares, with a new nonce
+    async fn restart_rekey_verification(&self) -> Result<Response, Error>;
+    /// Provide a single new unseal key share for the verification with the `nonce`
+    async fn provide_rekey_verification_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyVerificationStatus, Error>;
+    /// Read the PGP encrypted key shares backed up by the last rekey with `backup` set
+    async fn read_rekey_backup(&self) -> Result<RekeyBackup, Error>;
+    /// Delete the backed up key shares
+    async fn delete_rekey_backup(&self) -> Result<Response, Error>;
+}
+
+const UNSEAL: &str = "sys/rekey";
+
+#[async_trait]
+impl<T> Rekey for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn rekey_status(&self) -> Result<RekeyStatus, Error> {
+        status(self, UNSEAL).await
+    }
+
+    async fn start_rekey(&self, init: &RekeyInit) -> Result<RekeyStatus, Error> {
+        start(self, UNSEAL, init).await
+    }
+
+    async fn cancel_rekey(&self) -> Result<Response, Error> {
+        cancel(self, UNSEAL).await
+    }
+
+    async fn provide_rekey_key(&self, key: &str, nonce: &str) -> Result<RekeyStatus, Error> {
+        provide_key(self, UNSEAL, key, nonce).await
+    }
+
+    async fn rekey_verification_status(&self) -> Result<RekeyVerificationStatus, Error> {
+        verification_status(self, UNSEAL).await
+    }
+
+    async fn restart_rekey_verification(&self) -> Result<Response, Error> {
+        restart_verification(self, UNSEAL).await
+    }
+
+    async fn provide_rekey_verification_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyVerificationStatus, Error> {
+        provide_verification_key(self, UNSEAL, key, nonce).await
+    }
+
+    async fn read_rekey_backup(&self) -> Result<RekeyBackup, Error> {
+        self.get("sys/rekey/backup").await?.data()
+    }
+
+    async fn delete_rekey_backup(&self) -> Result<Response, Error> {
+        self.delete("sys/rekey/backup", false).await
+    }
+}
+
+/// Implements the
+/// [`/sys/rekey-recovery-key`](https://www.vaultproject.io/api/system/rekey-recovery-key.html)
+/// endpoints
+#[async_trait]
+pub trait RecoveryRekey {
+    /// Read the progress of the recovery key rekey in progress, if any
+    async fn recovery_rekey_status(&self) -> Result<RekeyStatus, Error>;
+    /// Start a recovery key rekey
+    async fn start_recovery_rekey(&self, init: &RekeyInit) -> Result<RekeyStatus, Error>;
+    /// Cancel the recovery key rekey in progress. Key shares provided so far are discarded.
+    async fn cancel_recovery_rekey(&self) -> Result<Response, Error>;
+    /// Provide a single current recovery key share for the rekey with the `nonce`. The
+    /// returned status contains the new key shares once enough shares are provided.
+    async fn provide_recovery_rekey_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyStatus, Error>;
+    /// Read the progress of the verification of the new recovery key shares
+    async fn recovery_rekey_verification_status(&self) -> Result<RekeyVerificationStatus, Error>;
+    /// Restart the verification of the new recovery key shares, with a new nonce
+    async fn restart_recovery_rekey_verification(&self) -> Result<Response, Error>;
+    /// Provide a single new recovery key share for the verification with the `nonce`
+    async fn provide_recovery_rekey_verification_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyVerificationStatus, Error>;
+}
+
+const RECOVERY: &str = "sys/rekey-recovery-key";
+
+#[async_trait]
+impl<T> RecoveryRekey for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn recovery_rekey_status(&self) -> Result<RekeyStatus, Error> {
+        status(self, RECOVERY).await
+    }
+
+    async fn start_recovery_rekey(&self, init: &RekeyInit) -> Result<RekeyStatus, Error> {
+        start(self, RECOVERY, init).await
+    }
+
+    async fn cancel_recovery_rekey(&self) -> Result<Response, Error> {
+        cancel(self, RECOVERY).await
+    }
+
+    async fn provide_recovery_rekey_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyStatus, Error> {
+        provide_key(self, RECOVERY, key, nonce).await
+    }
+
+    async fn recovery_rekey_verification_status(&self) -> Result<RekeyVerificationStatus, Error> {
+        verification_status(self, RECOVERY).await
+    }
+
+    async fn restart_recovery_rekey_verification(&self) -> Result<Response, Error> {
+        restart_verification(self, RECOVERY).await
+    }
+
+    async fn provide_recovery_rekey_verification_key(
+        &self,
+        key: &str,
+        nonce: &str,
+    ) -> Result<RekeyVerificationStatus, Error> {
+        provide_verification_key(self, RECOVERY, key, nonce).await
+    }
+}
+
+// The rekey endpoints return their responses as-is, without the usual response wrapping
+
+async fn status<T>(client: &T, base: &str) -> Result<RekeyStatus, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/init", base);
+    client
+        .read_with_options(&path, Method::GET, &Default::default())
+        .await
+}
+
+async fn start<T>(client: &T, base: &str, init: &RekeyInit) -> Result<RekeyStatus, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/init", base);
+    client
+        .write_raw(&path, init, Method::PUT, &Default::default())
+        .await
+}
+
+async fn cancel<T>(client: &T, base: &str) -> Result<Response, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/init", base);
+    client.delete(&path, false).await
+}
+
+async fn provide_key<T>(
+    client: &T,
+    base: &str,
+    key: &str,
+    nonce: &str,
+) -> Result<RekeyStatus, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/update", base);
+    client
+        .write_raw(
+            &path,
+            &KeyShare { key, nonce },
+            Method::PUT,
+            &Default::default(),
+        )
+        .await
+}
+
+async fn verification_status<T>(client: &T, base: &str) -> Result<RekeyVerificationStatus, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/verify", base);
+    client
+        .read_with_options(&path, Method::GET, &Default::default())
+        .await
+}
+
+async fn restart_verification<T>(client: &T, base: &str) -> Result<Response, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/verify", base);
+    client.delete(&path, false).await
+}
+
+async fn provide_verification_key<T>(
+    client: &T,
+    base: &str,
+    key: &str,
+    nonce: &str,
+) -> Result<RekeyVerificationStatus, Error>
+where
+    T: crate::Vault + Send + Sync,
+{
+    let path = format!("{}/verify", base);
+    client
+        .write_raw(
+            &path,
+            &KeyShare { key, nonce },
+            Method::PUT,
+            &Default::default(),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completed_rekey_returns_new_keys() {
+        let status: RekeyStatus = serde_json::from_value(serde_json::json!({
+            "complete": true,
+            "keys": ["3a4f", "9c1e"],
+            "keys_base64": ["Ok8=", "nB4="],
+            "pgp_fingerprints": null,
+            "backup": false,
+            "nonce": "2dbd10f1-8528-6246-09e7-82b25b8aba63",
+            "verification_required": true,
+            "verification_nonce": "8b112c9e-2738-929d-bcc2-19aff249ff10"
+        }))
+        .unwrap();
+        assert!(status.complete);
+        assert_eq!(status.keys.len(), 2);
+        assert!(status.pgp_fingerprints.is_empty());
+        assert_eq!(
+            status.stage(),
+            RekeyStage::AwaitingVerification {
+                nonce: "8b112c9e-2738-929d-bcc2-19aff249ff10".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn rekey_stage_follows_progress() {
+        assert_eq!(RekeyStatus::default().stage(), RekeyStage::NotStarted);
+        let status = RekeyStatus {
+            started: true,
+            nonce: "nonce".to_string(),
+            progress: 1,
+            required: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            status.stage(),
+            RekeyStage::CollectingKeys {
+                nonce: "nonce".to_string(),
+                progress: 1,
+                required: 3
+            }
+        );
+        let status = RekeyStatus {
+            complete: true,
+            ..status
+        };
+        assert_eq!(status.stage(), RekeyStage::Complete);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_start_and_cancel_rekey() {
+        let client = crate::tests::vault_client();
+        let init = RekeyInit {
+            secret_shares: 3,
+            secret_threshold: 2,
+            ..Default::default()
+        };
+        let status = Rekey::start_rekey(&client, &init).await.unwrap();
+        assert!(matches!(
+            status.stage(),
+            RekeyStage::CollectingKeys { progress: 0, .. }
+        ));
+        assert_eq!(
+            Rekey::rekey_status(&client).await.unwrap().nonce,
+            status.nonce
+        );
+
+        let _ = Rekey::cancel_rekey(&client).await.unwrap();
+        assert_eq!(
+            Rekey::rekey_status(&client).await.unwrap().stage(),
+            RekeyStage::NotStarted
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shamir_seals_have_no_recovery_keys() {
+        // The dev server uses a Shamir seal, so recovery keys cannot be rekeyed
+        let client = crate::tests::vault_client();
+        let init = RekeyInit {
+            secret_shares: 1,
+            secret_threshold: 1,
+            ..Default::default()
+        };
+        assert!(RecoveryRekey::start_recovery_rekey(&client, &init)
+            .await
+            .is_err());
+        let _ = RecoveryRekey::cancel_recovery_rekey(&client).await;
+    }
+}
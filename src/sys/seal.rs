@@ -0,0 +1,216 @@
+//! Implements the [`/sys/seal-status`](https://www.vaultproject.io/api/system/seal-status.html),
+//! [`/sys/seal`](https://www.vaultproject.io/api/system/seal.html)
+//! and [`/sys/unseal`](https://www.vaultproject.io/api/system/unseal.html) endpoints
+use crate::{Error, Method, RequestOptions, Response};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Seal status of Vault, and progress of the unsealing
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct SealStatus {
+    /// Type of the seal, like `shamir` or `awskms`
+    pub r#type: String,
+    /// Whether Vault has been initialized
+    pub initialized: bool,
+    /// Whether Vault is sealed
+    pub sealed: bool,
+    /// Number of key shares required to unseal
+    pub t: u64,
+    /// Number of key shares
+    pub n: u64,
+    /// Number of key shares provided so far
+    pub progress: u64,
+    /// Nonce of the unsealing in progress
+    #[serde(default)]
+    pub nonce: String,
+    /// Version of Vault
+    #[serde(default)]
+    pub version: String,
+    /// Whether a seal migration is in progress. Key shares must then be provided with
+    /// `migrate` set.
+    #[serde(default)]
+    pub migration: bool,
+    /// Whether the key shares are recovery keys, because Vault is unsealed automatically
+    #[serde(default)]
+    pub recovery_seal: bool,
+    /// Type of the storage backend
+    #[serde(default)]
+    pub storage_type: Option<String>,
+    /// Name of the cluster, once unsealed
+    #[serde(default)]
+    pub cluster_name: Option<String>,
+    /// ID of the cluster, once unsealed
+    #[serde(default)]
+    pub cluster_id: Option<String>,
+}
+
+impl SealStatus {
+    /// Number of key shares still required to unseal, or 0 if Vault is unsealed
+    pub fn remaining(&self) -> u64 {
+        if self.sealed {
+            self.t.saturating_sub(self.progress)
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Unseal<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'a str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    reset: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    migrate: bool,
+}
+
+fn unauthenticated() -> RequestOptions {
+    RequestOptions {
+        unauthenticated: true,
+        ..Default::default()
+    }
+}
+
+/// Implements the [`/sys/seal-status`](https://www.vaultproject.io/api/system/seal-status.html)
+/// and [`/sys/unseal`](https://www.vaultproject.io/api/system/unseal.html) endpoints
+#[async_trait]
+pub trait Seal {
+    /// Read the seal status. This endpoint is unauthenticated.
+    async fn seal_status(&self) -> Result<SealStatus, Error>;
+
+    /// Provide a single unseal key share, or recovery key share for auto-unsealed Vaults.
+    ///
+    /// During a seal migration, `migrate` must be set, to confirm that the key shares are
+    /// provided for the migration. This endpoint is unauthenticated.
+    async fn unseal(&self, key: &str, migrate: bool) -> Result<SealStatus, Error>;
+
+    /// Discard the key shares provided so far, to start unsealing again. This endpoint is
+    /// unauthenticated.
+    async fn reset_unseal(&self) -> Result<SealStatus, Error>;
+
+    /// Provide key shares until Vault is unsealed, returning the final status. Fails if Vault
+    /// is still sealed once every key share has been provided.
+    async fn unseal_with_keys<S: AsRef<str> + Sync>(
+        &self,
+        keys: &[S],
+        migrate: bool,
+    ) -> Result<SealStatus, Error> {
+        let mut status = self.seal_status().await?;
+        for key in keys {
+            if !status.sealed {
+                break;
+            }
+            status = self.unseal(key.as_ref(), migrate).await?;
+        }
+        if status.sealed {
+            return Err(Error::InvalidRequest(format!(
+                "Vault is still sealed: {} more key shares are required",
+                status.remaining()
+            )));
+        }
+        Ok(status)
+    }
+
+    /// Seal Vault. The token needs `sudo` on `sys/seal`.
+    async fn seal(&self) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl<T> Seal for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn seal_status(&self) -> Result<SealStatus, Error> {
+        // This endpoint returns the status as-is, without the usual response wrapping
+        self.read_with_options("sys/seal-status", Method::GET, &unauthenticated())
+            .await
+    }
+
+    async fn unseal(&self, key: &str, migrate: bool) -> Result<SealStatus, Error> {
+        self.write_raw(
+            "sys/unseal",
+            &Unseal {
+                key: Some(key),
+                reset: false,
+                migrate,
+            },
+            Method::PUT,
+            &unauthenticated(),
+        )
+        .await
+    }
+
+    async fn reset_unseal(&self) -> Result<SealStatus, Error> {
+        self.write_raw(
+            "sys/unseal",
+            &Unseal {
+                key: None,
+                reset: true,
+                migrate: false,
+            },
+            Method::PUT,
+            &unauthenticated(),
+        )
+        .await
+    }
+
+    async fn seal(&self) -> Result<Response, Error> {
+        self.put("sys/seal", &crate::Empty, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_status_is_parsed() {
+        let status: SealStatus = serde_json::from_value(serde_json::json!({
+            "type": "shamir",
+            "initialized": true,
+            "sealed": true,
+            "t": 3,
+            "n": 5,
+            "progress": 1,
+            "nonce": "f8f7b6a0-5b2a-4f3c-8f7e-9a1d0f7c6e4b",
+            "version": "1.15.0",
+            "migration": true,
+            "recovery_seal": false,
+            "storage_type": "raft"
+        }))
+        .unwrap();
+        assert!(status.migration);
+        assert_eq!(status.t, 3);
+        assert_eq!(status.storage_type.as_deref(), Some("raft"));
+        assert!(status.cluster_name.is_none());
+
+        assert_eq!(status.remaining(), 2);
+
+        let payload = serde_json::to_value(Unseal {
+            key: Some("key"),
+            reset: false,
+            migrate: false,
+        })
+        .unwrap();
+        assert_eq!(payload, serde_json::json!({"key": "key"}));
+        let payload = serde_json::to_value(Unseal {
+            key: None,
+            reset: true,
+            migrate: false,
+        })
+        .unwrap();
+        assert_eq!(payload, serde_json::json!({"reset": true}));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_seal_status() {
+        let client = crate::tests::vault_client();
+        let status = Seal::seal_status(&client).await.unwrap();
+        assert!(status.initialized);
+        assert!(!status.sealed);
+        assert!(!status.migration);
+        assert_eq!(status.remaining(), 0);
+    }
+}
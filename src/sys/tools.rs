@@ -0,0 +1,171 @@
+//! Implements the [`/sys/tools`](https://www.vaultproject.io/api/system/tools.html) endpoints
+use crate::crypto::HashAlgorithm;
+use crate::{Error, Method};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Source of the entropy of random bytes generated by Vault
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RandomSource {
+    /// Entropy of the platform Vault runs on
+    #[default]
+    Platform,
+    /// Entropy of the seal, like an HSM. Requires Vault 1.12 or later.
+    Seal,
+    /// Entropy of both the platform and the seal, mixed together. Requires Vault 1.12 or later.
+    All,
+}
+
+impl RandomSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            RandomSource::Platform => "platform",
+            RandomSource::Seal => "seal",
+            RandomSource::All => "all",
+        }
+    }
+}
+
+/// Encoding of the random bytes and digests returned by Vault
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RandomFormat {
+    /// Base64 encoded
+    #[default]
+    Base64,
+    /// Hexadecimal encoded
+    Hex,
+}
+
+impl RandomFormat {
+    /// Decode bytes encoded in this format
+    pub fn decode(self, encoded: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            RandomFormat::Base64 => crate::crypto::base64().decode(encoded),
+            RandomFormat::Hex => crate::utils::decode_hex(encoded),
+        }
+    }
+}
+
+/// Implements the [`/sys/tools`](https://www.vaultproject.io/api/system/tools.html) endpoints
+#[async_trait]
+pub trait Tools {
+    /// Generate `bytes` random bytes from `source`, returned in `format`
+    async fn random(
+        &self,
+        bytes: u32,
+        source: RandomSource,
+        format: RandomFormat,
+    ) -> Result<String, Error>;
+
+    /// Generate `bytes` random bytes from `source`
+    async fn random_bytes(&self, bytes: u32, source: RandomSource) -> Result<Vec<u8>, Error> {
+        let format = RandomFormat::Base64;
+        format.decode(&self.random(bytes, source, format).await?)
+    }
+
+    /// Hash the input with the algorithm, returning the digest
+    async fn hash(&self, input: &[u8], algorithm: HashAlgorithm) -> Result<Vec<u8>, Error>;
+}
+
+#[async_trait]
+impl<T> Tools for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn random(
+        &self,
+        bytes: u32,
+        source: RandomSource,
+        format: RandomFormat,
+    ) -> Result<String, Error> {
+        #[derive(Serialize)]
+        struct Request {
+            bytes: u32,
+            format: RandomFormat,
+        }
+
+        #[derive(Deserialize)]
+        struct Random {
+            random_bytes: String,
+        }
+
+        let path = match source {
+            RandomSource::Platform => "sys/tools/random".to_string(),
+            source => format!("sys/tools/random/{}", source.as_str()),
+        };
+        let request = Request { bytes, format };
+        let random: Random = self
+            .write(&path, &request, Method::POST, true)
+            .await?
+            .data()?;
+        Ok(random.random_bytes)
+    }
+
+    async fn hash(&self, input: &[u8], algorithm: HashAlgorithm) -> Result<Vec<u8>, Error> {
+        #[derive(Serialize)]
+        struct Request {
+            input: String,
+            format: RandomFormat,
+        }
+
+        #[derive(Deserialize)]
+        struct Hash {
+            sum: String,
+        }
+
+        let path = format!("sys/tools/hash/{}", algorithm.as_str());
+        let format = RandomFormat::Base64;
+        let request = Request {
+            input: crate::crypto::base64().encode(input),
+            format,
+        };
+        let hash: Hash = self
+            .write(&path, &request, Method::POST, true)
+            .await?
+            .data()?;
+        format.decode(&hash.sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_generate_random_bytes() {
+        let client = crate::tests::vault_client();
+
+        let bytes = Tools::random_bytes(&client, 32, RandomSource::Platform)
+            .await
+            .unwrap();
+        assert_eq!(bytes.len(), 32);
+
+        let hex = Tools::random(&client, 16, RandomSource::Platform, RandomFormat::Hex)
+            .await
+            .unwrap();
+        assert_eq!(RandomFormat::Hex.decode(&hex).unwrap().len(), 16);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_hash_bytes() {
+        let client = crate::tests::vault_client();
+
+        let digest = Tools::hash(&client, b"", HashAlgorithm::Sha2_256)
+            .await
+            .unwrap();
+        assert_eq!(
+            digest,
+            crate::utils::decode_hex(
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            )
+            .unwrap()
+        );
+        let digest = Tools::hash(&client, &[0, 255], HashAlgorithm::Sha2_512)
+            .await
+            .unwrap();
+        assert_eq!(digest.len(), 64);
+    }
+}
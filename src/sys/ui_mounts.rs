@@ -0,0 +1,111 @@
+//! Implements the
+//! [`/sys/internal/ui/mounts`](https://www.vaultproject.io/api-docs/system/internal-ui-mounts)
+//! endpoints, which list the mounts visible to the token making the request
+//!
+//! Without a token, or with a token which cannot access any path of a mount, only mounts tuned
+//! with `listing_visibility` set to `unauth` are listed, without their accessor.
+use crate::Error;
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Secrets engine or auth method visible to the token
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VisibleMount {
+    /// Type of the secrets engine or auth method, like `kv` or `approle`
+    pub r#type: String,
+    /// Human-friendly description of the mount
+    #[serde(default)]
+    pub description: String,
+    /// Accessor of the mount. Only returned to tokens which can access the mount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessor: Option<String>,
+    /// Options of the mount, like the `version` of Key/Value secrets engines
+    #[serde(default, deserialize_with = "crate::utils::deserialize_null_default")]
+    pub options: HashMap<String, String>,
+    /// Whether the mount is local to the cluster, and not replicated
+    #[serde(default)]
+    pub local: bool,
+    /// Whether values of the mount are wrapped with the seal
+    #[serde(default)]
+    pub seal_wrap: bool,
+    /// Path of the mount, without a trailing `/`. Only returned when reading a single mount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Secrets engines and auth methods visible to the token, keyed by their path without a
+/// trailing `/`
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone)]
+pub struct VisibleMounts {
+    /// Secrets engines
+    #[serde(default)]
+    pub secret: HashMap<String, VisibleMount>,
+    /// Auth methods, without the `auth/` prefix
+    #[serde(default)]
+    pub auth: HashMap<String, VisibleMount>,
+}
+
+/// Implements the
+/// [`/sys/internal/ui/mounts`](https://www.vaultproject.io/api-docs/system/internal-ui-mounts)
+/// endpoints
+#[async_trait]
+pub trait UiMounts {
+    /// List the secrets engines and auth methods visible to the token
+    async fn visible_mounts(&self) -> Result<VisibleMounts, Error>;
+
+    /// Read the secrets engine mounted at `path`, or containing `path`. Fails if the token
+    /// cannot access the mount.
+    async fn visible_mount(&self, path: &str) -> Result<VisibleMount, Error>;
+}
+
+#[async_trait]
+impl<T> UiMounts for T
+where
+    T: crate::Vault + Send + Sync,
+{
+    async fn visible_mounts(&self) -> Result<VisibleMounts, Error> {
+        let mounts: VisibleMounts = self.get("sys/internal/ui/mounts").await?.data()?;
+        Ok(VisibleMounts {
+            secret: trim_paths(mounts.secret),
+            auth: trim_paths(mounts.auth),
+        })
+    }
+
+    async fn visible_mount(&self, path: &str) -> Result<VisibleMount, Error> {
+        let path = format!("sys/internal/ui/mounts/{}", path);
+        let mut mount: VisibleMount = self.get(&path).await?.data()?;
+        mount.path = mount
+            .path
+            .map(|path| path.trim_end_matches('/').to_string());
+        Ok(mount)
+    }
+}
+
+fn trim_paths(mounts: HashMap<String, VisibleMount>) -> HashMap<String, VisibleMount> {
+    mounts
+        .into_iter()
+        .map(|(path, mount)| (path.trim_end_matches('/').to_string(), mount))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_list_visible_mounts() {
+        let client = crate::tests::vault_client();
+
+        let mounts = UiMounts::visible_mounts(&client).await.unwrap();
+        assert_eq!(mounts.secret["cubbyhole"].r#type, "cubbyhole");
+        assert_eq!(mounts.auth["token"].r#type, "token");
+
+        let mount = UiMounts::visible_mount(&client, "cubbyhole/secret")
+            .await
+            .unwrap();
+        assert_eq!(mount.path.as_deref(), Some("cubbyhole"));
+    }
+}
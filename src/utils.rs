@@ -1,3 +1,4 @@
+use serde::de::{Deserialize, Deserializer};
 use serde::ser::Serializer;
 
 pub(crate) fn serialize_bytes<S, T>(bytes: T, serializer: S) -> Result<S::Ok, S::Error>
@@ -5,7 +6,7 @@ where
     S: Serializer,
     T: AsRef<[u8]>,
 {
-    serializer.serialize_str(&base64::encode(&bytes))
+    serializer.serialize_str(&crate::crypto::base64().encode(bytes.as_ref()))
 }
 
 pub(crate) fn serialize_option_bytes<S, T>(
@@ -18,6 +19,141 @@ where
 {
     match bytes {
         None => serializer.serialize_none(),
-        Some(ref bytes) => serializer.serialize_str(&base64::encode(&bytes)),
+        Some(ref bytes) => {
+            serializer.serialize_str(&crate::crypto::base64().encode(bytes.as_ref()))
+        }
     }
 }
+
+/// Serialize a list of strings as a comma separated string
+pub(crate) fn serialize_option_comma_separated<S, T>(
+    values: &Option<Vec<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: AsRef<str>,
+{
+    match values {
+        None => serializer.serialize_none(),
+        Some(values) => {
+            let values: Vec<&str> = values.iter().map(AsRef::as_ref).collect();
+            serializer.serialize_str(&values.join(","))
+        }
+    }
+}
+
+/// Deserialize a `null` value into the default value of the type
+pub(crate) fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Split a DER element into its tag, its contents and the remaining input
+#[cfg(feature = "ring")]
+pub(crate) fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, input) = input.split_first()?;
+    let (length, input) = if first & 0x80 == 0 {
+        (usize::from(first), input)
+    } else {
+        let octets = usize::from(first & 0x7f);
+        if octets == 0 || octets > 4 || input.len() < octets {
+            return None;
+        }
+        let length = input[..octets]
+            .iter()
+            .fold(0, |length, &octet| (length << 8) | usize::from(octet));
+        (length, &input[octets..])
+    };
+    if input.len() < length {
+        return None;
+    }
+    Some((tag, &input[..length], &input[length..]))
+}
+
+/// Decode a hexadecimal string, as returned by Vault for some binary values
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>, crate::Error> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(crate::Error::InvalidVaultResponse(format!(
+            "Invalid hexadecimal string {}",
+            hex
+        )));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| {
+                crate::Error::InvalidVaultResponse(format!("Invalid hexadecimal string {}", hex))
+            })
+        })
+        .collect()
+}
+
+/// Parse a timestamp in the RFC 3339 format used by Vault, like `2023-09-12T15:19:49.39-07:00`
+pub(crate) fn parse_rfc3339(timestamp: &str) -> Option<std::time::SystemTime> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let number = |s: &str| -> Option<i64> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    };
+
+    let (date, time) = timestamp.split_at(timestamp.find(['T', 't'])?);
+    let time = &time[1..];
+    let mut date = date.splitn(3, '-');
+    let (year, month, day) = (
+        number(date.next()?)?,
+        number(date.next()?)?,
+        number(date.next()?)?,
+    );
+
+    // Split the offset from the time of the day
+    let (time, offset) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let index = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(index);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let mut offset = offset[1..].splitn(2, ':');
+        let (hours, minutes) = (number(offset.next()?)?, number(offset.next()?)?);
+        (time, sign * (hours * 3600 + minutes * 60))
+    };
+    let (time, nanos) = match time.find('.') {
+        Some(index) => {
+            let fraction = &time[index + 1..];
+            if fraction.len() > 9 {
+                return None;
+            }
+            let nanos = number(fraction)? * 10i64.pow(9 - fraction.len() as u32);
+            (&time[..index], nanos)
+        }
+        None => (time, 0),
+    };
+    let mut time = time.splitn(3, ':');
+    let (hour, minute, second) = (
+        number(time.next()?)?,
+        number(time.next()?)?,
+        number(time.next()?)?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    // Days since the Unix epoch of the civil date, from Howard Hinnant's algorithm
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    let seconds = <u64 as std::convert::TryFrom<_>>::try_from(seconds).ok()?;
+    UNIX_EPOCH.checked_add(Duration::new(seconds, nanos as u32))
+}
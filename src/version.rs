@@ -0,0 +1,168 @@
+//! Version of the Vault server
+//!
+//! Features introduced in recent versions of Vault fail with opaque errors, like a 404, on older
+//! servers. [`Client::require_version`] checks the version of the server first, so that these
+//! features fail with [`Error::UnsupportedByServer`] instead.
+use crate::sys::Seal;
+use crate::{Client, Error};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Version of Vault, like `1.15.2+ent`
+///
+/// Versions are not ordered, as pre-releases and build metadata have no meaningful order
+/// between Vault releases. Compare them with [`ServerVersion::at_least`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ServerVersion {
+    /// Major version
+    pub major: u64,
+    /// Minor version
+    pub minor: u64,
+    /// Patch version
+    pub patch: u64,
+    /// Pre-release, like `rc1`, if any. Ignored when comparing with the `at_least` helper.
+    pub pre: Option<String>,
+    /// Build metadata, like `ent` or `ent.hsm`, if any
+    pub build: Option<String>,
+}
+
+impl ServerVersion {
+    /// Version with no pre-release and no build metadata
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+            build: None,
+        }
+    }
+
+    /// Returns whether the version is at least `major.minor.patch`
+    pub fn at_least(&self, minimum: &ServerVersion) -> bool {
+        (self.major, self.minor, self.patch) >= (minimum.major, minimum.minor, minimum.patch)
+    }
+
+    /// Returns whether the server is Vault Enterprise
+    pub fn is_enterprise(&self) -> bool {
+        self.build
+            .as_deref()
+            .is_some_and(|build| build.split('.').any(|part| part == "ent"))
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = Error;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidVaultResponse(format!("Invalid Vault version {}", version));
+        let trimmed = version.trim().trim_start_matches('v');
+        let (rest, build) = match trimmed.split_once('+') {
+            Some((rest, build)) => (rest, Some(build.to_string())),
+            None => (trimmed, None),
+        };
+        let (numbers, pre) = match rest.split_once('-') {
+            Some((numbers, pre)) => (numbers, Some(pre.to_string())),
+            None => (rest, None),
+        };
+        let mut numbers = numbers.split('.').map(u64::from_str);
+        let mut next = || numbers.next().ok_or_else(invalid)?.map_err(|_| invalid());
+        let (major, minor) = (next()?, next()?);
+        let patch = next().unwrap_or(0);
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+impl Client {
+    /// Returns the version of the Vault server, read from the unauthenticated seal status
+    /// endpoint. The version is cached until the address of the client changes.
+    pub async fn server_version(&self) -> Result<ServerVersion, Error> {
+        if let Some(version) = self.cached_server_version() {
+            return Ok(version);
+        }
+        let version: ServerVersion = Seal::seal_status(self).await?.version.parse()?;
+        *self.server_version.lock().expect("Lock not to be poisoned") = Some(version.clone());
+        Ok(version)
+    }
+
+    /// Fail with [`Error::UnsupportedByServer`] if the Vault server is older than `minimum`,
+    /// which `feature` requires
+    pub async fn require_version(
+        &self,
+        feature: &str,
+        minimum: &ServerVersion,
+    ) -> Result<(), Error> {
+        let version = self.server_version().await?;
+        if version.at_least(minimum) {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedByServer {
+                feature: feature.to_string(),
+                required: minimum.to_string(),
+                actual: version.to_string(),
+            })
+        }
+    }
+
+    fn cached_server_version(&self) -> Option<ServerVersion> {
+        self.server_version
+            .lock()
+            .expect("Lock not to be poisoned")
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_parsed_and_compared() {
+        let version: ServerVersion = "1.15.2-rc1+ent.hsm".parse().unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 15, 2));
+        assert_eq!(version.pre.as_deref(), Some("rc1"));
+        assert!(version.is_enterprise());
+        assert_eq!(version.to_string(), "1.15.2-rc1+ent.hsm");
+
+        let version: ServerVersion = "1.3.2".parse().unwrap();
+        assert!(!version.is_enterprise());
+        assert!(version.at_least(&ServerVersion::new(1, 3, 0)));
+        assert!(!version.at_least(&ServerVersion::new(1, 12, 0)));
+        assert!("unknown".parse::<ServerVersion>().is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_read_server_version() {
+        let client = crate::tests::vault_client();
+        let version = client.server_version().await.unwrap();
+        assert!(version.at_least(&ServerVersion::new(1, 0, 0)));
+
+        match client
+            .require_version("the future", &ServerVersion::new(99, 0, 0))
+            .await
+        {
+            Err(Error::UnsupportedByServer { required, .. }) => assert_eq!(required, "99.0.0"),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+}